@@ -0,0 +1,631 @@
+//! This module provides static analysis of script commands, shared by `run` diagnostics and `validate`.
+
+use std::path::PathBuf;
+use regex::Regex;
+use clap::Subcommand;
+use crate::commands::Commands;
+use crate::commands::explain::command_programs;
+use crate::commands::script::{check_requirements, effective_interpreter, find_in_path, flatten_chain, platform_commands, resolve_command, script_command, script_command_file, script_toolchain, script_umask, Script, Scripts};
+use crate::commands::umask::parse_umask;
+use crate::error::{closest_match, CargoScriptError};
+use colored::*;
+use emoji::symbols;
+
+/// Annotation that disables the word-splitting check for a command.
+pub const ALLOW_UNQUOTED_ANNOTATION: &str = "cargo-script: allow-unquoted";
+
+/// Find variable expansions that are not protected by double quotes.
+///
+/// Unquoted `$VAR`/`${VAR}` expansions undergo word splitting and globbing in POSIX shells, so a
+/// value containing spaces turns into several arguments (the classic `rm $FILE` bug). Expansions
+/// inside double quotes are safe and text inside single quotes is not expanded at all. Expansions
+/// forming the value of a plain assignment (`NAME=$VAR`) are not split and are not reported.
+/// Commands containing the `cargo-script: allow-unquoted` annotation (e.g. in a trailing
+/// comment) are skipped entirely.
+///
+/// # Arguments
+///
+/// * `command` - The command string to analyze.
+///
+/// # Returns
+///
+/// The names of the unquoted variables, once each, in order of appearance.
+pub fn unquoted_expansions(command: &str) -> Vec<String> {
+    if command.contains(ALLOW_UNQUOTED_ANNOTATION) {
+        return Vec::new();
+    }
+
+    let chars: Vec<char> = command.chars().collect();
+    let mut names: Vec<String> = Vec::new();
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut word_start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if in_single {
+            if c == '\'' {
+                in_single = false;
+            }
+            i += 1;
+            continue;
+        }
+        match c {
+            '\\' => {
+                i += 2;
+                continue;
+            }
+            '\'' if !in_double => in_single = true,
+            '"' => in_double = !in_double,
+            '#' if !in_double && (i == word_start) => {
+                // A comment runs until the end of the line.
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            c if !in_double && (c.is_whitespace() || matches!(c, ';' | '|' | '&' | '(' | ')')) => {
+                word_start = i + 1;
+            }
+            '$' if !in_double => {
+                let braced = chars.get(i + 1) == Some(&'{');
+                let start = if braced { i + 2 } else { i + 1 };
+                let mut end = start;
+                while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_') {
+                    end += 1;
+                }
+                let is_name = end > start && (chars[start].is_ascii_alphabetic() || chars[start] == '_');
+                let word_prefix: String = chars[word_start..i].iter().collect();
+                let is_assignment = is_assignment_prefix(&word_prefix);
+                if is_name && !is_assignment {
+                    let name: String = chars[start..end].iter().collect();
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+                i = end.max(i + 1);
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    names
+}
+
+/// Check whether the text before an expansion makes it the value of a `NAME=` assignment.
+fn is_assignment_prefix(prefix: &str) -> bool {
+    match prefix.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => false,
+    }
+}
+
+/// Check whether an interpreter is a POSIX-style shell where word splitting applies.
+///
+/// `zsh` is not listed since it does not split unquoted parameter expansions by default.
+pub fn is_posix_shell(interpreter: &str) -> bool {
+    matches!(interpreter, "sh" | "bash" | "dash" | "ksh")
+}
+
+/// Warn about unquoted variable expansions in a script chain.
+///
+/// Only commands run by POSIX-style shells are checked. Warnings are printed to stderr.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script whose chain is checked.
+/// * `strict` - Whether any finding should fail the check.
+///
+/// # Errors
+///
+/// This function will return an error if a script in the chain does not exist, or if `strict`
+/// is set and any unquoted expansion was found.
+pub fn shell_escape_check(scripts: &Scripts, script_name: &str, strict: bool) -> Result<(), CargoScriptError> {
+    let mut findings = 0;
+    for name in flatten_chain(scripts, script_name)? {
+        let script = &scripts.scripts[&name];
+        let Some(command) = script_command(script) else { continue };
+        if !is_posix_shell(effective_interpreter(scripts, script)) {
+            continue;
+        }
+        for var in unquoted_expansions(command) {
+            findings += 1;
+            eprintln!(
+                "{}  {}: [ {} ] expands ${} without quotes; values with spaces will be split. Use \"${}\" or add `# {}`.",
+                symbols::warning::WARNING.glyph,
+                "Shell escape check".yellow(),
+                name,
+                var,
+                var,
+                ALLOW_UNQUOTED_ANNOTATION
+            );
+        }
+    }
+
+    if strict && findings > 0 {
+        return Err(CargoScriptError::ValidationFailed {
+            message: format!("{} unquoted variable expansion(s) found by the shell escape check", findings),
+        });
+    }
+    Ok(())
+}
+
+/// Check whether a command re-invokes cargo-script to run the script it belongs to, as in
+/// `cargo script run deploy` inside `deploy`, which would start itself over and over.
+///
+/// `cargo script`, `cargo-script`, and `cgs` are recognized, with flags between `run` and the
+/// script name.
+///
+/// # Arguments
+///
+/// * `command` - The command string to check.
+/// * `script_name` - The name of the script the command belongs to.
+pub fn is_self_invocation(command: &str, script_name: &str) -> bool {
+    let pattern = format!(
+        r"(?:^|[\s;&|(`])(?:cargo\s+script|cargo-script|cgs)\s+run\s+(?:-\S+\s+)*['\x22]?{}['\x22]?(?:$|[\s;&|)`])",
+        regex::escape(script_name)
+    );
+    Regex::new(&pattern).is_ok_and(|pattern| pattern.is_match(command))
+}
+
+/// Shell builtins and keywords that never need to be found on `PATH`.
+const SHELL_BUILTINS: &[&str] = &[
+    ".", ":", "[", "[[", "alias", "break", "case", "cd", "continue", "declare", "do", "done", "echo", "elif",
+    "else", "esac", "eval", "exec", "exit", "export", "false", "fi", "for", "function", "if", "local", "popd",
+    "printf", "pushd", "pwd", "read", "return", "set", "shift", "source", "test", "then", "trap", "true",
+    "type", "ulimit", "umask", "unset", "until", "wait", "while",
+    // cmd and PowerShell
+    "call", "copy", "del", "dir", "goto", "md", "mkdir", "rd", "rem", "ren", "setlocal", "endlocal",
+];
+
+/// Return the programs of a command that cannot be found on `PATH`.
+///
+/// Shell builtins, words containing a variable expansion, and quoted words are never reported.
+///
+/// # Arguments
+///
+/// * `command` - The command string to check.
+pub fn missing_programs(command: &str) -> Vec<String> {
+    command_programs(command)
+        .into_iter()
+        .filter(|program| {
+            let lower = program.to_lowercase();
+            !SHELL_BUILTINS.contains(&lower.as_str())
+                && !program.contains(['$', '%', '"', '\'', '`'])
+                && !program.starts_with('-')
+                && find_in_path(program).is_none()
+        })
+        .collect()
+}
+
+/// Check that the programs used by a script chain exist, for `run --dry-run --verify-commands`.
+///
+/// For shell commands, the first word of each line is looked up on `PATH`; for other
+/// interpreters (e.g. `python`), the interpreter itself is. Scripts pinned to a toolchain are
+/// skipped, as `requires` covers them. Warnings are printed to stderr.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script whose chain is checked.
+/// * `strict` - Whether missing programs are an error.
+///
+/// # Errors
+///
+/// This function will return an error if a script in the chain does not exist, or if `strict`
+/// is set and any program is missing.
+pub fn verify_commands(scripts: &Scripts, script_name: &str, strict: bool) -> Result<(), CargoScriptError> {
+    let mut missing = 0;
+    for name in flatten_chain(scripts, script_name)? {
+        let script = &scripts.scripts[&name];
+        let Some(command) = script_command(script) else { continue };
+        if script_toolchain(scripts, script).is_some() {
+            continue;
+        }
+        let interpreter = effective_interpreter(scripts, script);
+        let programs = if is_posix_shell(interpreter) || matches!(interpreter, "zsh" | "cmd" | "powershell" | "pwsh") {
+            missing_programs(command)
+        } else if find_in_path(interpreter).is_none() {
+            vec![interpreter.to_string()]
+        } else {
+            Vec::new()
+        };
+        for program in programs {
+            missing += 1;
+            eprintln!(
+                "{}  {}: [ {} ] runs `{}`, which was not found on PATH.",
+                symbols::warning::WARNING.glyph,
+                "Verify commands".yellow(),
+                name,
+                program
+            );
+        }
+    }
+
+    if strict && missing > 0 {
+        return Err(CargoScriptError::ValidationFailed {
+            message: format!("{} program(s) not found by --verify-commands", missing),
+        });
+    }
+    Ok(())
+}
+
+/// A problem found by `validate` in a single script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    pub script: String,
+    pub message: String,
+}
+
+/// The script name convention `validate --strict-names` enforces when `[settings] name_pattern`
+/// is not set.
+pub const DEFAULT_NAME_PATTERN: &str = "^[a-z][a-z0-9:_-]*$";
+
+/// Options selecting the optional checks run by `validate`.
+#[derive(Debug, Clone, Default)]
+pub struct ValidateOptions {
+    /// Check that shebangs and the `interpreter` field agree.
+    pub check_shebang: bool,
+    /// Check script names against the naming convention, even without `[settings] name_pattern`.
+    pub strict_names: bool,
+    /// Directory containing the scripts file, which `command_file` paths are resolved against.
+    pub scripts_dir: PathBuf,
+}
+
+/// Statically check every script, without running anything.
+///
+/// Include chains are always checked for missing scripts and cycles. Optional checks are
+/// enabled through `options`.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `options` - The optional checks to run.
+///
+/// # Returns
+///
+/// The problems found: those of the top-level settings first, then those of each script, sorted
+/// by script name.
+pub fn validate_scripts(scripts: &Scripts, options: &ValidateOptions) -> Vec<ValidationWarning> {
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+
+    let mut warnings = Vec::new();
+    if let Some(interpreter) = &scripts.default_interpreter {
+        if !matches!(interpreter.as_str(), "cmd" | "powershell") && find_in_path(interpreter).is_none() {
+            warnings.push(ValidationWarning {
+                script: "default_interpreter".to_string(),
+                message: format!("interpreter `{}` was not found on PATH", interpreter),
+            });
+        }
+    }
+    if let Some(toolchain) = &scripts.settings.default_toolchain {
+        if let Err(message) = check_requirements(&[], Some(toolchain), None) {
+            warnings.push(ValidationWarning { script: "default_toolchain".to_string(), message });
+        }
+    }
+    for name in names {
+        let script = &scripts.scripts[name];
+        let mut warn = |message: String| warnings.push(ValidationWarning { script: name.clone(), message });
+
+        if let Err(e) = flatten_chain(scripts, name) {
+            warn(e.to_string());
+        }
+        if let Some(command) = reserved_names().into_iter().find(|command| command == name) {
+            warn(format!("name collides with the `{}` command and cannot be run with the short form; use `cargo script run {}`", command, name));
+        }
+        if let Some(Err(e)) = script_umask(script).map(parse_umask) {
+            warn(e);
+        }
+        if let Some(message) = check_platforms(script) {
+            warn(message);
+        }
+        if script_command(script).is_some_and(|command| is_self_invocation(command, name)) {
+            warn(format!("command runs `cargo script run {}` again, which would never stop; use `include` to run other scripts", name));
+        }
+        if let Some(message) = check_no_op(script) {
+            warn(message);
+        }
+        if let Some(message) = check_interpreter(scripts, script) {
+            warn(message);
+        }
+        if script_command_file(script).is_some() {
+            if let Err(e) = resolve_command(name, script, &options.scripts_dir) {
+                warn(e.to_string());
+            }
+        }
+        if options.check_shebang {
+            if let Some(message) = check_shebang(scripts, script) {
+                warn(message);
+            }
+        }
+    }
+    warnings
+}
+
+/// Return the names and aliases of the cargo-script commands, which script names should not reuse.
+fn reserved_names() -> Vec<String> {
+    Commands::augment_subcommands(clap::Command::new("cargo-script"))
+        .get_subcommands()
+        .flat_map(|command| std::iter::once(command.get_name()).chain(command.get_all_aliases()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Check that every script only sets known fields, so a typo like `comand` is not silently
+/// ignored.
+///
+/// The accepted fields are read from the JSON Schema of a script, so they stay in sync with the
+/// fields it deserializes.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+///
+/// # Errors
+///
+/// This function will return an error for the first unknown field, in script name order, with
+/// the closest accepted field as a suggestion.
+pub fn check_unknown_fields(scripts: &Scripts) -> Result<(), CargoScriptError> {
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    for name in names {
+        let unknown = match &scripts.scripts[name] {
+            Script::Default(_) => continue,
+            Script::Inline { unknown, .. } | Script::CILike { unknown, .. } => unknown,
+        };
+        if let Some(field) = unknown.keys().next() {
+            let fields = script_fields();
+            return Err(CargoScriptError::UnknownField {
+                script: name.clone(),
+                field: field.clone(),
+                suggestion: closest_match(field, fields.iter().map(String::as_str)).map(str::to_string),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Return the names of the fields a script table accepts, from its JSON Schema.
+fn script_fields() -> Vec<String> {
+    let schema = schemars::schema_for!(Script);
+    let variants = schema.schema.subschemas.and_then(|subschemas| subschemas.any_of).unwrap_or_default();
+    let mut fields: Vec<String> = variants.into_iter()
+        .filter_map(|variant| variant.into_object().object)
+        .flat_map(|object| object.properties.into_keys())
+        .collect();
+    fields.sort();
+    fields.dedup();
+    fields
+}
+
+/// Compile the `[settings] name_pattern` of the scripts file, if it has one.
+///
+/// # Errors
+///
+/// This function will return an error if the pattern is not a valid regular expression.
+pub fn name_pattern(scripts: &Scripts) -> Result<Option<Regex>, CargoScriptError> {
+    scripts.settings.name_pattern.as_deref().map(|pattern| {
+        Regex::new(pattern).map_err(|e| CargoScriptError::InvalidNamePattern { pattern: pattern.to_string(), message: e.to_string() })
+    }).transpose()
+}
+
+/// Find the script names that do not match the naming convention.
+///
+/// The convention is `[settings] name_pattern`, or [`DEFAULT_NAME_PATTERN`] with
+/// `strict_names`. Without either, names are not checked.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `strict_names` - Whether to check names even without `[settings] name_pattern`.
+///
+/// # Returns
+///
+/// A problem for each offending name, sorted by name.
+///
+/// # Errors
+///
+/// This function will return an error if the pattern is not a valid regular expression.
+pub fn check_names(scripts: &Scripts, strict_names: bool) -> Result<Vec<ValidationWarning>, CargoScriptError> {
+    let pattern = match name_pattern(scripts)? {
+        Some(pattern) => pattern,
+        None if strict_names => Regex::new(DEFAULT_NAME_PATTERN).expect("Invalid name pattern"),
+        None => return Ok(Vec::new()),
+    };
+    let mut names: Vec<&String> = scripts.scripts.keys().filter(|name| !pattern.is_match(name)).collect();
+    names.sort();
+    Ok(names.into_iter().map(|name| ValidationWarning {
+        script: name.clone(),
+        message: format!("name does not match the pattern `{}`", pattern.as_str()),
+    }).collect())
+}
+
+/// Find the scripts whose `command` is empty or only whitespace, which fail when run.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+///
+/// # Returns
+///
+/// A problem for each offending script, sorted by name.
+pub fn check_empty_commands(scripts: &Scripts) -> Vec<ValidationWarning> {
+    let mut names: Vec<&String> = scripts.scripts.iter()
+        .filter(|(_, script)| match script {
+            Script::Default(command) => command.trim().is_empty(),
+            Script::Inline { command, .. } | Script::CILike { command, .. } => command.as_ref().is_some_and(|c| c.text.trim().is_empty()),
+        })
+        .map(|(name, _)| name)
+        .collect();
+    names.sort();
+    names.into_iter().map(|name| ValidationWarning {
+        script: name.clone(),
+        message: "command is empty".to_string(),
+    }).collect()
+}
+
+/// Check that a detailed script has something to run: a command, a command file, a platform
+/// variant, or includes.
+fn check_no_op(script: &Script) -> Option<String> {
+    let (command, include) = match script {
+        Script::Default(_) => return None,
+        Script::Inline { command, include, .. } | Script::CILike { command, include, .. } => (command, include),
+    };
+    let has_work = command.is_some()
+        || script_command_file(script).is_some()
+        || !platform_commands(script).is_empty()
+        || include.as_ref().is_some_and(|include| !include.is_empty());
+    (!has_work).then(|| "has neither a `command` nor an `include`, so running it does nothing".to_string())
+}
+
+/// Check that the `interpreter` named by a script can be found on `PATH`.
+///
+/// Scripts pinned to a toolchain run through `sh` and are skipped, as are `cmd` and
+/// `powershell`, which are resolved by Windows itself.
+fn check_interpreter(scripts: &Scripts, script: &Script) -> Option<String> {
+    let interpreter = match script {
+        Script::Inline { interpreter: Some(interpreter), .. } | Script::CILike { interpreter: Some(interpreter), .. } => interpreter,
+        _ => return None,
+    };
+    if script_toolchain(scripts, script).is_some() || matches!(interpreter.as_str(), "cmd" | "powershell") {
+        return None;
+    }
+    find_in_path(interpreter).is_none().then(|| format!("interpreter `{}` was not found on PATH", interpreter))
+}
+
+/// Check that a script with platform-specific variants can run on every OS: either all of
+/// `windows`, `macos`, and `linux` are set, or a base `command` covers the rest.
+fn check_platforms(script: &Script) -> Option<String> {
+    let platforms = platform_commands(script);
+    let has_base = matches!(script, Script::Inline { command: Some(_), .. } | Script::CILike { command: Some(_), .. })
+        || script_command_file(script).is_some();
+    if platforms.is_empty() || has_base {
+        return None;
+    }
+    let missing: Vec<&str> = ["windows", "macos", "linux"].into_iter()
+        .filter(|os| !platforms.iter().any(|(p, _)| p == os))
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    Some(format!("has no base `command` and no variant for {}, so it cannot run there", missing.join(", ")))
+}
+
+/// Check that a script's shebang agrees with its interpreter, and that commands that look like
+/// Python or Node code are not sent to the default shell.
+fn check_shebang(scripts: &Scripts, script: &Script) -> Option<String> {
+    let command = script_command(script)?;
+    if script_toolchain(scripts, script).is_some() {
+        return None;
+    }
+    let interpreter = match script {
+        Script::Default(_) => None,
+        Script::Inline { interpreter, .. } | Script::CILike { interpreter, .. } => interpreter.as_deref(),
+    }
+    .or(scripts.default_interpreter.as_deref());
+
+    if let Some(shebang) = command.trim_start().strip_prefix("#!") {
+        let shebang = shebang.lines().next().unwrap_or_default().trim();
+        let shebang_interpreter = shebang_interpreter(shebang)?;
+        return match interpreter {
+            Some(interpreter) if interpreter != shebang_interpreter => Some(format!(
+                "command starts with `#!{}` but interpreter is `{}`; the shebang is ignored",
+                shebang, interpreter
+            )),
+            _ => None,
+        };
+    }
+
+    if interpreter.is_none() {
+        let language = if command.lines().any(|l| l.starts_with("import ") || l.starts_with("def ") || (l.starts_with("from ") && l.contains(" import "))) {
+            Some("Python")
+        } else if command.contains("console.log(") || command.contains("require(") {
+            Some("Node")
+        } else {
+            None
+        };
+        if let Some(language) = language {
+            return Some(format!(
+                "command looks like {} code but has no interpreter, so it would run with `{}`",
+                language,
+                effective_interpreter(scripts, script)
+            ));
+        }
+    }
+    None
+}
+
+/// Return the interpreter named by a shebang line such as `/usr/bin/env python3` or `/bin/bash`.
+fn shebang_interpreter(shebang: &str) -> Option<&str> {
+    let mut parts = shebang.split_whitespace();
+    let program = parts.next()?;
+    let name = program.rsplit('/').next().unwrap_or(program);
+    if name == "env" {
+        parts.find(|arg| !arg.starts_with('-'))
+    } else {
+        Some(name)
+    }
+}
+
+/// Print the problems found in the scripts file.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `options` - The optional checks to run.
+/// * `strict` - Whether any problem should fail validation.
+///
+/// Names that break the naming convention and empty commands are errors, which fail validation
+/// even without `strict`.
+///
+/// # Errors
+///
+/// This function will return an error if a script name breaks the naming convention, if a
+/// command is empty, or if `strict` is set and any problem was found.
+pub fn validate(scripts: &Scripts, options: &ValidateOptions, strict: bool) -> Result<(), CargoScriptError> {
+    let warnings = validate_scripts(scripts, options);
+    let name_errors = check_names(scripts, options.strict_names)?;
+    let command_errors = check_empty_commands(scripts);
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    for name in names {
+        let platforms: Vec<&str> = platform_commands(&scripts.scripts[name]).into_iter().map(|(os, _)| os).collect();
+        if !platforms.is_empty() {
+            println!("{}  [ {} ] platform-specific commands: {}", symbols::other_symbol::CHECK_MARK.glyph, name.green(), platforms.join(", "));
+        }
+    }
+    if warnings.is_empty() && name_errors.is_empty() && command_errors.is_empty() {
+        println!("{}  {}", symbols::other_symbol::CHECK_MARK.glyph, "No problems found".green());
+        return Ok(());
+    }
+    for error in name_errors.iter().chain(&command_errors) {
+        println!("{}  [ {} ] {}", symbols::other_symbol::CROSS_MARK.glyph, error.script.red(), error.message);
+    }
+    for warning in &warnings {
+        println!("{}  [ {} ] {}", symbols::warning::WARNING.glyph, warning.script.yellow(), warning.message);
+    }
+    if !name_errors.is_empty() {
+        return Err(CargoScriptError::ValidationFailed {
+            message: format!("{} script name(s) break the naming convention", name_errors.len()),
+        });
+    }
+    if !command_errors.is_empty() {
+        return Err(CargoScriptError::ValidationFailed {
+            message: format!("{} script(s) have an empty command", command_errors.len()),
+        });
+    }
+    if strict {
+        return Err(CargoScriptError::ValidationFailed {
+            message: format!("{} problem(s) found", warnings.len()),
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+//! This module provides the functionality to collect files produced by a run into an artifacts directory.
+
+use std::{fs, path::Path};
+use colored::*;
+use emoji::symbols;
+use crate::error::CargoScriptError;
+
+/// Copy the files matching glob patterns into an artifacts directory, preserving relative paths.
+///
+/// Patterns are expanded after the run, relative to `base_dir`, so freshly generated files are
+/// found. Directories matched by a pattern are skipped; use a pattern such as `dist/**/*` to
+/// capture their contents. A pattern without matches only prints a warning unless `require` is set.
+///
+/// # Arguments
+///
+/// * `patterns` - The glob patterns to expand.
+/// * `base_dir` - The directory patterns are relative to.
+/// * `artifacts_dir` - The directory files are copied into.
+/// * `require` - Whether a pattern without matches is an error.
+///
+/// # Returns
+///
+/// The number of files captured.
+///
+/// # Errors
+///
+/// This function will return an error if a pattern is invalid, a file cannot be copied, or
+/// `require` is set and a pattern matched nothing.
+pub fn capture_artifacts(patterns: &[String], base_dir: &Path, artifacts_dir: &Path, require: bool) -> Result<usize, CargoScriptError> {
+    let artifact_error = |pattern: &str, message: String| CargoScriptError::ArtifactCaptureFailed { pattern: pattern.to_string(), message };
+    let mut captured = 0;
+
+    for pattern in patterns {
+        let full_pattern = base_dir.join(pattern).display().to_string();
+        let paths = glob::glob(&full_pattern).map_err(|e| artifact_error(pattern, e.to_string()))?;
+        let mut matched = 0;
+        for path in paths.filter_map(Result::ok).filter(|p| p.is_file()) {
+            let relative = path.strip_prefix(base_dir).unwrap_or(&path);
+            let target = artifacts_dir.join(relative);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| artifact_error(pattern, e.to_string()))?;
+            }
+            fs::copy(&path, &target).map_err(|e| artifact_error(pattern, e.to_string()))?;
+            matched += 1;
+        }
+
+        if matched == 0 {
+            if require {
+                return Err(artifact_error(pattern, "no files matched".to_string()));
+            }
+            eprintln!("{}  {}: no files matched [ {} ]", symbols::warning::WARNING.glyph, "Artifacts".yellow(), pattern);
+        }
+        captured += matched;
+    }
+
+    println!("{}: {} file(s) captured into {}", "Artifacts".green(), captured, artifacts_dir.display());
+    Ok(captured)
+}
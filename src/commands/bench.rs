@@ -0,0 +1,76 @@
+//! This module computes and prints the timing statistics of `bench`.
+
+use std::time::Duration;
+use colored::*;
+use emoji::symbols;
+
+/// Timing statistics of the measured runs of a script.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchStats {
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    /// The sample standard deviation, zero for a single run.
+    pub stddev: Duration,
+}
+
+impl BenchStats {
+    /// Compute the statistics of a set of durations, or `None` when there are none.
+    pub fn from_durations(durations: &[Duration]) -> Option<Self> {
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let count = sorted.len();
+        let secs: Vec<f64> = sorted.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / count as f64;
+        let median = if count % 2 == 0 { (secs[count / 2 - 1] + secs[count / 2]) / 2.0 } else { secs[count / 2] };
+        let variance = if count > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+        } else {
+            0.0
+        };
+        Some(BenchStats {
+            min,
+            max,
+            mean: Duration::from_secs_f64(mean),
+            median: Duration::from_secs_f64(median),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+/// Print the statistics of the measured runs of a script.
+///
+/// # Arguments
+///
+/// * `script_name` - The name of the benchmarked script.
+/// * `durations` - The duration of each measured run.
+/// * `warmup` - The number of warm-up runs left out of the statistics.
+/// * `failed` - The number of runs that failed with `--ignore-failures`.
+pub fn print_bench_stats(script_name: &str, durations: &[Duration], warmup: u32, failed: u32) {
+    let Some(stats) = BenchStats::from_durations(durations) else {
+        return;
+    };
+    let mut summary = format!("{} run(s)", durations.len());
+    if warmup > 0 {
+        summary.push_str(&format!(", {} warmup", warmup));
+    }
+    if failed > 0 {
+        summary.push_str(&format!(", {} failed", failed));
+    }
+    println!("\n{} [ {} ] ({})", "Benchmark".bold().yellow(), script_name, summary);
+    println!("{}", "-".repeat(40).yellow());
+    for (label, value) in [
+        ("min", stats.min),
+        ("max", stats.max),
+        ("mean", stats.mean),
+        ("median", stats.median),
+        ("stddev", stats.stddev),
+    ] {
+        println!("  {:<8}{:.2?}", label.green(), value);
+    }
+    if failed > 0 {
+        println!("{}  {} run(s) failed and are included in the statistics", symbols::warning::WARNING.glyph, failed);
+    }
+}
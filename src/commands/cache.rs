@@ -0,0 +1,101 @@
+//! This module skips scripts whose `inputs` have not changed since their last successful run.
+//!
+//! The hash of a script's inputs is kept in the `.cargo-script-cache` directory after each
+//! successful run, one file per script.
+
+use std::{collections::HashMap, fs, io, path::{Path, PathBuf}};
+use sha2::{Digest, Sha256};
+
+/// Directory, relative to the base directory of a run, holding the input hashes.
+pub const CACHE_DIR: &str = ".cargo-script-cache";
+
+/// Return the files matching glob patterns relative to a base directory, sorted and without duplicates.
+///
+/// # Errors
+///
+/// This function will return an error if a pattern is invalid.
+pub fn matching_files(base_dir: &Path, patterns: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for pattern in patterns {
+        let full_pattern = base_dir.join(pattern).display().to_string();
+        let paths = glob::glob(&full_pattern).map_err(|e| format!("invalid pattern `{}`: {}", pattern, e))?;
+        files.extend(paths.filter_map(Result::ok).filter(|path| path.is_file()));
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Hash everything a cached script's result depends on: the files matching its `inputs`, its
+/// command and extra arguments, and its configured variables.
+///
+/// # Arguments
+///
+/// * `base_dir` - The directory the input patterns are relative to.
+/// * `inputs` - The glob patterns of the input files.
+/// * `command` - The command the script runs, after expansion.
+/// * `args` - The extra arguments passed to the command.
+/// * `env` - The variables configured for the command.
+///
+/// # Errors
+///
+/// This function will return an error if a pattern is invalid or an input file cannot be read.
+pub fn input_hash(base_dir: &Path, inputs: &[String], command: &str, args: &[String], env: &HashMap<String, String>) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    hasher.update(command.as_bytes());
+    for arg in args {
+        hasher.update(b"\0");
+        hasher.update(arg.as_bytes());
+    }
+    let mut env: Vec<(&String, &String)> = env.iter().collect();
+    env.sort();
+    for (key, value) in env {
+        hasher.update(format!("\0{}={}", key, value).as_bytes());
+    }
+    for path in matching_files(base_dir, inputs)? {
+        let content = fs::read(&path).map_err(|e| format!("failed to read input {}: {}", path.display(), e))?;
+        let relative = path.strip_prefix(base_dir).unwrap_or(&path);
+        hasher.update(format!("\0{}\0{}\0", relative.display(), content.len()).as_bytes());
+        hasher.update(&content);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Return the file holding the input hash of a script's last successful run.
+///
+/// The name is percent-encoded, so that names such as `test:unit` and `test_unit` get files of
+/// their own.
+fn cache_file(base_dir: &Path, script_name: &str) -> PathBuf {
+    let file_name: String = script_name.bytes()
+        .map(|b| if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' { (b as char).to_string() } else { format!("%{:02X}", b) })
+        .collect();
+    base_dir.join(CACHE_DIR).join(file_name)
+}
+
+/// Check whether a script can be skipped: its last successful run had the same input hash, and
+/// every `outputs` pattern still matches at least one file.
+///
+/// # Arguments
+///
+/// * `base_dir` - The directory the cache and output patterns are relative to.
+/// * `script_name` - The name of the script.
+/// * `hash` - The current hash of the script's inputs.
+/// * `outputs` - The glob patterns of the files the script produces.
+pub fn is_fresh(base_dir: &Path, script_name: &str, hash: &str, outputs: &[String]) -> bool {
+    let recorded = fs::read_to_string(cache_file(base_dir, script_name)).unwrap_or_default();
+    recorded.trim() == hash
+        && outputs.iter().all(|pattern| matching_files(base_dir, std::slice::from_ref(pattern)).is_ok_and(|files| !files.is_empty()))
+}
+
+/// Record the input hash of a successful run of a script.
+///
+/// # Errors
+///
+/// This function will return an error if the cache file cannot be written.
+pub fn record(base_dir: &Path, script_name: &str, hash: &str) -> io::Result<()> {
+    let path = cache_file(base_dir, script_name);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format!("{}\n", hash))
+}
@@ -0,0 +1,60 @@
+//! This module finds the files changed since a git ref, for `run --changed`.
+
+use std::{path::Path, process::Command};
+use glob::Pattern;
+use crate::error::CargoScriptError;
+
+/// The files changed since a git ref, relative to the base directory of the run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedFiles {
+    /// The ref passed to `--changed`.
+    pub git_ref: String,
+    pub files: Vec<String>,
+}
+
+impl ChangedFiles {
+    /// List the files changed since a ref with `git diff --name-only`.
+    ///
+    /// Only files below the base directory are listed, with paths relative to it, so they
+    /// compare directly with `inputs` patterns.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_dir` - The directory `inputs` patterns are relative to.
+    /// * `git_ref` - The ref to compare against, such as `origin/main` or `HEAD~1`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the base directory is not inside a git repository,
+    /// or if git cannot be run or rejects the ref.
+    pub fn since(base_dir: &Path, git_ref: &str) -> Result<Self, CargoScriptError> {
+        let base_dir = base_dir.canonicalize().unwrap_or_else(|_| base_dir.to_path_buf());
+        if !base_dir.ancestors().any(|dir| dir.join(".git").exists()) {
+            return Err(CargoScriptError::NotAGitRepository { path: base_dir.display().to_string() });
+        }
+        let git_failed = |message: String| CargoScriptError::GitFailed { git_ref: git_ref.to_string(), message };
+        let output = Command::new("git")
+            .args(["diff", "--name-only", "--relative", git_ref, "--"])
+            .current_dir(&base_dir)
+            .output()
+            .map_err(|e| git_failed(e.to_string()))?;
+        if !output.status.success() {
+            return Err(git_failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+        }
+        let files = String::from_utf8_lossy(&output.stdout).lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(ChangedFiles { git_ref: git_ref.to_string(), files })
+    }
+
+    /// Check whether any changed file matches one of a script's `inputs` patterns.
+    ///
+    /// Patterns that are not valid globs count as matching, so the script runs.
+    pub fn matches(&self, inputs: &[String]) -> bool {
+        inputs.iter().any(|input| match Pattern::new(input.trim_start_matches("./")) {
+            Ok(pattern) => self.files.iter().any(|file| pattern.matches(file)),
+            Err(_) => true,
+        })
+    }
+}
@@ -0,0 +1,239 @@
+//! This module provides the functionality to generate shell completion scripts.
+
+use std::{fmt, fs, io::{self, Write}, path::{Path, PathBuf}};
+use clap::ValueEnum;
+use clap_complete::{generate, Generator, Shell};
+use clap_complete_fig::Fig;
+use colored::*;
+use crate::commands::{format::ScriptsFormat, script::Scripts};
+
+/// Name of the hidden command printing the script names for dynamic completions.
+pub const COMPLETE_SCRIPTS_COMMAND: &str = "__complete_scripts";
+
+/// The shells (and autocomplete tools) completions can be generated for.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Elvish,
+    Fish,
+    #[value(name = "powershell")]
+    PowerShell,
+    Zsh,
+    /// A TypeScript spec for the Fig autocomplete tool.
+    Fig,
+}
+
+impl CompletionShell {
+    /// Return the `clap_complete` shell, or `None` for Fig.
+    fn shell(self) -> Option<Shell> {
+        match self {
+            CompletionShell::Bash => Some(Shell::Bash),
+            CompletionShell::Elvish => Some(Shell::Elvish),
+            CompletionShell::Fish => Some(Shell::Fish),
+            CompletionShell::PowerShell => Some(Shell::PowerShell),
+            CompletionShell::Zsh => Some(Shell::Zsh),
+            CompletionShell::Fig => None,
+        }
+    }
+}
+
+impl fmt::Display for CompletionShell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.to_possible_value().expect("no skipped variants");
+        write!(f, "{}", value.get_name())
+    }
+}
+
+impl Generator for CompletionShell {
+    fn file_name(&self, name: &str) -> String {
+        match self.shell() {
+            Some(shell) => shell.file_name(name),
+            None => Fig.file_name(name),
+        }
+    }
+
+    fn generate(&self, cmd: &clap::Command, buf: &mut dyn Write) {
+        match self.shell() {
+            Some(shell) => shell.generate(cmd, buf),
+            None => Fig.generate(cmd, buf),
+        }
+    }
+}
+
+/// Number of lines of each generated completion shown in dry-run mode.
+const PREVIEW_LINES: usize = 5;
+
+/// Generate a completion script for a shell.
+///
+/// The script is printed to stdout, or written to `out_dir` using the shell's conventional
+/// file name. In dry-run mode nothing is written: the target path (if any) and a preview of the
+/// generated script are printed instead.
+///
+/// # Arguments
+///
+/// * `shell` - The shell to generate completions for.
+/// * `cmd` - The clap command describing the CLI.
+/// * `out_dir` - An optional directory to write the completion file to.
+/// * `dry_run` - Whether to only preview what would be generated.
+///
+/// # Errors
+///
+/// This function will return an error if the completion file cannot be written.
+pub fn generate_completions(shell: CompletionShell, cmd: &mut clap::Command, out_dir: Option<&Path>, dry_run: bool) -> io::Result<()> {
+    let bin_name = cmd.get_name().to_string();
+    // Hidden commands, such as `__complete_scripts`, are left out of the completions.
+    let mut cmd = clap::Command::new(bin_name.clone())
+        .args(cmd.get_arguments().cloned())
+        .subcommands(cmd.get_subcommands().filter(|sub| !sub.is_hide_set()).cloned());
+    let mut buffer = Vec::new();
+    generate(shell, &mut cmd, &bin_name, &mut buffer);
+    let buffer = add_script_completions(shell, &cmd, &bin_name, buffer);
+
+    match (out_dir, dry_run) {
+        (None, false) => {
+            print!("{}", String::from_utf8_lossy(&buffer));
+        }
+        (None, true) => {
+            println!("{}: {} completions are valid ({} bytes)", "Dry run".yellow(), shell, buffer.len());
+        }
+        (Some(dir), false) => {
+            let path = completion_path(shell, dir, &bin_name);
+            fs::create_dir_all(dir)?;
+            fs::write(&path, &buffer)?;
+            println!("{}: {}", "Completions written".green(), path.display());
+        }
+        (Some(dir), true) => {
+            let path = completion_path(shell, dir, &bin_name);
+            println!("{}: would write {} ({} bytes)", "Dry run".yellow(), path.display(), buffer.len());
+            for line in String::from_utf8_lossy(&buffer).lines().take(PREVIEW_LINES) {
+                println!("  {}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Make the `script` argument of commands such as `run` complete the names of the scripts, by
+/// calling the hidden `__complete_scripts` command when completing.
+///
+/// Only bash, zsh, and fish completions are extended; the others are returned unchanged. When
+/// no scripts file is found, nothing is suggested and the shell's default completion applies.
+fn add_script_completions(shell: CompletionShell, cmd: &clap::Command, bin_name: &str, buffer: Vec<u8>) -> Vec<u8> {
+    let subcommands: Vec<&str> = cmd.get_subcommands()
+        .filter(|sub| sub.get_positionals().any(|arg| arg.get_id() == "script"))
+        .map(|sub| sub.get_name())
+        .collect();
+    let script = String::from_utf8_lossy(&buffer).into_owned();
+    let script = match shell {
+        CompletionShell::Bash => {
+            let function = format!("_{}", bin_name.replace('-', "__"));
+            let dynamic = format!("{}_scripts", function);
+            let script = script.replace(&format!("complete -F {} ", function), &format!("complete -F {} ", dynamic));
+            format!(
+                r#"{script}
+{dynamic}() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" word sub="" positionals=0 i
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        word="${{COMP_WORDS[i]}}"
+        case "$word" in
+            -e|--env|--scripts-path|--scripts-env) ((i++)) ;;
+            -*) ;;
+            *) if [[ -z "$sub" ]]; then sub="$word"; else ((positionals++)); fi ;;
+        esac
+    done
+    if [[ "$cur" != -* && $positionals -eq 0 ]]; then
+        case "$sub" in
+            {subcommands})
+                COMPREPLY=( $(compgen -W "$("${{COMP_WORDS[0]}}" {complete} 2>/dev/null)" -- "$cur") )
+                return 0
+                ;;
+        esac
+    fi
+    {function} "$@"
+}}
+"#,
+                subcommands = subcommands.join("|"),
+                complete = COMPLETE_SCRIPTS_COMMAND,
+            )
+        }
+        CompletionShell::Zsh => {
+            let function = format!("_{}_scripts", bin_name);
+            let script: String = script.lines()
+                .map(|line| match line.contains(":script -- ") {
+                    true => line.replacen(":_default'", &format!(":{}'", function), 1),
+                    false => line.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let definition = format!(
+                "(( $+functions[{function}] )) ||\n{function}() {{\n    local -a scripts\n    scripts=(${{(f)\"$(${{words[1]}} {complete} 2>/dev/null)\"}})\n    compadd -a scripts\n}}\n\n",
+                complete = COMPLETE_SCRIPTS_COMMAND,
+            );
+            let dispatch = format!("if [ \"$funcstack[1]\" = \"_{}\" ]; then", bin_name);
+            match script.find(&dispatch) {
+                Some(index) => format!("{}{}{}\n", &script[..index], definition, &script[index..]),
+                None => script + "\n",
+            }
+        }
+        CompletionShell::Fish => {
+            let mut script = script;
+            for sub in subcommands {
+                script.push_str(&format!(
+                    "complete -c {bin} -n \"__fish_{fn_bin}_using_subcommand {sub}; and not __fish_seen_argument -l help\" -f -a \"({bin} {complete} 2>/dev/null)\"\n",
+                    bin = bin_name,
+                    fn_bin = bin_name.replace('-', "_"),
+                    complete = COMPLETE_SCRIPTS_COMMAND,
+                ));
+            }
+            script
+        }
+        _ => script,
+    };
+    script.into_bytes()
+}
+
+/// Print the names of the scripts, one per line, for the hidden `__complete_scripts` command.
+///
+/// Nothing is printed when the scripts file is missing or invalid, so completion falls back to
+/// the shell's default instead of showing an error.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file or directory.
+pub fn complete_script_names(scripts_path: &str) {
+    let path = Path::new(scripts_path);
+    let scripts = if path.is_dir() {
+        Scripts::load_dir(path).ok().map(|(scripts, _)| scripts)
+    } else {
+        fs::read_to_string(path).ok()
+            .and_then(|content| ScriptsFormat::from_path(path).parse::<Scripts>(&content).ok())
+            .map(|mut scripts| {
+                let _ = scripts.merge_imports(scripts_path);
+                scripts
+            })
+    };
+    let Some(scripts) = scripts else {
+        return;
+    };
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    let mut stdout = io::stdout().lock();
+    for name in names {
+        if writeln!(stdout, "{}", name).is_err() {
+            return;
+        }
+    }
+}
+
+/// Return the conventional completion file path for a shell inside a directory.
+fn completion_path(shell: CompletionShell, dir: &Path, bin_name: &str) -> PathBuf {
+    let file_name = match shell {
+        CompletionShell::Bash => bin_name.to_string(),
+        CompletionShell::Zsh => format!("_{}", bin_name),
+        CompletionShell::Fish => format!("{}.fish", bin_name),
+        CompletionShell::PowerShell => format!("_{}.ps1", bin_name),
+        CompletionShell::Elvish => format!("{}.elv", bin_name),
+        CompletionShell::Fig => format!("{}.ts", bin_name),
+    };
+    dir.join(file_name)
+}
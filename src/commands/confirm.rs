@@ -0,0 +1,30 @@
+//! This module asks for confirmation before running scripts marked with `confirm`.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+/// Ask a yes/no question, defaulting to no.
+///
+/// On a terminal the question is asked with an interactive prompt. Otherwise it is printed to
+/// stderr and a line is read from stdin, so answers can be piped in; anything other than `y` or
+/// `yes` (including end of input) is a no.
+///
+/// # Arguments
+///
+/// * `prompt` - The question to ask.
+pub fn confirm(prompt: &str) -> bool {
+    if io::stdin().is_terminal() && io::stderr().is_terminal() {
+        return Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .default(false)
+            .interact()
+            .unwrap_or(false);
+    }
+    eprint!("{} (y/N) ", prompt);
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
@@ -0,0 +1,66 @@
+//! This module compares a command's environment with the environment cargo-script was started
+//! with, for `run --env-diff`.
+
+use std::collections::{BTreeMap, HashMap};
+use colored::*;
+use crate::commands::secrets::SecretMask;
+
+/// How a variable differs between the parent process and a command.
+#[derive(Debug, PartialEq, Eq)]
+pub enum EnvChange {
+    /// Set for the command, but not in the parent process.
+    Added { value: String },
+    /// Set in both, with different values.
+    Changed { old: String, new: String },
+    /// Set in the parent process, but not passed to the command.
+    Removed,
+}
+
+/// Compute how a command's environment differs from the parent process environment.
+///
+/// # Arguments
+///
+/// * `ambient_env` - The environment cargo-script was started with.
+/// * `child_env` - The environment the command runs with.
+///
+/// # Returns
+///
+/// The changed variables, sorted by name.
+pub fn env_diff(ambient_env: &[(String, String)], child_env: &HashMap<String, String>) -> BTreeMap<String, EnvChange> {
+    let ambient: HashMap<&str, &str> = ambient_env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+    let mut diff = BTreeMap::new();
+    for (key, value) in child_env {
+        match ambient.get(key.as_str()) {
+            None => { diff.insert(key.clone(), EnvChange::Added { value: value.clone() }); }
+            Some(old) if old != value => { diff.insert(key.clone(), EnvChange::Changed { old: old.to_string(), new: value.clone() }); }
+            Some(_) => {}
+        }
+    }
+    for key in ambient.keys().filter(|key| !child_env.contains_key(**key)) {
+        diff.insert(key.to_string(), EnvChange::Removed);
+    }
+    diff
+}
+
+/// Format an environment diff as lines: `+ NAME = value`, `~ NAME: old -> new`, and `- NAME`,
+/// masking secret values.
+///
+/// # Arguments
+///
+/// * `script_name` - The name of the script the diff belongs to.
+/// * `diff` - The diff to format.
+/// * `mask` - The masking applied to secret values.
+pub fn format_env_diff(script_name: &str, diff: &BTreeMap<String, EnvChange>, mask: &SecretMask) -> Vec<String> {
+    let mut lines = vec![format!("{} [ {} ]:", "Env diff".yellow(), script_name)];
+    if diff.is_empty() {
+        lines.push("  (no changes)".to_string());
+    }
+    for (key, change) in diff {
+        lines.push(match change {
+            EnvChange::Added { value } => format!("  {} {} = {}", "+".green(), key, mask.apply(key, value)),
+            EnvChange::Changed { old, new } => format!("  {} {}: {} -> {}", "~".yellow(), key, mask.apply(key, old), mask.apply(key, new)),
+            EnvChange::Removed => format!("  {} {}", "-".red(), key),
+        });
+    }
+    lines
+}
@@ -0,0 +1,85 @@
+//! This module reads `.env` files referenced by `env_file`.
+
+use std::{collections::HashMap, fs, path::Path};
+use crate::error::CargoScriptError;
+
+/// Parse the content of a `.env` file into its variables.
+///
+/// Each line holds `KEY=VALUE`, optionally prefixed with `export`. Blank lines and lines
+/// starting with `#` are ignored. Double-quoted values support the escapes `\n`, `\t`, `\r`,
+/// `\"`, `\\`, and `\$`; single-quoted values are taken literally. Unquoted values are trimmed
+/// and a ` #` starts a trailing comment.
+///
+/// # Errors
+///
+/// This function will return an error naming the line number of the first malformed line.
+pub fn parse_env_file(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {}: expected KEY=VALUE", number + 1));
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("line {}: invalid variable name `{}`", number + 1, key));
+        }
+        let value = parse_value(value.trim()).map_err(|e| format!("line {}: {}", number + 1, e))?;
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+/// Parse a single value, handling quotes and escapes.
+fn parse_value(value: &str) -> Result<String, String> {
+    if let Some(rest) = value.strip_prefix('\'') {
+        let end = rest.find('\'').ok_or("unterminated single quote")?;
+        return Ok(rest[..end].to_string());
+    }
+    if let Some(rest) = value.strip_prefix('"') {
+        let mut parsed = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Ok(parsed),
+                '\\' => match chars.next() {
+                    Some('n') => parsed.push('\n'),
+                    Some('t') => parsed.push('\t'),
+                    Some('r') => parsed.push('\r'),
+                    Some(other @ ('"' | '\\' | '$')) => parsed.push(other),
+                    Some(other) => {
+                        parsed.push('\\');
+                        parsed.push(other);
+                    }
+                    None => break,
+                },
+                _ => parsed.push(c),
+            }
+        }
+        return Err("unterminated double quote".to_string());
+    }
+    let value = match value.find(" #") {
+        Some(comment) => &value[..comment],
+        None => value,
+    };
+    Ok(value.trim_end().to_string())
+}
+
+/// Read and parse an env file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file, already resolved against the base directory.
+///
+/// # Errors
+///
+/// This function will return an error if the file is missing, unreadable, or malformed.
+pub fn load_env_file(path: &Path) -> Result<HashMap<String, String>, CargoScriptError> {
+    let env_file_error = |message: String| CargoScriptError::EnvFileFailed { path: path.display().to_string(), message };
+    let content = fs::read_to_string(path).map_err(|e| env_file_error(e.to_string()))?;
+    Ok(parse_env_file(&content).map_err(env_file_error)?.into_iter().collect())
+}
@@ -0,0 +1,194 @@
+//! This module provides the functionality to describe the environment contract of a script.
+
+use std::{collections::{BTreeMap, HashSet}, env};
+use crate::commands::{OutputFormat, secrets::SecretMask, script::{flatten_chain, platform_commands, referenced_env_vars, script_command, script_env, Scripts}};
+use crate::error::CargoScriptError;
+use colored::*;
+use emoji::symbols;
+use serde::Serialize;
+
+/// A variable referenced or declared by a script chain.
+#[derive(Serialize, Debug)]
+pub struct EnvSchemaEntry {
+    pub name: String,
+    /// `optional` when a default is declared, `referenced` when only used by a command.
+    pub kind: String,
+    /// Where the variable is declared or referenced (e.g. `global_env`, `env:build`, `command:build`).
+    pub sources: Vec<String>,
+    pub default: Option<String>,
+    /// Whether a value is currently available from a default, `--env`, or the process environment.
+    pub satisfied: bool,
+}
+
+/// Build the environment schema of a script and every script it includes.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to describe.
+/// * `env_overrides` - A slice of command line environment variable overrides.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist.
+pub fn env_schema(scripts: &Scripts, script_name: &str, env_overrides: &[String]) -> Result<Vec<EnvSchemaEntry>, CargoScriptError> {
+    let chain = flatten_chain(scripts, script_name)?;
+    let mut entries: BTreeMap<String, EnvSchemaEntry> = BTreeMap::new();
+
+    let mut declare = |name: &str, source: String, default: Option<&String>| {
+        let entry = entries.entry(name.to_string()).or_insert_with(|| EnvSchemaEntry {
+            name: name.to_string(),
+            kind: "referenced".to_string(),
+            sources: Vec::new(),
+            default: None,
+            satisfied: false,
+        });
+        if let Some(value) = default {
+            entry.kind = "optional".to_string();
+            entry.default = Some(value.clone());
+        }
+        if !entry.sources.contains(&source) {
+            entry.sources.push(source);
+        }
+    };
+
+    for (key, value) in scripts.global_env.iter().flatten() {
+        declare(key, "global_env".to_string(), Some(value));
+    }
+    for name in &chain {
+        let script = &scripts.scripts[name];
+        for (key, value) in script_env(script).into_iter().flatten() {
+            declare(key, format!("env:{}", name), Some(value));
+        }
+        for var in script_command(script).map(referenced_env_vars).unwrap_or_default() {
+            declare(&var, format!("command:{}", name), None);
+        }
+    }
+    for override_str in env_overrides {
+        if let Some((key, _)) = override_str.split_once('=') {
+            declare(key, "cli".to_string(), None);
+        }
+    }
+
+    let overridden: Vec<&str> = env_overrides.iter().filter_map(|o| o.split_once('=').map(|(k, _)| k)).collect();
+    for entry in entries.values_mut() {
+        entry.satisfied = entry.default.is_some()
+            || overridden.contains(&entry.name.as_str())
+            || env::var_os(&entry.name).is_some();
+    }
+    Ok(entries.into_values().collect())
+}
+
+/// Print the environment schema of a script as a table or as JSON.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to describe.
+/// * `env_overrides` - A slice of command line environment variable overrides.
+/// * `format` - The output format.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist.
+pub fn dump_env_schema(scripts: &Scripts, script_name: &str, env_overrides: &[String], format: OutputFormat, show_secrets: bool) -> Result<(), CargoScriptError> {
+    let mut entries = env_schema(scripts, script_name, env_overrides)?;
+    let mask = SecretMask::new(scripts, show_secrets);
+    for entry in &mut entries {
+        if let Some(default) = &entry.default {
+            entry.default = Some(mask.apply(&entry.name, default).to_string());
+        }
+    }
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&entries).expect("Failed to serialize env schema"));
+        return Ok(());
+    }
+
+    let max_name_len = entries.iter().map(|e| e.name.len()).max().unwrap_or(0).max("Variable".len()) + 2;
+    println!("{:<width$} {:<12} {:<10} {}", "Variable".yellow(), "Kind".yellow(), "Satisfied".yellow(), "Sources".yellow(), width = max_name_len);
+    println!("{}", "-".repeat(max_name_len + 40).yellow());
+    for entry in &entries {
+        let satisfied = if entry.satisfied { "yes".green() } else { "no".red() };
+        println!("{:<width$} {:<12} {:<10} {}", entry.name.green(), entry.kind, satisfied, entry.sources.join(", "), width = max_name_len);
+    }
+    Ok(())
+}
+
+/// A declared variable that no command of a script chain references.
+#[derive(Serialize, Debug)]
+pub struct UnusedEnvEntry {
+    pub name: String,
+    /// Where the variable is declared (`global_env` or `env:<script>`).
+    pub sources: Vec<String>,
+}
+
+/// Find the variables declared in `global_env` or the `env` of a script chain that none of its
+/// commands or declared values reference.
+///
+/// Programs can read variables without a command naming them (e.g. `RUST_LOG`, or a sourced
+/// file), so the result lists candidates for removal rather than certainties.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to check.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist.
+pub fn unused_env(scripts: &Scripts, script_name: &str) -> Result<Vec<UnusedEnvEntry>, CargoScriptError> {
+    let chain = flatten_chain(scripts, script_name)?;
+    let mut declared: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut referenced: HashSet<String> = HashSet::new();
+
+    for (key, value) in scripts.global_env.iter().flatten() {
+        declared.entry(key).or_default().push("global_env".to_string());
+        referenced.extend(referenced_env_vars(value));
+    }
+    for name in &chain {
+        let script = &scripts.scripts[name];
+        for (key, value) in script_env(script).into_iter().flatten() {
+            declared.entry(key).or_default().push(format!("env:{}", name));
+            referenced.extend(referenced_env_vars(value));
+        }
+        let commands = script_command(script).into_iter().chain(platform_commands(script).into_iter().map(|(_, command)| command));
+        for command in commands {
+            referenced.extend(referenced_env_vars(command));
+        }
+    }
+
+    Ok(declared.into_iter()
+        .filter(|(name, _)| !referenced.contains(*name))
+        .map(|(name, sources)| UnusedEnvEntry { name: name.to_string(), sources })
+        .collect())
+}
+
+/// Print the possibly unused variables of a script chain as a list or as JSON.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to check.
+/// * `format` - The output format.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist.
+pub fn report_unused_env(scripts: &Scripts, script_name: &str, format: OutputFormat) -> Result<(), CargoScriptError> {
+    let entries = unused_env(scripts, script_name)?;
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&entries).expect("Failed to serialize unused variables"));
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("{}  [ {} ] every declared variable is referenced", symbols::other_symbol::CHECK_MARK.glyph, script_name.green());
+    }
+    for entry in &entries {
+        println!(
+            "{}  [ {} ] {} is possibly unused (declared in {})",
+            symbols::warning::WARNING.glyph, script_name.yellow(), entry.name, entry.sources.join(", ")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! This module emits the newline-delimited JSON events printed by `run --json-stream`.
+
+use std::{io::{self, BufRead, Read, Write}, thread};
+use serde::Serialize;
+use crate::commands::metrics::Metrics;
+
+/// A progress event of a run, printed as one line of JSON.
+#[derive(Serialize, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A script (or include) is about to run; `level` is its depth in the include chain.
+    ScriptStarted { script: &'a str, level: usize },
+    /// A line printed by a command, with `--include-output`.
+    CommandOutput { script: &'a str, stream: &'a str, line: &'a str },
+    /// A script and its includes finished.
+    ScriptFinished { script: &'a str, success: bool, duration_ms: u128, error: Option<String> },
+    /// The whole run finished, with the running time of each script.
+    RunFinished {
+        success: bool,
+        error: Option<String>,
+        #[serde(flatten)]
+        metrics: Metrics,
+    },
+}
+
+/// Print an event to stdout as a single line and flush it right away.
+pub fn emit(event: &Event) {
+    let line = serde_json::to_string(event).expect("Failed to serialize event");
+    let mut stdout = io::stdout().lock();
+    let _ = writeln!(stdout, "{}", line);
+    let _ = stdout.flush();
+}
+
+/// Read a child's output pipe on a separate thread, emitting each line as a `command_output`
+/// event as soon as it is read.
+///
+/// # Arguments
+///
+/// * `pipe` - The pipe to read, if the output was captured.
+/// * `script` - The name of the script the command belongs to.
+/// * `stream` - `stdout` or `stderr`.
+pub fn stream_lines<R: Read + Send + 'static>(pipe: Option<R>, script: &str, stream: &'static str) -> thread::JoinHandle<Vec<String>> {
+    let script = script.to_string();
+    thread::spawn(move || {
+        if let Some(pipe) = pipe {
+            for line in io::BufReader::new(pipe).lines().map_while(Result::ok) {
+                emit(&Event::CommandOutput { script: &script, stream, line: &line });
+            }
+        }
+        Vec::new()
+    })
+}
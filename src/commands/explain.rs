@@ -0,0 +1,135 @@
+//! This module builds the post-mortem printed by `run --explain-failure` when a command fails.
+
+use std::{collections::HashMap, process::Command};
+use colored::*;
+use emoji::symbols;
+use crate::commands::{script::find_in_path, secrets::SecretMask};
+
+/// Number of captured output lines shown in a failure report.
+pub const EXPLAIN_TAIL_LINES: usize = 20;
+
+/// Diagnostics gathered about a failed command.
+#[derive(Debug, Clone)]
+pub struct FailureReport {
+    pub script: String,
+    pub command: String,
+    pub interpreter: String,
+    pub exit_code: Option<i32>,
+    /// The last captured output lines of the command, or `None` when its output was shown
+    /// as it ran instead of being captured.
+    pub output_tail: Option<Vec<String>>,
+    /// The variables configured for the command, sorted by name.
+    pub env: Vec<(String, String)>,
+    /// The `--version` output of the tools the command uses.
+    pub tool_versions: Vec<(String, String)>,
+}
+
+impl FailureReport {
+    /// Print the report to stderr.
+    pub fn print(&self) {
+        eprintln!();
+        eprintln!("{} {} [ {} ]", symbols::other_symbol::CROSS_MARK.glyph, "Failure report".bold().red(), self.script);
+        eprintln!("{}", "-".repeat(80).red());
+        eprintln!("{}: {}", "Interpreter".yellow(), self.interpreter);
+        eprintln!("{}:", "Command".yellow());
+        for line in self.command.lines() {
+            eprintln!("  {}", line);
+        }
+        match self.exit_code {
+            Some(code) => eprintln!("{}: {}", "Exit code".yellow(), code),
+            None => eprintln!("{}: terminated by a signal", "Exit code".yellow()),
+        }
+        match &self.output_tail {
+            Some(tail) if tail.is_empty() => eprintln!("{}: (none)", "Output".yellow()),
+            Some(tail) => {
+                eprintln!("{} (last {} line(s)):", "Output".yellow(), tail.len());
+                for line in tail {
+                    eprintln!("  {}", line);
+                }
+            }
+            None => {}
+        }
+        if self.env.is_empty() {
+            eprintln!("{}: (none configured)", "Env".yellow());
+        } else {
+            eprintln!("{}:", "Env".yellow());
+            for (key, value) in &self.env {
+                eprintln!("  {} = {}", key, value);
+            }
+        }
+        if !self.tool_versions.is_empty() {
+            eprintln!("{}:", "Tools".yellow());
+            for (tool, version) in &self.tool_versions {
+                eprintln!("  {}: {}", tool, version);
+            }
+        }
+        eprintln!();
+    }
+}
+
+/// Sort the configured variables of a failed command for display, masking secret values.
+pub fn sorted_env(env: &HashMap<String, String>, mask: &SecretMask) -> Vec<(String, String)> {
+    let mut env: Vec<(String, String)> = env.iter().map(|(k, v)| (k.clone(), mask.apply(k, v).to_string())).collect();
+    env.sort();
+    env
+}
+
+/// Return the programs a command invokes: the first word of each line, skipping comments,
+/// shebangs, and leading `NAME=value` assignments.
+pub fn command_programs(command: &str) -> Vec<String> {
+    let mut programs: Vec<String> = Vec::new();
+    for line in command.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let program = line
+            .split_whitespace()
+            .find(|word| !word.contains('='))
+            .map(|word| word.trim_matches(|c| matches!(c, '(' | ')' | '{' | '}')));
+        if let Some(program) = program.filter(|p| !p.is_empty()) {
+            if !programs.iter().any(|p| p == program) {
+                programs.push(program.to_string());
+            }
+        }
+    }
+    programs
+}
+
+/// Probe the version of the tools a failed command relied on.
+///
+/// The interpreter and the programs the command starts are probed with `--version`. With a
+/// toolchain, `cargo +<toolchain> --version` is reported. Tools not on `PATH`, or that do not
+/// answer `--version`, are skipped.
+///
+/// # Arguments
+///
+/// * `command` - The failed command.
+/// * `interpreter` - The interpreter the command ran with.
+/// * `toolchain` - The toolchain the command ran with, if any.
+pub fn tool_versions(command: &str, interpreter: &str, toolchain: Option<&str>) -> Vec<(String, String)> {
+    let mut tools: Vec<String> = Vec::new();
+    if let Some(toolchain) = toolchain {
+        tools.push(format!("cargo +{}", toolchain));
+    } else if !matches!(interpreter, "sh" | "cmd") {
+        tools.push(interpreter.to_string());
+    }
+    for program in command_programs(command) {
+        if !tools.contains(&program) {
+            tools.push(program);
+        }
+    }
+
+    tools.into_iter().filter_map(|tool| {
+        let mut words = tool.split_whitespace();
+        let program = words.next()?;
+        find_in_path(program)?;
+        let output = Command::new(program).args(words).arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+        let version = String::from_utf8_lossy(&text).lines().next()?.trim().to_string();
+        (!version.is_empty()).then_some((tool, version))
+    }).collect()
+}
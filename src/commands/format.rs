@@ -0,0 +1,74 @@
+//! This module reads and writes scripts files in the formats cargo-script understands: TOML,
+//! YAML, and JSON.
+
+use std::path::Path;
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+
+/// The file format of a scripts file, detected from its extension.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScriptsFormat {
+    #[default]
+    Toml,
+    Yaml,
+    Json,
+}
+
+/// The extensions of scripts files, in the order they are looked up.
+pub const EXTENSIONS: [&str; 4] = ["toml", "yaml", "yml", "json"];
+
+impl ScriptsFormat {
+    /// Return the format of a file from its extension, TOML when the extension is unknown.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("yaml" | "yml") => ScriptsFormat::Yaml,
+            Some("json") => ScriptsFormat::Json,
+            _ => ScriptsFormat::Toml,
+        }
+    }
+
+    /// Return the name of the format, for messages.
+    pub fn name(self) -> &'static str {
+        match self {
+            ScriptsFormat::Toml => "TOML",
+            ScriptsFormat::Yaml => "YAML",
+            ScriptsFormat::Json => "JSON",
+        }
+    }
+
+    /// Return the extension of files written in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScriptsFormat::Toml => "toml",
+            ScriptsFormat::Yaml => "yaml",
+            ScriptsFormat::Json => "json",
+        }
+    }
+
+    /// Parse content written in this format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the parser's message if the content is invalid.
+    pub fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T, String> {
+        match self {
+            ScriptsFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            ScriptsFormat::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+            ScriptsFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Convert TOML content into this format, keeping the same data.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error message if the TOML content cannot be parsed.
+    pub fn from_toml(self, content: &str) -> Result<String, String> {
+        let value = || content.parse::<toml::Table>().map_err(|e| e.to_string());
+        match self {
+            ScriptsFormat::Toml => Ok(content.to_string()),
+            ScriptsFormat::Yaml => serde_yaml::to_string(&value()?).map_err(|e| e.to_string()),
+            ScriptsFormat::Json => serde_json::to_string_pretty(&value()?).map(|json| json + "\n").map_err(|e| e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! This module prints the include graph of the scripts, for `graph`.
+
+use std::collections::HashSet;
+use clap::ValueEnum;
+use colored::*;
+use crate::commands::{script::{script_command, script_command_file, Scripts}, show::{includes, tree_roots}};
+
+/// Output format of the `graph` command.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// An indented tree of every top-level script and its includes.
+    #[default]
+    Tree,
+    /// A Graphviz DOT digraph with an edge per include.
+    Dot,
+}
+
+/// Print the include graph of the scripts.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `format` - The output format.
+pub fn print_graph(scripts: &Scripts, format: GraphFormat) {
+    match format {
+        GraphFormat::Tree => print_tree(scripts),
+        GraphFormat::Dot => print_dot(scripts),
+    }
+}
+
+/// Print each top-level script with its includes indented beneath it.
+///
+/// Scripts that only run a command are leaves and are shown with the first line of it. An
+/// include that leads back to a script of its own chain is marked as a cycle and not expanded again.
+fn print_tree(scripts: &Scripts) {
+    fn print_node(scripts: &Scripts, name: &str, prefix: &str, connector: &str, path: &mut Vec<String>) {
+        let label = format!("{}{}", prefix, connector);
+        let Some(script) = scripts.scripts.get(name) else {
+            println!("{}{} {}", label, name.red(), "(not found)".red());
+            return;
+        };
+        if let Some(start) = path.iter().position(|p| p == name) {
+            let mut chain = path[start..].to_vec();
+            chain.push(name.to_string());
+            println!("{}{} {}", label, name.red(), format!("(cycle: {})", chain.join(" -> ")).red());
+            return;
+        }
+        let children = includes(script);
+        if children.is_empty() {
+            let command = match (script_command(script), script_command_file(script)) {
+                (Some(command), _) => format!("$ {}", command.lines().next().unwrap_or_default().trim()),
+                (None, Some(path)) => format!("$ < {}", path),
+                (None, None) => "(no command)".to_string(),
+            };
+            println!("{}{}  {}", label, name.green(), command.dimmed());
+            return;
+        }
+        println!("{}{}", label, name.bold().cyan());
+
+        let child_prefix = match connector {
+            "" => prefix.to_string(),
+            "└─ " => format!("{}   ", prefix),
+            _ => format!("{}│  ", prefix),
+        };
+        path.push(name.to_string());
+        for (i, child) in children.iter().enumerate() {
+            let connector = if i + 1 == children.len() { "└─ " } else { "├─ " };
+            print_node(scripts, child, &child_prefix, connector, path);
+        }
+        path.pop();
+    }
+
+    for root in tree_roots(scripts) {
+        print_node(scripts, root, "", "", &mut Vec::new());
+    }
+}
+
+/// Print the include graph as a Graphviz DOT digraph.
+///
+/// Leaf command scripts are drawn as boxes, includes of missing scripts as dashed nodes, and
+/// edges that close an include cycle in red.
+fn print_dot(scripts: &Scripts) {
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+
+    println!("digraph scripts {{");
+    println!("    rankdir=LR;");
+    let mut missing = Vec::new();
+    for name in &names {
+        let children = includes(&scripts.scripts[name.as_str()]);
+        let shape = if children.is_empty() { "box" } else { "ellipse" };
+        println!("    {} [shape={}];", dot_id(name), shape);
+        for child in children {
+            if !scripts.scripts.contains_key(child) && !missing.contains(&child) {
+                missing.push(child);
+            }
+        }
+    }
+    for name in missing {
+        println!("    {} [style=dashed, label={}];", dot_id(name), dot_id(&format!("{} (not found)", name)));
+    }
+    for name in &names {
+        for child in includes(&scripts.scripts[name.as_str()]) {
+            if reaches(scripts, child, name) {
+                println!("    {} -> {} [color=red, label=\"cycle\"];", dot_id(name), dot_id(child));
+            } else {
+                println!("    {} -> {};", dot_id(name), dot_id(child));
+            }
+        }
+    }
+    println!("}}");
+}
+
+/// Check whether `target` is reachable from `from` through includes, `from` itself included.
+fn reaches(scripts: &Scripts, from: &str, target: &str) -> bool {
+    fn visit(scripts: &Scripts, name: &str, target: &str, seen: &mut HashSet<String>) -> bool {
+        if name == target {
+            return true;
+        }
+        if !seen.insert(name.to_string()) {
+            return false;
+        }
+        scripts.scripts.get(name).map(includes).unwrap_or_default()
+            .iter()
+            .any(|child| visit(scripts, child, target, seen))
+    }
+    visit(scripts, from, target, &mut HashSet::new())
+}
+
+/// Quote a name as a DOT identifier.
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,227 @@
+//! This module provides the functionality to remember previous runs in the `.cargo-script` directory:
+//! the last successful run (for `run -`) and a rolling history log (for `history`).
+//!
+//! State is stored next to the scripts file and keyed by the scripts file path, so different
+//! projects (and different scripts files in the same directory) do not collide.
+
+use std::{collections::BTreeMap, env, fs, io::Write, path::PathBuf, time::{Duration, SystemTime, UNIX_EPOCH}};
+use crate::commands::{script::scripts_dir, OutputFormat};
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+/// Name of the directory holding cargo-script state, created next to the scripts file.
+pub const STATE_DIR: &str = ".cargo-script";
+
+/// The script and environment overrides of the last successful run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LastRun {
+    pub script: String,
+    pub env: Vec<String>,
+}
+
+/// Number of entries kept in the history log unless `CARGO_SCRIPT_HISTORY_CAP` says otherwise.
+pub const DEFAULT_HISTORY_CAP: usize = 500;
+
+/// One run recorded in the history log.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoryEntry {
+    pub scripts_path: String,
+    pub script: String,
+    /// The `--label` the run was tagged with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Start of the run, in seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub success: bool,
+    pub duration_ms: u128,
+    /// The scripts whose own step failed, rerun by `run --select-failed`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<String>,
+}
+
+impl HistoryEntry {
+    /// Return the scripts to rerun for this run: the scripts that failed, or the scripts the
+    /// run was started with when no failing script was recorded.
+    pub fn failed_scripts(&self) -> Vec<String> {
+        if self.failed.is_empty() {
+            self.script.split(", ").map(str::to_string).collect()
+        } else {
+            self.failed.clone()
+        }
+    }
+}
+
+/// Return the state directory for a scripts file.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+pub fn state_dir(scripts_path: &str) -> PathBuf {
+    scripts_dir(scripts_path).join(STATE_DIR)
+}
+
+/// Return the key identifying a scripts file inside the state files.
+fn scripts_key(scripts_path: &str) -> String {
+    fs::canonicalize(scripts_path)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| scripts_path.to_string())
+}
+
+/// Read the last successful run recorded for a scripts file, if any.
+///
+/// A missing or unreadable record is treated as no previous run.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+pub fn read_last_run(scripts_path: &str) -> Option<LastRun> {
+    let content = fs::read_to_string(state_dir(scripts_path).join("last-run")).ok()?;
+    let mut records: BTreeMap<String, LastRun> = serde_json::from_str(&content).ok()?;
+    records.remove(&scripts_key(scripts_path))
+}
+
+/// Record the last successful run for a scripts file.
+///
+/// Failing to write the record is not fatal for the run, so errors are ignored.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+/// * `last_run` - The run to record.
+pub fn write_last_run(scripts_path: &str, last_run: &LastRun) {
+    let dir = state_dir(scripts_path);
+    let path = dir.join("last-run");
+    let mut records: BTreeMap<String, LastRun> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    records.insert(scripts_key(scripts_path), last_run.clone());
+
+    if fs::create_dir_all(&dir).is_ok() {
+        if let Ok(content) = serde_json::to_string_pretty(&records) {
+            let _ = fs::write(path, content);
+        }
+    }
+}
+
+/// Append a run to the history log, pruning the oldest entries beyond the cap.
+///
+/// The cap is read from `CARGO_SCRIPT_HISTORY_CAP` and defaults to `DEFAULT_HISTORY_CAP`.
+/// Failing to write the log is not fatal for the run, so errors are ignored.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+/// * `script` - The name of the script that was run (comma-separated for several scripts).
+/// * `label` - The `--label` the run was tagged with, if any.
+/// * `started` - When the run started.
+/// * `duration` - How long the run took.
+/// * `success` - Whether the run succeeded.
+/// * `failed` - The scripts whose own step failed.
+pub fn record_history(scripts_path: &str, script: &str, label: Option<&str>, started: SystemTime, duration: Duration, success: bool, failed: &[String]) {
+    let cap = env::var("CARGO_SCRIPT_HISTORY_CAP").ok().and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_HISTORY_CAP);
+    let dir = state_dir(scripts_path);
+    let path = dir.join("history.jsonl");
+    let entry = HistoryEntry {
+        scripts_path: scripts_key(scripts_path),
+        script: script.to_string(),
+        label: label.map(str::to_string),
+        timestamp: started.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default(),
+        success,
+        duration_ms: duration.as_millis(),
+        failed: failed.to_vec(),
+    };
+    let Ok(line) = serde_json::to_string(&entry) else { return };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let lines: Vec<&str> = existing.lines().filter(|l| !l.trim().is_empty()).collect();
+    if lines.len() >= cap {
+        // Rewrite the log with only the newest entries.
+        let keep = cap.saturating_sub(1);
+        let mut content: String = lines[lines.len() - keep..].iter().map(|l| format!("{}\n", l)).collect();
+        if cap > 0 {
+            content.push_str(&format!("{}\n", line));
+        }
+        let _ = fs::write(&path, content);
+    } else if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// Read the recorded runs of a scripts file, oldest first.
+///
+/// A missing log is treated as empty and lines that cannot be parsed are skipped.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+pub fn read_history(scripts_path: &str) -> Vec<HistoryEntry> {
+    let key = scripts_key(scripts_path);
+    fs::read_to_string(state_dir(scripts_path).join("history.jsonl"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<HistoryEntry>(line).ok())
+        .filter(|entry| entry.scripts_path == key)
+        .collect()
+}
+
+/// Show the most recent runs of a scripts file, newest first.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+/// * `limit` - The maximum number of runs to show.
+/// * `format` - Whether to print a table or JSON.
+pub fn show_history(scripts_path: &str, limit: usize, format: OutputFormat) {
+    let entries: Vec<HistoryEntry> = read_history(scripts_path).into_iter().rev().take(limit).collect();
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&entries).expect("Failed to serialize history"));
+        return;
+    }
+    if entries.is_empty() {
+        println!("No runs recorded for [ {} ].", scripts_path);
+        return;
+    }
+
+    let names: Vec<String> = entries.iter()
+        .map(|e| match &e.label {
+            Some(label) => format!("{} [{}]", e.script, label),
+            None => e.script.clone(),
+        })
+        .collect();
+    let max_name_len = names.iter().map(|n| n.chars().count()).max().unwrap_or(0).max("Script".len()) + 2;
+    println!("{:<23} {:<width$} {:<8} {}", "Time (UTC)".yellow(), "Script".yellow(), "Status".yellow(), "Duration".yellow(), width = max_name_len);
+    println!("{}", "-".repeat(max_name_len + 46).yellow());
+    for (entry, name) in entries.iter().zip(&names) {
+        let status = if entry.success { "ok".green() } else { "failed".red() };
+        println!(
+            "{:<23} {:<width$} {:<8} {} ms",
+            format_timestamp(entry.timestamp),
+            name.green(),
+            status,
+            entry.duration_ms,
+            width = max_name_len
+        );
+    }
+}
+
+/// Format seconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS` in UTC.
+pub(crate) fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+    // Civil-from-days conversion (proleptic Gregorian calendar).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, seconds / 3_600, seconds % 3_600 / 60, seconds % 60)
+}
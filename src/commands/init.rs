@@ -1,29 +1,13 @@
 //! This module provides the functionality to initialize a `Scripts.toml` file.
 
-use std::{fs, io};
+use std::{fs, io::{self, IsTerminal}, path::Path};
 use colored::*;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input};
 use emoji::symbols;
+use crate::commands::format::ScriptsFormat;
 
-/// Initialize a `Scripts.toml` file in the current directory.
-///
-/// If the file already exists, it prompts the user for confirmation to replace it.
-/// The function creates a default `Scripts.toml` file if the user agrees.
-///
-/// # Panics
-///
-/// This function will panic if it fails to read user input or write to the `Scripts.toml` file.
-pub fn init_script_file() {
-    let file_path = "Scripts.toml";
-    if fs::metadata(file_path).is_ok() {
-        println!("{}  [ {} ] already exists. Do you want to replace it? ({}/{})", symbols::warning::WARNING.glyph, file_path.yellow(), "y".green(), "n".red());
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).expect("Failed to read input");
-        if input.trim().to_lowercase() != "y" {
-            println!("Operation cancelled.");
-            return;
-        }
-    }
-    let default_content = r#"
+/// The template written by `init` when no other source is selected.
+const DEFAULT_TEMPLATE: &str = r#"
 [global_env]
 
 [scripts]
@@ -33,6 +17,222 @@ release = "cargo build --release"
 test = { command = "cargo test", env = { RUST_LOG = "warn" } }
 doc = "cargo doc --no-deps --open"
 "#;
-    fs::write(file_path, default_content).expect("Failed to write Scripts.toml");
-    println!("{}  [ {} ] has been created.", symbols::other_symbol::CHECK_MARK.glyph, "Scripts.toml".green());
+
+/// A script entry generated by `init`.
+struct ScriptEntry {
+    name: String,
+    command: String,
+    info: Option<String>,
+    interpreter: Option<String>,
+}
+
+/// Initialize a `Scripts.toml` file in the current directory.
+///
+/// If the file already exists, it prompts the user for confirmation to replace it unless `force` is set.
+/// The function creates a default `Scripts.toml` file if the user agrees, or one tailored to the
+/// project when `from_existing` is set. With a YAML or JSON `format`, the same scripts are
+/// written to `Scripts.yaml` or `Scripts.json` instead.
+///
+/// # Arguments
+///
+/// * `from_existing` - Whether to scaffold scripts from the project files found in the current directory.
+/// * `interactive` - Whether to prompt for the scripts to add.
+/// * `force` - Whether to replace an existing file without asking.
+/// * `format` - The format of the file to write.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read user input or write to the scripts file.
+pub fn init_script_file(from_existing: bool, interactive: bool, force: bool, format: ScriptsFormat) {
+    let file_path = format!("Scripts.{}", format.extension());
+    let file_path = file_path.as_str();
+    if !force && !confirm_overwrite(file_path) {
+        println!("Operation cancelled.");
+        return;
+    }
+    let content = if from_existing {
+        scaffold_from_existing(Path::new("."))
+    } else if interactive && io::stdin().is_terminal() {
+        match interactive_scripts(file_path, format) {
+            Some(content) => content,
+            None => {
+                println!("Operation cancelled.");
+                return;
+            }
+        }
+    } else {
+        if interactive {
+            println!("{}  Not running in a terminal, using the default template", symbols::warning::WARNING.glyph);
+        }
+        DEFAULT_TEMPLATE.to_string()
+    };
+    let content = format.from_toml(&content).expect("Failed to convert the scripts");
+    fs::write(file_path, content).unwrap_or_else(|e| panic!("Failed to write {}: {}", file_path, e));
+    println!("{}  [ {} ] has been created.", symbols::other_symbol::CHECK_MARK.glyph, file_path.green());
+}
+
+/// Prompt for scripts until the user is done, then preview the result.
+///
+/// The preview is shown in `format`. Returns the TOML content to write, or `None` when no script
+/// was added or the preview was rejected.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read user input.
+fn interactive_scripts(file_path: &str, format: ScriptsFormat) -> Option<String> {
+    let theme = ColorfulTheme::default();
+    let mut scripts: Vec<ScriptEntry> = Vec::new();
+    loop {
+        let name: String = Input::with_theme(&theme)
+            .with_prompt("Script name (leave empty to finish)")
+            .allow_empty(true)
+            .validate_with(|name: &String| -> Result<(), String> {
+                if name.is_empty() {
+                    Ok(())
+                } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                    Err("Use letters, digits, `_`, or `-`".to_string())
+                } else if scripts.iter().any(|s| &s.name == name) {
+                    Err(format!("[ {} ] was already added", name))
+                } else {
+                    Ok(())
+                }
+            })
+            .interact_text()
+            .expect("Failed to read input");
+        if name.is_empty() {
+            break;
+        }
+        let command: String = Input::with_theme(&theme).with_prompt("Command").interact_text().expect("Failed to read input");
+        let info: String = Input::with_theme(&theme)
+            .with_prompt("Description (optional)")
+            .allow_empty(true)
+            .interact_text()
+            .expect("Failed to read input");
+        let interpreter: String = Input::with_theme(&theme)
+            .with_prompt("Interpreter (leave empty for the default shell)")
+            .allow_empty(true)
+            .interact_text()
+            .expect("Failed to read input");
+        scripts.push(ScriptEntry {
+            name,
+            command,
+            info: Some(info).filter(|s| !s.is_empty()),
+            interpreter: Some(interpreter).filter(|s| !s.is_empty()),
+        });
+        let more = Confirm::with_theme(&theme).with_prompt("Add another script?").default(true).interact().expect("Failed to read input");
+        if !more {
+            break;
+        }
+    }
+    if scripts.is_empty() {
+        return None;
+    }
+
+    let content = render_scripts(&scripts);
+    println!("\n{}\n{}", "Preview".bold().yellow(), format.from_toml(&content).expect("Failed to convert the scripts"));
+    Confirm::with_theme(&theme)
+        .with_prompt(format!("Write {}?", file_path))
+        .default(true)
+        .interact()
+        .expect("Failed to read input")
+        .then_some(content)
+}
+
+/// Render script entries as `Scripts.toml` content, one inline table per script.
+fn render_scripts(scripts: &[ScriptEntry]) -> String {
+    let mut content = String::from("[global_env]\n\n[scripts]\n");
+    for script in scripts {
+        let mut fields = vec![format!("command = {}", toml::Value::String(script.command.clone()))];
+        if let Some(info) = &script.info {
+            fields.push(format!("info = {}", toml::Value::String(info.clone())));
+        }
+        if let Some(interpreter) = &script.interpreter {
+            fields.push(format!("interpreter = {}", toml::Value::String(interpreter.clone())));
+        }
+        content.push_str(&format!("{} = {{ {} }}\n", script.name, fields.join(", ")));
+    }
+    content
+}
+
+/// Ask whether an existing file may be replaced.
+///
+/// Returns `true` when the file does not exist or the user answers `y`.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read user input.
+fn confirm_overwrite(file_path: &str) -> bool {
+    if fs::metadata(file_path).is_err() {
+        return true;
+    }
+    println!("{}  [ {} ] already exists. Do you want to replace it? ({}/{})", symbols::warning::WARNING.glyph, file_path.yellow(), "y".green(), "n".red());
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read input");
+    input.trim().to_lowercase() == "y"
+}
+
+/// Build `Scripts.toml` content from the project files found in a directory.
+///
+/// Detects a Cargo package or workspace (`Cargo.toml`), npm scripts (`package.json`), and Docker
+/// Compose files, printing what was detected and which scripts were generated. Falls back to
+/// running `cargo run` when nothing is detected.
+///
+/// # Arguments
+///
+/// * `dir` - The project directory to inspect.
+pub fn scaffold_from_existing(dir: &Path) -> String {
+    let mut scripts: Vec<(String, String, String)> = Vec::new();
+
+    if let Ok(cargo_toml) = fs::read_to_string(dir.join("Cargo.toml")) {
+        let is_workspace = cargo_toml.parse::<toml::Table>().is_ok_and(|t| t.contains_key("workspace"));
+        let scope = if is_workspace { " --workspace" } else { "" };
+        println!("{}  Detected {}", symbols::other_symbol::CHECK_MARK.glyph, if is_workspace { "Cargo workspace" } else { "Cargo package" }.green());
+        scripts.push(("build".into(), format!("cargo build{}", scope), "Build the project".into()));
+        scripts.push(("test".into(), format!("cargo test{}", scope), "Run the tests".into()));
+        scripts.push(("release".into(), format!("cargo build{} --release", scope), "Build the project in release mode".into()));
+        scripts.push(("lint".into(), format!("cargo clippy{} --all-targets", scope), "Run clippy".into()));
+        scripts.push(("fmt".into(), "cargo fmt --all".into(), "Format the code".into()));
+        scripts.push(("doc".into(), format!("cargo doc{} --no-deps", scope), "Build the documentation".into()));
+    }
+
+    if let Ok(package_json) = fs::read_to_string(dir.join("package.json")) {
+        let npm_scripts = serde_json::from_str::<serde_json::Value>(&package_json)
+            .ok()
+            .and_then(|v| v.get("scripts").and_then(|s| s.as_object()).cloned())
+            .unwrap_or_default();
+        println!("{}  Detected {} ({} npm scripts)", symbols::other_symbol::CHECK_MARK.glyph, "package.json".green(), npm_scripts.len());
+        for (name, command) in &npm_scripts {
+            let key: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' }).collect();
+            let base = format!("npm_{}", key);
+            // Different npm names can sanitize to the same key (`build:dev` and `build/dev`),
+            // so append a numeric suffix until the key is unique.
+            let mut script_name = base.clone();
+            let mut suffix = 2;
+            while scripts.iter().any(|(existing, _, _)| *existing == script_name) {
+                script_name = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            let info = format!("npm script: {}", command.as_str().unwrap_or_default());
+            scripts.push((script_name, format!("npm run {}", name), info));
+        }
+    }
+
+    let compose_files = ["docker-compose.yml", "docker-compose.yaml", "compose.yml", "compose.yaml"];
+    if let Some(compose_file) = compose_files.iter().find(|f| dir.join(f).exists()) {
+        println!("{}  Detected {}", symbols::other_symbol::CHECK_MARK.glyph, compose_file.green());
+        scripts.push(("docker-up".into(), "docker compose up -d".into(), "Start the Docker Compose services".into()));
+        scripts.push(("docker-down".into(), "docker compose down".into(), "Stop the Docker Compose services".into()));
+        scripts.push(("docker-logs".into(), "docker compose logs -f".into(), "Follow the Docker Compose logs".into()));
+    }
+
+    if scripts.is_empty() {
+        println!("{}  No known project files detected, using a minimal template", symbols::warning::WARNING.glyph);
+        scripts.push(("dev".into(), "cargo run".into(), "Run the project".into()));
+    }
+
+    let entries: Vec<ScriptEntry> = scripts.into_iter().map(|(name, command, info)| {
+        println!("   {} {}", "+".green(), name);
+        ScriptEntry { name, command, info: Some(info), interpreter: None }
+    }).collect();
+    render_scripts(&entries)
 }
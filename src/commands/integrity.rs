@@ -0,0 +1,52 @@
+//! This module provides integrity checking of the scripts file through SHA-256 hashes.
+//!
+//! This protects against tampering (for example a malicious edit in CI), not against reading:
+//! the scripts file itself is not encrypted or hidden.
+
+use std::{fs, path::PathBuf};
+use sha2::{Digest, Sha256};
+use crate::commands::script::scripts_dir;
+use crate::error::CargoScriptError;
+
+/// Name of the file holding the pinned hash, looked up next to the scripts file.
+pub const LOCK_FILE: &str = ".cargo-script.lock";
+
+/// Return the lowercase hex SHA-256 hash of some content.
+pub fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Return the path of the lock file for a scripts file.
+pub fn lock_path(scripts_path: &str) -> PathBuf {
+    scripts_dir(scripts_path).join(LOCK_FILE)
+}
+
+/// Check the content of a scripts file against the expected hash.
+///
+/// The expected hash comes from `expected`, or else from the lock file next to the scripts file.
+/// When neither is present, nothing is checked.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+/// * `content` - The content that was read from the scripts file.
+/// * `expected` - The hash passed on the command line, if any.
+///
+/// # Errors
+///
+/// This function will return an error if the hash of the content does not match.
+pub fn verify_scripts_hash(scripts_path: &str, content: &[u8], expected: Option<&str>) -> Result<(), CargoScriptError> {
+    let expected = match expected {
+        Some(hash) => hash.trim().to_lowercase(),
+        None => match fs::read_to_string(lock_path(scripts_path)) {
+            Ok(lock) => lock.split_whitespace().next().unwrap_or_default().to_lowercase(),
+            Err(_) => return Ok(()),
+        },
+    };
+    let actual = sha256_hex(content);
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(CargoScriptError::IntegrityMismatch { path: scripts_path.to_string(), expected, actual })
+    }
+}
@@ -0,0 +1,79 @@
+//! This module expands the `${VAR}` and `$(script)` references of a command before it runs.
+
+use std::{collections::HashMap, env};
+use crate::commands::script::{script_command, Scripts};
+use crate::error::CargoScriptError;
+
+/// Expand the references of a command, the same way for every interpreter.
+///
+/// * `${VAR}` is replaced by the value of `VAR` from the script's environment, falling back to
+///   the process environment. An unknown variable is an error, so a reference meant for the
+///   shell, like a loop variable, is written `$${VAR}`.
+/// * `$(name)` is replaced by the command of the script `name`, itself expanded. References to
+///   anything other than a script (such as `$(date)`) are left to the shell.
+/// * `$${` and `$$(` are written as a literal `${` and `$(`, leaving the reference to the shell.
+///
+/// Other forms, like `$VAR` or `${VAR:-default}`, are left to the shell.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script the command belongs to.
+/// * `command` - The command to expand.
+/// * `env_vars` - The variables configured for the script.
+///
+/// # Errors
+///
+/// This function will return an error if a variable is unknown, or if scripts reference each
+/// other in a cycle.
+pub fn interpolate(scripts: &Scripts, script_name: &str, command: &str, env_vars: &HashMap<String, String>) -> Result<String, CargoScriptError> {
+    expand(scripts, command, env_vars, &mut vec![script_name.to_string()])
+}
+
+/// Expand a command, where `stack` holds the scripts whose commands are being expanded.
+fn expand(scripts: &Scripts, command: &str, env_vars: &HashMap<String, String>, stack: &mut Vec<String>) -> Result<String, CargoScriptError> {
+    let mut expanded = String::with_capacity(command.len());
+    let mut rest = command;
+    while let Some(pos) = rest.find('$') {
+        expanded.push_str(&rest[..pos]);
+        let reference = &rest[pos..];
+        if reference.starts_with("$${") || reference.starts_with("$$(") {
+            expanded.push('$');
+            rest = &reference[2..];
+        } else if let Some(name) = enclosed(reference, '{', '}').filter(|name| is_variable_name(name)) {
+            let value = env_vars.get(name).cloned().or_else(|| env::var(name).ok()).ok_or_else(|| {
+                CargoScriptError::UndefinedVariable { script: stack[0].clone(), variable: name.to_string() }
+            })?;
+            expanded.push_str(&value);
+            rest = &reference[name.len() + 3..];
+        } else if let Some((name, other)) = enclosed(reference, '(', ')').and_then(|name| scripts.scripts.get_key_value(name)) {
+            if let Some(start) = stack.iter().position(|s| s == name) {
+                let mut chain = stack[start..].to_vec();
+                chain.push(name.clone());
+                return Err(CargoScriptError::CircularInclude { chain });
+            }
+            stack.push(name.clone());
+            expanded.push_str(&expand(scripts, script_command(other).unwrap_or_default(), env_vars, stack)?);
+            stack.pop();
+            rest = &reference[name.len() + 3..];
+        } else {
+            expanded.push('$');
+            rest = &reference[1..];
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Return the text between `$<open>` and the next `close`, if it has no whitespace.
+fn enclosed(reference: &str, open: char, close: char) -> Option<&str> {
+    let inner = reference.strip_prefix('$')?.strip_prefix(open)?;
+    let name = &inner[..inner.find(close)?];
+    (!name.is_empty() && !name.contains(char::is_whitespace)).then_some(name)
+}
+
+/// Check whether a name is a plain variable name, such as `RUST_LOG`.
+fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
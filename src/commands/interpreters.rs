@@ -0,0 +1,99 @@
+//! This module provides the functionality to list the interpreters used by scripts.
+
+use std::{collections::BTreeMap, process::Command};
+use crate::commands::{OutputFormat, script::{effective_interpreter, find_in_path, script_command, Scripts}};
+use colored::*;
+use serde::Serialize;
+
+/// An interpreter referenced by scripts, with its availability on the current system.
+#[derive(Serialize, Debug)]
+pub struct InterpreterInfo {
+    pub name: String,
+    pub available: bool,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    /// Whether this is the interpreter used for scripts without an `interpreter` field.
+    pub default: bool,
+    pub used_by: Vec<String>,
+}
+
+/// Collect every interpreter referenced across scripts, plus the default one.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+pub fn collect_interpreters(scripts: &Scripts) -> Vec<InterpreterInfo> {
+    let default = scripts.shell();
+    let mut used_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    used_by.entry(default.to_string()).or_default();
+
+    for (name, script) in &scripts.scripts {
+        if script_command(script).is_some() {
+            used_by.entry(effective_interpreter(scripts, script).to_string()).or_default().push(name.clone());
+        }
+    }
+
+    used_by.into_iter().map(|(name, mut scripts)| {
+        scripts.sort();
+        let path = find_in_path(&name);
+        let version = path.as_ref().and_then(|_| probe_version(&name));
+        InterpreterInfo {
+            default: name == default,
+            available: path.is_some(),
+            path: path.map(|p| p.display().to_string()),
+            version,
+            name,
+            used_by: scripts,
+        }
+    }).collect()
+}
+
+/// Probe an interpreter's version by running it with `--version`.
+///
+/// Returns the first non-empty line of its output, checking stdout before stderr. Interpreters
+/// that reject `--version` (such as `sh` on most systems) report no version.
+fn probe_version(interpreter: &str) -> Option<String> {
+    let output = Command::new(interpreter).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stdout.lines().chain(stderr.lines())
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Print the interpreters used by scripts as a table or as JSON.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `format` - The output format.
+pub fn list_interpreters(scripts: &Scripts, format: OutputFormat) {
+    let interpreters = collect_interpreters(scripts);
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&interpreters).expect("Failed to serialize interpreters"));
+        return;
+    }
+
+    let names: Vec<String> = interpreters.iter()
+        .map(|i| if i.default { format!("{} (default)", i.name) } else { i.name.clone() })
+        .collect();
+    let max_name_len = names.iter().map(String::len).max().unwrap_or(0).max("Interpreter".len()) + 2;
+    println!("{:<width$} {:<10} {:<30} {}", "Interpreter".yellow(), "Available".yellow(), "Version".yellow(), "Used by".yellow(), width = max_name_len);
+    println!("{}", "-".repeat(max_name_len + 60).yellow());
+    for (interpreter, name) in interpreters.iter().zip(&names) {
+        let available = if interpreter.available { "yes".green() } else { "no".red() };
+        println!(
+            "{:<width$} {:<10} {:<30} {}",
+            name.green(),
+            available,
+            interpreter.version.as_deref().unwrap_or("-"),
+            interpreter.used_by.join(", "),
+            width = max_name_len
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! This module handles Ctrl-C and SIGTERM, stopping the running command and everything it
+//! started before cargo-script exits, instead of leaving them orphaned.
+
+use std::{process, sync::{atomic::{AtomicBool, Ordering}, Mutex}, thread, time::{Duration, Instant}};
+
+/// The exit code of a run stopped by Ctrl-C or SIGTERM, as shells report an interrupted command.
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// How long an interrupted command gets to exit on its own before its tree is killed.
+const GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// How often the grace period checks whether the command has exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// The command being run, if any.
+static RUNNING: Mutex<Option<RunningCommand>> = Mutex::new(None);
+
+/// Set once Ctrl-C or SIGTERM was received.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// A signal sent to a running command.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Signal {
+    /// SIGINT, asking the command to stop.
+    Interrupt,
+    /// SIGKILL, or killing the process tree on Windows.
+    Kill,
+}
+
+/// A command started by cargo-script.
+#[derive(Clone, Copy, Debug)]
+struct RunningCommand {
+    pid: u32,
+    /// Whether the command leads its own process group, so the whole group can be signalled.
+    own_group: bool,
+}
+
+/// Marks a command as running until it is dropped, so an interruption stops it.
+///
+/// Dropped once the command has exited. When the run was interrupted, the rest of the
+/// command's process group is killed then, since background processes of a shell ignore
+/// SIGINT and would outlive it.
+pub struct RunningGuard(RunningCommand);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        *RUNNING.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        if self.0.own_group && INTERRUPTED.load(Ordering::SeqCst) {
+            signal(self.0, Signal::Kill);
+        }
+    }
+}
+
+/// Install the handler for Ctrl-C and SIGTERM.
+///
+/// On interruption, the running command is sent SIGINT and given a grace period to exit before
+/// it is killed, and the rest of its process group is killed once it is gone. cargo-script then
+/// exits with [`INTERRUPTED_EXIT_CODE`], without printing metrics or failures. A handler that
+/// cannot be installed leaves the default behavior in place.
+pub fn install_handler() {
+    let _ = ctrlc::set_handler(|| {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        let running = *RUNNING.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(command) = running {
+            stop(command);
+            // The run exits on its own once the command is gone, after removing its temporary files.
+            thread::sleep(GRACE_PERIOD);
+        }
+        process::exit(INTERRUPTED_EXIT_CODE);
+    });
+}
+
+/// Record a started command until the returned guard is dropped.
+///
+/// # Arguments
+///
+/// * `pid` - The process id of the command.
+/// * `own_group` - Whether the command was started in its own process group.
+pub fn track(pid: u32, own_group: bool) -> RunningGuard {
+    let command = RunningCommand { pid, own_group };
+    *RUNNING.lock().unwrap_or_else(|e| e.into_inner()) = Some(command);
+    RunningGuard(command)
+}
+
+/// Exit with [`INTERRUPTED_EXIT_CODE`] if the run was interrupted.
+///
+/// Called once a command has exited, so the failure of an interrupted command is not reported
+/// while the handler is still stopping it.
+pub fn exit_if_interrupted() {
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        process::exit(INTERRUPTED_EXIT_CODE);
+    }
+}
+
+/// Send SIGINT to a command, then kill it if it is still running after the grace period.
+///
+/// A command sharing the terminal's foreground group already got the SIGINT of Ctrl-C, and is
+/// not sent a second one that could make it skip its own cleanup.
+fn stop(command: RunningCommand) {
+    if command.own_group {
+        signal(command, Signal::Interrupt);
+    }
+    let deadline = Instant::now() + GRACE_PERIOD;
+    while Instant::now() < deadline {
+        if RUNNING.lock().unwrap_or_else(|e| e.into_inner()).is_none() {
+            return;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    signal(command, Signal::Kill);
+}
+
+/// Send a signal to a command, or to its whole process group when it leads one.
+///
+/// On Windows, where consoles deliver Ctrl-C to every attached process, the tree is killed
+/// through `taskkill /T` instead.
+fn signal(command: RunningCommand, signal: Signal) {
+    #[cfg(unix)]
+    {
+        let pid = command.pid as libc::pid_t;
+        let signal = match signal {
+            Signal::Interrupt => libc::SIGINT,
+            Signal::Kill => libc::SIGKILL,
+        };
+        // SAFETY: sending a signal touches no memory. Failures, such as a command that already
+        // exited, are ignored.
+        unsafe {
+            if command.own_group {
+                libc::killpg(pid, signal);
+            } else {
+                libc::kill(pid, signal);
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    if signal == Signal::Kill {
+        let _ = process::Command::new("taskkill").args(["/T", "/F", "/PID", &command.pid.to_string()]).output();
+    }
+}
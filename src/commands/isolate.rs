@@ -0,0 +1,69 @@
+//! This module provides the functionality to run script steps in isolated copies of the project.
+//!
+//! With `run --isolate-cwd`, every step gets a fresh temporary directory holding a full copy of
+//! the project directory, so steps cannot modify the working tree or see each other's leftovers.
+//! The copy is a plain recursive copy: its cost grows with the size of the project, which is why
+//! version control and build output directories are skipped.
+
+use std::{env, fs, io, path::{Path, PathBuf}, sync::atomic::{AtomicUsize, Ordering}};
+
+/// Directories that are never copied into an isolated directory.
+const SKIPPED_DIRS: [&str; 4] = [".git", "target", "node_modules", crate::commands::history::STATE_DIR];
+
+/// Create a fresh temporary copy of a project directory for one script step.
+///
+/// # Arguments
+///
+/// * `project_dir` - The directory to copy.
+/// * `script_name` - The name of the step, used in the directory name.
+///
+/// # Errors
+///
+/// This function will return an error if the directory cannot be created or copied.
+pub fn create_isolated_dir(project_dir: &Path, script_name: &str) -> io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let safe_name: String = script_name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect();
+    let dir = env::temp_dir().join(format!(
+        "cargo-script-isolated-{}-{}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst),
+        safe_name
+    ));
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    copy_tree(project_dir, &dir)?;
+    Ok(dir)
+}
+
+/// Recursively copy a directory, recreating symlinks instead of following them where supported.
+fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let target = to.join(&name);
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+                continue;
+            }
+            copy_tree(&entry.path(), &target)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(from)?, to)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to).map(|_| ())
+}
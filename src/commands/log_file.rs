@@ -0,0 +1,106 @@
+//! This module copies command output into a log file while it is shown as usual, for
+//! `--log-file`.
+
+use std::{fs::File, io::{self, Read, Write}, mem, path::Path, sync::{Arc, Mutex}, thread, time::{SystemTime, UNIX_EPOCH}};
+use crate::commands::history::format_timestamp;
+use crate::error::CargoScriptError;
+
+/// A log file shared by every command of a run.
+#[derive(Clone, Debug)]
+pub struct LogFile(Arc<Mutex<File>>);
+
+impl LogFile {
+    /// Create the log file, truncating it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be created.
+    pub fn create(path: &Path) -> Result<Self, CargoScriptError> {
+        let file = File::create(path)
+            .map_err(|e| CargoScriptError::LogFileFailed { path: path.display().to_string(), message: e.to_string() })?;
+        Ok(LogFile(Arc::new(Mutex::new(file))))
+    }
+
+    /// Write the header line that starts the output of a script's command.
+    pub fn header(&self, script: &str) {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        self.write(format!("==> [ {} ] started at {} UTC\n", script, format_timestamp(now)).as_bytes());
+    }
+
+    /// Wrap a command's output pipe so everything read from it is also written to the log.
+    pub fn tee<R: Read>(&self, pipe: R) -> TeeReader<R> {
+        TeeReader { pipe, log: self.clone(), line: Vec::new() }
+    }
+
+    /// Append bytes to the log. Write errors are ignored, so a full disk doesn't fail the run.
+    fn write(&self, bytes: &[u8]) {
+        let mut file = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = file.write_all(bytes);
+    }
+}
+
+/// A pipe whose output is copied into a [`LogFile`] as it is read.
+///
+/// Output is logged a whole line at a time, so the lines of stdout and stderr don't get mixed
+/// within a line.
+pub struct TeeReader<R> {
+    pipe: R,
+    log: LogFile,
+    line: Vec<u8>,
+}
+
+impl<R: Read> Read for TeeReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.pipe.read(buf)?;
+        self.line.extend_from_slice(&buf[..read]);
+        if let Some(end) = self.line.iter().rposition(|byte| *byte == b'\n') {
+            let rest = self.line.split_off(end + 1);
+            self.log.write(&mem::replace(&mut self.line, rest));
+        }
+        Ok(read)
+    }
+}
+
+impl<R> Drop for TeeReader<R> {
+    /// Log the last line, when the output didn't end with a newline.
+    fn drop(&mut self) {
+        if !self.line.is_empty() {
+            self.line.push(b'\n');
+            self.log.write(&self.line);
+        }
+    }
+}
+
+/// Return a child's output pipe, copied into the log file when there is one.
+pub fn tee_pipe<R: Read + Send + 'static>(log: Option<&LogFile>, pipe: Option<R>) -> Option<Box<dyn Read + Send>> {
+    let pipe = pipe?;
+    Some(match log {
+        Some(log) => Box::new(log.tee(pipe)),
+        None => Box::new(pipe),
+    })
+}
+
+/// Copy a child's output pipe to the terminal on a separate thread, as it arrives.
+///
+/// This is used when the output is piped for the log file only, so it still shows up as if it
+/// were inherited.
+///
+/// # Arguments
+///
+/// * `pipe` - The child's stdout or stderr.
+/// * `to_stderr` - Whether to write to stderr instead of stdout.
+pub fn pass_through<R: Read + Send + 'static>(pipe: Option<R>, to_stderr: bool) -> thread::JoinHandle<Vec<String>> {
+    thread::spawn(move || {
+        let Some(mut pipe) = pipe else { return Vec::new() };
+        let mut buf = [0; 8192];
+        while let Ok(read @ 1..) = pipe.read(&mut buf) {
+            let _ = if to_stderr {
+                io::stderr().lock().write_all(&buf[..read])
+            } else {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(&buf[..read]).and_then(|_| stdout.flush())
+            };
+        }
+        Vec::new()
+    })
+}
@@ -0,0 +1,129 @@
+//! This module provides the functionality to save script durations as JSON and compare them against a baseline.
+
+use std::{collections::{BTreeMap, HashMap}, fs, path::Path, time::Duration};
+use crate::error::CargoScriptError;
+use colored::*;
+use serde::{Deserialize, Serialize};
+
+/// Machine-readable running times of a run, in milliseconds.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Metrics {
+    pub scripts: BTreeMap<String, u128>,
+    pub total_ms: u128,
+}
+
+impl Metrics {
+    /// Build metrics from the durations recorded by `run_script`.
+    pub fn from_durations(durations: &HashMap<String, Duration>) -> Self {
+        let scripts: BTreeMap<String, u128> = durations.iter().map(|(name, d)| (name.clone(), d.as_millis())).collect();
+        let total_ms = scripts.values().sum();
+        Metrics { scripts, total_ms }
+    }
+}
+
+/// Write the durations of a run to a JSON file.
+///
+/// # Arguments
+///
+/// * `path` - The file to write.
+/// * `durations` - The durations recorded by `run_script`.
+///
+/// # Errors
+///
+/// This function will return an error if the file cannot be written.
+pub fn write_metrics(path: &Path, durations: &HashMap<String, Duration>) -> Result<(), CargoScriptError> {
+    let metrics_error = |message: String| CargoScriptError::MetricsOutputFailed { path: path.display().to_string(), message };
+    let content = serde_json::to_string_pretty(&Metrics::from_durations(durations)).map_err(|e| metrics_error(e.to_string()))?;
+    fs::write(path, content).map_err(|e| metrics_error(e.to_string()))
+}
+
+/// Return the scripts that ran longer than a threshold, sorted by name.
+///
+/// # Arguments
+///
+/// * `durations` - The durations recorded by `run_script`.
+/// * `threshold` - The running time after which a script counts as slow.
+pub fn slow_scripts(durations: &HashMap<String, Duration>, threshold: Duration) -> Vec<String> {
+    let mut slow: Vec<String> = durations.iter()
+        .filter(|(_, duration)| **duration > threshold)
+        .map(|(name, _)| name.clone())
+        .collect();
+    slow.sort();
+    slow
+}
+
+/// Settings for comparing a run against a baseline.
+#[derive(Debug, Clone)]
+pub struct BenchmarkCompare {
+    /// Path of the baseline JSON file.
+    pub baseline: String,
+    /// Allowed slowdown in percent before a script counts as regressed.
+    pub threshold_percent: f64,
+    /// Slowdowns smaller than this many milliseconds are treated as noise.
+    pub min_delta_ms: u128,
+    /// Write the current durations as the new baseline instead of comparing.
+    pub update_baseline: bool,
+}
+
+/// Parse a regression threshold such as `20%` or `20` into a percentage.
+pub fn parse_threshold(value: &str) -> Result<f64, String> {
+    value.trim().trim_end_matches('%').trim().parse::<f64>()
+        .map_err(|_| format!("invalid threshold `{}`, expected a percentage such as `20%`", value))
+}
+
+/// Compare a run against its baseline, or write the baseline when `update_baseline` is set.
+///
+/// Each script present in both the baseline and the run is compared. A script regressed when it
+/// is slower by more than `min_delta_ms` and by more than `threshold_percent` of its baseline.
+///
+/// # Arguments
+///
+/// * `durations` - The durations recorded by `run_script`.
+/// * `compare` - The comparison settings.
+///
+/// # Errors
+///
+/// This function will return an error if the baseline cannot be read or written, or if any script regressed.
+pub fn compare_with_baseline(durations: &HashMap<String, Duration>, compare: &BenchmarkCompare) -> Result<(), CargoScriptError> {
+    let current = Metrics::from_durations(durations);
+    let invalid_baseline = |message: String| CargoScriptError::InvalidBaseline { path: compare.baseline.clone(), message };
+
+    if compare.update_baseline {
+        let content = serde_json::to_string_pretty(&current).map_err(|e| invalid_baseline(e.to_string()))?;
+        fs::write(&compare.baseline, content).map_err(|e| invalid_baseline(e.to_string()))?;
+        println!("{}: {}", "Baseline updated".green(), compare.baseline);
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&compare.baseline).map_err(|e| invalid_baseline(e.to_string()))?;
+    let baseline: Metrics = serde_json::from_str(&content).map_err(|e| invalid_baseline(e.to_string()))?;
+
+    let mut regressed = Vec::new();
+    println!("\n{}", "Benchmark Comparison".bold().yellow());
+    println!("{}", "-".repeat(80).yellow());
+    for (script, &current_ms) in &current.scripts {
+        let Some(&baseline_ms) = baseline.scripts.get(script) else {
+            println!("   Script: {:<25}  {} ms (no baseline)", script, current_ms);
+            continue;
+        };
+        let delta_ms = current_ms as i128 - baseline_ms as i128;
+        let delta_percent = if baseline_ms == 0 { 0.0 } else { delta_ms as f64 * 100.0 / baseline_ms as f64 };
+        let is_regression = delta_ms > compare.min_delta_ms as i128 && delta_percent > compare.threshold_percent;
+        let line = format!(
+            "   Script: {:<25}  {} ms -> {} ms ({:+.1}%)",
+            script, baseline_ms, current_ms, delta_percent
+        );
+        if is_regression {
+            println!("{}", line.red());
+            regressed.push(script.clone());
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    if regressed.is_empty() {
+        Ok(())
+    } else {
+        Err(CargoScriptError::BenchmarkRegression { scripts: regressed, threshold_percent: compare.threshold_percent })
+    }
+}
@@ -2,24 +2,390 @@
 //!
 //! It includes functionalities to run scripts, initialize the Scripts.toml file, and handle script execution.
 
-use clap::{Subcommand, ArgAction};
+use clap::{Subcommand, ArgAction, ValueEnum};
+
+/// Output format for commands that can emit machine-readable results.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
 
 /// Enum representing the different commands supported by the CLI tool.
+// Parsed once per process, so the size of the `Run` variant does not matter.
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     #[command(about = "Run a script by name defined in Scripts.toml")]
     Run {
+        /// Name of the script to run, or `-` to rerun the last successful script. Without it, a script is picked interactively.
         #[arg(value_name = "SCRIPT_NAME", action = ArgAction::Set)]
-        script: String,
+        script: Option<String>,
+        /// Rerun only the scripts that failed in the last recorded run of this scripts file.
+        #[arg(long, conflicts_with_all = ["script", "print_includes_order", "dump_env_schema", "report_unused_env", "step"])]
+        select_failed: bool,
         #[arg(short, long, value_name = "KEY=VALUE", action = ArgAction::Append)]
         env: Vec<String>,
+        /// Only print a script's messages and output when it fails.
+        #[arg(long)]
+        quiet_success: bool,
+        /// Directory used as the root for relative paths; commands run from it (defaults to the Scripts.toml directory).
+        #[arg(long, value_name = "DIR")]
+        working_set: Option<String>,
+        /// Compare script durations against a baseline JSON file and fail on regressions.
+        #[arg(long, value_name = "BASELINE")]
+        benchmark_compare: Option<String>,
+        /// Allowed slowdown before a script counts as regressed (e.g. `20%`).
+        #[arg(long, value_name = "PERCENT", default_value = "20%", value_parser = metrics::parse_threshold)]
+        regression_threshold: f64,
+        /// Slowdowns smaller than this many milliseconds are ignored as noise.
+        #[arg(long, value_name = "MS", default_value_t = 50)]
+        min_delta_ms: u128,
+        /// Write the current durations to the baseline file instead of comparing.
+        #[arg(long, requires = "benchmark_compare")]
+        update_baseline: bool,
+        /// Warn about unquoted variable expansions that are subject to word splitting.
+        #[arg(long)]
+        shell_escape_check: bool,
+        /// Print each requirement probe, its output, and pass/fail to stderr before running.
+        #[arg(long)]
+        trace_requirements: bool,
+        /// Run commands in a Linux namespace sandbox configured by each script's `sandbox` table.
+        #[arg(long)]
+        sandbox: bool,
+        /// Run each step in a fresh temporary copy of the project directory.
+        #[arg(long)]
+        isolate_cwd: bool,
+        /// Keep the isolated directories for inspection instead of removing them.
+        #[arg(long, requires = "isolate_cwd")]
+        keep_isolated: bool,
+        /// Refuse to run unless the scripts file has this SHA-256 hash (overrides `.cargo-script.lock`).
+        #[arg(long, value_name = "SHA256")]
+        verify_hash: Option<String>,
+        /// Print each step's elapsed time as soon as it finishes.
+        #[arg(long)]
+        print_stage_timings: bool,
+        /// On failure with --quiet-success, only show the last N output lines plus error lines.
+        #[arg(long, value_name = "N", requires = "quiet_success", num_args = 0..=1, default_missing_value = "50")]
+        error_context: Option<usize>,
+        /// After a successful run, copy files matching this glob (relative to the working set) into --artifacts-dir.
+        #[arg(long, value_name = "GLOB", action = ArgAction::Append)]
+        capture_artifacts: Vec<String>,
+        /// Directory captured artifacts are copied into, preserving relative paths.
+        #[arg(long, value_name = "DIR", default_value = "artifacts")]
+        artifacts_dir: String,
+        /// Fail when a --capture-artifacts pattern matches no files.
+        #[arg(long)]
+        require_artifacts: bool,
+        /// Format of the performance summary; `json` prints script durations in milliseconds.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        metrics_format: OutputFormat,
+        /// Also write the performance metrics as JSON to this file.
+        #[arg(long, value_name = "PATH")]
+        metrics_output: Option<String>,
+        /// Flag scripts that ran longer than this in the performance summary (e.g. `30`, `2m`; overrides `[settings] slow_threshold`).
+        #[arg(long, value_name = "SECONDS", value_parser = notify::parse_duration)]
+        warn_slow: Option<std::time::Duration>,
+        /// Exit with an error when a script ran longer than the slow threshold.
+        #[arg(long)]
+        fail_slow: bool,
+        /// Run scripts with `inputs` even when their inputs are unchanged since their last successful run.
+        #[arg(long)]
+        no_cache: bool,
+        /// Skip scripts with `inputs` when none of the files changed since this git ref match them.
+        #[arg(long, value_name = "GIT_REF")]
+        changed: Option<String>,
+        /// Do not run the `pre<name>` and `post<name>` scripts around each script.
+        #[arg(long)]
+        no_hooks: bool,
+        /// Apply the variables of `[profiles.<name>]` on top of `global_env`.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Do not print the performance summary or write metrics.
+        #[arg(long, conflicts_with_all = ["metrics_output", "benchmark_compare"])]
+        no_metrics: bool,
+        /// Tag the run with a label shown in the performance summary and recorded in the history.
+        #[arg(long, value_name = "TEXT")]
+        label: Option<String>,
+        /// Treat these exit codes (e.g. `1,2`) as success for every command.
+        #[arg(long, value_name = "CODES", value_delimiter = ',')]
+        ignore_exit_codes: Vec<i32>,
+        /// Send a desktop notification when a step is still running after this long (e.g. `60s`, `2m`).
+        #[arg(long, value_name = "DURATION", value_parser = notify::parse_duration)]
+        notify_on_long: Option<std::time::Duration>,
+        /// Print lines appended to this file while the script runs (waits for the file to appear).
+        #[arg(long, value_name = "FILE")]
+        tail: Option<String>,
+        /// Run only the Nth step (1-based) of the chain, as listed by --print-includes-order.
+        #[arg(long, value_name = "N")]
+        step: Option<usize>,
+        /// Run only these included scripts (and what they include); may be repeated.
+        #[arg(long, value_name = "NAME", action = ArgAction::Append, conflicts_with = "step")]
+        only: Vec<String>,
+        /// Bypass these included scripts and everything they include; may be repeated.
+        #[arg(long, value_name = "NAME", action = ArgAction::Append, conflicts_with = "step")]
+        skip: Vec<String>,
+        /// Kill a step and everything it started after this many seconds (overrides each script's `timeout`).
+        #[arg(long, value_name = "SECONDS")]
+        timeout: Option<u64>,
+        /// Retry a failing command up to N more times (overrides each script's `retries`).
+        #[arg(long, value_name = "N")]
+        retries: Option<u32>,
+        /// When a command fails, print a report with its exit code, captured output, env, and tool versions.
+        #[arg(long)]
+        explain_failure: bool,
+        /// Keep running the remaining includes and commands after a step fails, then fail with a summary.
+        #[arg(short = 'k', long)]
+        keep_going: bool,
+        /// Name the pipeline stage that failed when a piped bash/zsh command fails.
+        #[arg(long)]
+        pipefail_report: bool,
+        /// Print newline-delimited JSON progress events on stdout; command output goes to stderr.
+        #[arg(long, conflicts_with_all = ["quiet_success", "dry_run", "tail"])]
+        json_stream: bool,
+        /// With --json-stream, emit command output as `command_output` events.
+        #[arg(long, requires = "json_stream")]
+        include_output: bool,
+        /// Turn diagnostic warnings into errors.
+        #[arg(long)]
+        strict: bool,
+        /// Print what would be executed without running anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// With --dry-run, check that the program each command starts can be found on PATH.
+        #[arg(long, requires = "dry_run")]
+        verify_commands: bool,
+        /// Print how each command's environment differs from the environment cargo-script was started with.
+        #[arg(long)]
+        env_diff: bool,
+        /// Prefix every line of command output with the name of the script it came from.
+        #[arg(long)]
+        prefix: bool,
+        /// Run scripts without an interpreter with the shell in `$SHELL`, when it exists.
+        #[arg(long)]
+        use_shell_env: bool,
+        /// Show the values of variables matching secret patterns (`*_TOKEN`, `*_KEY`, ...) instead of `***`.
+        #[arg(long)]
+        show_secrets: bool,
+        /// Print the variables the script chain declares or references instead of running it.
+        #[arg(long)]
+        dump_env_schema: bool,
+        /// List the variables declared in `env`/`global_env` that no command of the chain references, instead of running it.
+        #[arg(long)]
+        report_unused_env: bool,
+        /// Print the order in which the script chain runs, one name per line, without running it.
+        #[arg(long)]
+        print_includes_order: bool,
+        /// Output format for diagnostic output.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+        /// Extra arguments appended to the script's command, given after `--`.
+        #[arg(last = true, value_name = "ARGS")]
+        args: Vec<String>,
     },
     #[command(about = "Initialize a Scripts.toml file in the current directory")]
-    Init,
+    Init {
+        /// Scaffold scripts from the project files detected in the current directory.
+        #[arg(long)]
+        from_existing: bool,
+        /// Prompt for each script to add and preview the file before writing it.
+        #[arg(long, conflicts_with = "from_existing")]
+        interactive: bool,
+        /// Replace an existing Scripts.toml without asking.
+        #[arg(long)]
+        force: bool,
+        /// Format of the scripts file to write: Scripts.toml, Scripts.yaml, or Scripts.json.
+        #[arg(long, value_enum, default_value_t = format::ScriptsFormat::Toml)]
+        format: format::ScriptsFormat,
+    },
     #[command(about = "Show all script names and descriptions defined in Scripts.toml")]
-    Show,
+    Show {
+        /// Show included scripts indented beneath the scripts that include them.
+        #[arg(long)]
+        tree: bool,
+        /// Only show scripts whose name or description contains this text.
+        #[arg(long)]
+        filter: Option<String>,
+        /// Only show scripts carrying this tag.
+        #[arg(long, value_name = "TAG")]
+        tag: Option<String>,
+        /// Output format; `json` lists every script with its command, interpreter, requirements, and includes.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table, conflicts_with = "tree")]
+        format: OutputFormat,
+    },
+    #[command(about = "Print a script's description, tags, includes, and long description")]
+    Info {
+        /// The name of the script to describe.
+        script: String,
+    },
+    #[command(about = "Print the script names, one per line, for piping into other tools")]
+    List,
+    #[command(about = "Print how scripts include each other, as a tree or a Graphviz graph")]
+    Graph {
+        /// Output format; `dot` prints a digraph for Graphviz.
+        #[arg(long, value_enum, default_value_t = graph::GraphFormat::Tree)]
+        format: graph::GraphFormat,
+    },
+    #[command(about = "Generate shell completions for cargo-script")]
+    Completions {
+        /// The shell to generate completions for, or `fig` for a Fig autocomplete spec.
+        #[arg(value_enum)]
+        shell: completions::CompletionShell,
+        /// Write the completion file into this directory instead of printing it.
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<String>,
+        /// Preview what would be generated without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the script names for shell completions.
+    #[command(name = "__complete_scripts", hide = true)]
+    CompleteScripts,
+    #[command(about = "Check the scripts file for problems without running anything")]
+    Validate {
+        /// Check that command shebangs agree with the `interpreter` field.
+        #[arg(long)]
+        check_shebang: bool,
+        /// Exit with an error when any problem is found.
+        #[arg(long)]
+        strict: bool,
+        /// Require script names to match `[settings] name_pattern` (default `^[a-z][a-z0-9:_-]*$`).
+        #[arg(long)]
+        strict_names: bool,
+    },
+    #[command(about = "Print a JSON Schema describing Scripts.toml for editors and validators")]
+    Schema,
+    #[command(about = "Print the SHA-256 hash of the scripts file for pinning")]
+    Hash,
+    #[command(about = "Show the most recent runs of the scripts file")]
+    History {
+        /// Maximum number of runs to show.
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    #[command(about = "List the interpreters used by scripts and whether they are available")]
+    Interpreters {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+    #[command(about = "List the tools and toolchains declared by all scripts, without checking them")]
+    Requirements {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Json)]
+        format: OutputFormat,
+    },
+    #[command(about = "Run a script's command repeatedly and print timing statistics")]
+    Bench {
+        /// The name of the script to benchmark.
+        script: String,
+        /// Number of measured runs.
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        runs: u32,
+        /// Number of runs before the measured ones, left out of the statistics.
+        #[arg(long, default_value_t = 0)]
+        warmup: u32,
+        /// Keep going when a run fails instead of stopping at the first failure.
+        #[arg(long)]
+        ignore_failures: bool,
+        /// Set an environment variable for each run (KEY=VALUE).
+        #[arg(short, long, value_name = "KEY=VALUE", action = ArgAction::Append)]
+        env: Vec<String>,
+    },
+    #[command(about = "Print the environment variables a script runs with, sorted by name")]
+    Env {
+        /// The name of the script whose environment is printed.
+        script: String,
+        /// Set an environment variable, as `run --env` would (KEY=VALUE).
+        #[arg(short, long, value_name = "KEY=VALUE", action = ArgAction::Append)]
+        env: Vec<String>,
+        /// Apply the variables of `[profiles.<name>]` on top of `global_env`.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Output format; `export` prints `export KEY=VALUE` lines for `eval`.
+        #[arg(long, value_enum, default_value_t = print_env::EnvFormat::Plain)]
+        format: print_env::EnvFormat,
+        /// Show the values of variables matching secret patterns instead of `***`.
+        #[arg(long)]
+        show_secrets: bool,
+    },
+    #[command(about = "Print every command a script runs, in order, as a tree")]
+    Explain {
+        /// The name of the script whose execution plan is printed.
+        script: String,
+        /// Set an environment variable, as `run --env` would (KEY=VALUE).
+        #[arg(short, long, value_name = "KEY=VALUE", action = ArgAction::Append)]
+        env: Vec<String>,
+        /// Apply the variables of `[profiles.<name>]` on top of `global_env`.
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+        /// Leave out the `pre<name>` and `post<name>` hooks, as `run --no-hooks` would.
+        #[arg(long)]
+        no_hooks: bool,
+        /// Resolve scripts without an interpreter to the shell in `$SHELL`, as `run --use-shell-env` would.
+        #[arg(long)]
+        use_shell_env: bool,
+        /// Show the values of variables matching secret patterns instead of `***`.
+        #[arg(long)]
+        show_secrets: bool,
+    },
+    #[command(about = "Run a script, then rerun it whenever watched files change")]
+    Watch {
+        /// The name of the script to run.
+        script: String,
+        /// Set an environment variable for each run (KEY=VALUE).
+        #[arg(short, long, value_name = "KEY=VALUE", action = ArgAction::Append)]
+        env: Vec<String>,
+        /// A file or directory to watch; may be repeated.
+        #[arg(long, value_name = "PATH", action = ArgAction::Append, default_value = ".")]
+        path: Vec<String>,
+        /// Clear the screen before each run (skipped when output isn't a terminal or colors are off).
+        #[arg(short, long)]
+        clear: bool,
+    },
 }
 
+pub mod analysis;
+pub mod artifacts;
+pub mod bench;
+pub mod cache;
+pub mod changed;
+pub mod completions;
+pub mod confirm;
+pub mod env_diff;
+pub mod env_file;
+pub mod env_schema;
+pub mod events;
+pub mod explain;
+pub mod format;
+pub mod graph;
+pub mod history;
 pub mod init;
+pub mod interpolate;
+pub mod integrity;
+pub mod interpreters;
+pub mod interrupt;
+pub mod isolate;
+pub mod log_file;
+pub mod metrics;
+pub mod requirements;
+pub mod notify;
+pub mod pipefail;
+pub mod plan;
+pub mod prefix;
+pub mod print_env;
+pub mod sandbox;
+pub mod schema;
+pub mod secrets;
 pub mod script;
-pub mod show;
\ No newline at end of file
+pub mod select;
+pub mod show;
+pub mod tail;
+pub mod timeout;
+pub mod umask;
+pub mod version;
+pub mod watch;
\ No newline at end of file
@@ -0,0 +1,80 @@
+//! This module provides desktop notifications for steps that run longer than expected.
+
+use std::{process::Command, sync::mpsc, thread, time::Duration};
+use colored::*;
+use emoji::symbols;
+
+/// Parse a duration such as `60s`, `2m`, `1h`, or `500ms`. A plain number is read as seconds.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().map_err(|_| format!("invalid duration `{}`, expected e.g. `60s`", value))?;
+    let seconds = match unit {
+        "" | "s" => number,
+        "ms" => number / 1000.0,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return Err(format!("invalid duration unit `{}`, expected ms, s, m, or h", unit)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("duration `{}` is out of range", value))
+}
+
+/// A timer that notifies when a step is still running after a threshold.
+///
+/// The timer runs on its own thread alongside the child process and is cancelled when dropped,
+/// so steps finishing in time never notify.
+pub struct LongRunTimer {
+    cancel: mpsc::Sender<()>,
+}
+
+impl LongRunTimer {
+    /// Start a timer for a step.
+    ///
+    /// # Arguments
+    ///
+    /// * `script_name` - The name of the step being timed.
+    /// * `threshold` - How long the step may run before notifying.
+    pub fn start(script_name: &str, threshold: Duration) -> Self {
+        let (cancel, cancelled) = mpsc::channel::<()>();
+        let script_name = script_name.to_string();
+        thread::spawn(move || {
+            if let Err(mpsc::RecvTimeoutError::Timeout) = cancelled.recv_timeout(threshold) {
+                let message = format!("[ {} ] is still running after {:.0?}", script_name, threshold);
+                eprintln!("{}  {}: {}", symbols::warning::WARNING.glyph, "Long run".yellow(), message);
+                send_notification("cargo-script", &message);
+            }
+        });
+        LongRunTimer { cancel }
+    }
+}
+
+impl Drop for LongRunTimer {
+    fn drop(&mut self) {
+        let _ = self.cancel.send(());
+    }
+}
+
+/// Show a desktop notification, if the platform has a way to.
+///
+/// Uses `notify-send` on Linux and `osascript` on macOS. Failures are ignored, since the
+/// message is also printed to stderr.
+fn send_notification(title: &str, message: &str) {
+    let _ = if cfg!(target_os = "macos") {
+        let script = format!("display notification {:?} with title {:?}", message, title);
+        Command::new("osascript").args(["-e", &script]).output()
+    } else {
+        Command::new("notify-send").args([title, message]).output()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        assert_eq!(parse_duration("99999999999999999999h"), Err("duration `99999999999999999999h` is out of range".to_string()));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+    }
+}
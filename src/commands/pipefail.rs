@@ -0,0 +1,86 @@
+//! This module reports which stage of a failed shell pipeline exited non-zero, for `run --pipefail-report`.
+
+use std::{fs, path::Path};
+
+/// Variable holding the path of the file the wrapped command writes pipeline statuses to.
+pub const STATUS_FILE_VAR: &str = "CARGO_SCRIPT_PIPESTATUS_FILE";
+
+/// Check whether an interpreter can report pipeline statuses.
+pub fn supports(interpreter: &str) -> bool {
+    matches!(interpreter, "bash" | "zsh")
+}
+
+/// Split a command line at its pipes, ignoring `||` and pipes inside quotes.
+fn pipe_stages(line: &str) -> Vec<&str> {
+    let bytes = line.as_bytes();
+    let (mut in_single, mut in_double) = (false, false);
+    let mut stages = Vec::new();
+    let mut start = 0;
+    for (i, &c) in bytes.iter().enumerate() {
+        match c {
+            b'\'' if !in_double => in_single = !in_single,
+            b'"' if !in_single => in_double = !in_double,
+            b'|' if !in_single && !in_double => {
+                let doubled = bytes.get(i + 1) == Some(&b'|') || (i > 0 && bytes[i - 1] == b'|');
+                if !doubled {
+                    stages.push(&line[start..i]);
+                    start = i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    stages.push(&line[start..]);
+    stages
+}
+
+/// Check whether a command contains at least one pipeline.
+pub fn has_pipeline(command: &str) -> bool {
+    command.lines().any(|line| pipe_stages(line).len() > 1)
+}
+
+/// Wrap a command so that it records the statuses of each pipeline that fails.
+///
+/// `pipefail` is not enabled, so the command passes or fails exactly as it does unwrapped. The
+/// statuses are appended as `<line> <status>...` to the file named by `STATUS_FILE_VAR`, where
+/// `<line>` is the line of the original command.
+///
+/// # Arguments
+///
+/// * `interpreter` - `bash` or `zsh`.
+/// * `command` - The command to wrap.
+pub fn wrap_command(interpreter: &str, command: &str) -> String {
+    // The header line is subtracted from `LINENO` so it points into the original command.
+    let (statuses, signal) = match interpreter {
+        "zsh" => ("pipestatus", "ZERR"),
+        _ => ("PIPESTATUS", "ERR"),
+    };
+    format!(
+        "trap '__cs_status=(\"${{{}[@]}}\"); echo \"$((LINENO - 1)) ${{__cs_status[*]}}\" >> \"${}\"' {}\n{}",
+        statuses, STATUS_FILE_VAR, signal, command
+    )
+}
+
+/// Describe the failed stage of the last failed pipeline recorded in `status_file`.
+///
+/// # Returns
+///
+/// A message such as `pipeline stage 2 (grep) failed with status 1 (statuses: 0 1 0)`, or `None`
+/// when no pipeline failed.
+pub fn read_report(status_file: &Path, command: &str) -> Option<String> {
+    let content = fs::read_to_string(status_file).ok()?;
+    let mut fields = content.lines().last()?.split_whitespace();
+    let line: usize = fields.next()?.parse().ok()?;
+    let statuses: Vec<i32> = fields.filter_map(|s| s.parse().ok()).collect();
+    if statuses.len() < 2 {
+        return None;
+    }
+    let (index, status) = statuses.iter().enumerate().find(|(_, status)| **status != 0)?;
+    let stages = command.lines().nth(line.checked_sub(1)?).map(pipe_stages).unwrap_or_default();
+    let program = stages.get(index).and_then(|stage| stage.split_whitespace().next());
+    let statuses: Vec<String> = statuses.iter().map(i32::to_string).collect();
+    Some(match program {
+        Some(program) => format!("pipeline stage {} ({}) failed with status {} (statuses: {})", index + 1, program, status, statuses.join(" ")),
+        None => format!("pipeline stage {} failed with status {} (statuses: {})", index + 1, status, statuses.join(" ")),
+    })
+}
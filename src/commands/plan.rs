@@ -0,0 +1,143 @@
+//! This module prints the resolved execution plan of a script as a tree, for `explain`.
+
+use std::{env, fs};
+use colored::*;
+use crate::commands::{env_diff::{env_diff, EnvChange}, secrets::SecretMask, show::includes};
+use crate::commands::script::{check_platform_command, child_env, describe_interpreter, get_final_env, resolve_command, script_command_lines, script_env_vars, script_hooks, script_toolchain, script_workdir, RunOptions, Scripts};
+use crate::error::CargoScriptError;
+
+/// A line of the plan tree, with the lines printed beneath it and its children.
+struct PlanNode {
+    label: String,
+    details: Vec<String>,
+    children: Vec<PlanNode>,
+}
+
+/// Print every command a script runs, in order, as a tree.
+///
+/// Each script is a node whose children are its `pre` hook, its includes, its own command, and
+/// its `post` hook, in the order `run` goes through them. Commands are leaves listing their
+/// interpreter, toolchain, workdir, and how their environment differs from the one cargo-script
+/// was started with. Nothing is executed.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script whose plan is printed.
+/// * `options` - The options controlling the run, with the overrides and profile to apply.
+///
+/// # Errors
+///
+/// This function will return an error if a script in the plan does not exist, has no command
+/// for the current OS, or includes itself, or if its environment cannot be resolved.
+pub fn print_plan(scripts: &Scripts, script_name: &str, options: &RunOptions) -> Result<(), CargoScriptError> {
+    let ambient_env: Vec<(String, String)> = env::vars().collect();
+    let plan = build_node(scripts, script_name, None, options, &ambient_env, &mut Vec::new())?;
+    print_node(&plan, "", "");
+    Ok(())
+}
+
+/// Build the plan of a script, with the hooks around it unless it is a hook itself.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script.
+/// * `hook` - `pre hook` or `post hook` when the script runs as a hook of its parent.
+/// * `options` - The options controlling the run.
+/// * `ambient_env` - The environment cargo-script was started with.
+/// * `path` - The scripts leading to this one, to detect cycles.
+fn build_node(
+    scripts: &Scripts,
+    script_name: &str,
+    hook: Option<&str>,
+    options: &RunOptions,
+    ambient_env: &[(String, String)],
+    path: &mut Vec<String>,
+) -> Result<PlanNode, CargoScriptError> {
+    if let Some(start) = path.iter().position(|name| name == script_name) {
+        let mut chain = path[start..].to_vec();
+        chain.push(script_name.to_string());
+        return Err(CargoScriptError::CircularInclude { chain });
+    }
+    let script = scripts.scripts.get(script_name)
+        .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+    check_platform_command(script_name, script)?;
+
+    let label = match hook {
+        Some(hook) => format!("{} {}", script_name.bold().cyan(), format!("({})", hook).dimmed()),
+        None => script_name.bold().cyan().to_string(),
+    };
+    let (pre, post) = if hook.is_none() { script_hooks(scripts, script_name, options) } else { (None, None) };
+    let mut children = Vec::new();
+    path.push(script_name.to_string());
+    if let Some(pre) = pre {
+        children.push(build_node(scripts, &pre, Some("pre hook"), options, ambient_env, path)?);
+    }
+    for include_script in includes(script) {
+        children.push(build_node(scripts, include_script, None, options, ambient_env, path)?);
+    }
+    if let Some(command) = resolve_command(script_name, script, options.base_dir())? {
+        let lines = script_command_lines(script).map_or_else(|| command.lines().count(), <[String]>::len);
+        let first = script_command_lines(script).and_then(|lines| lines.first().map(String::as_str))
+            .unwrap_or_else(|| command.lines().next().unwrap_or_default());
+        let more = if lines > 1 { format!(" (+{} more line(s))", lines - 1).dimmed().to_string() } else { String::new() };
+        children.push(PlanNode {
+            label: format!("{} {}{}", "$".dimmed(), first.trim().green(), more),
+            details: command_details(scripts, script_name, options, ambient_env)?,
+            children: Vec::new(),
+        });
+    }
+    if let Some(post) = post {
+        children.push(build_node(scripts, &post, Some("post hook"), options, ambient_env, path)?);
+    }
+    path.pop();
+    Ok(PlanNode { label, details: Vec::new(), children })
+}
+
+/// Describe how a script's command is run: its interpreter, toolchain, workdir, and env changes.
+fn command_details(scripts: &Scripts, script_name: &str, options: &RunOptions, ambient_env: &[(String, String)]) -> Result<Vec<String>, CargoScriptError> {
+    let script = &scripts.scripts[script_name];
+    let mut details = vec![format!("{}: {}", "Interpreter".yellow(), describe_interpreter(scripts, script, options))];
+    if let Some((toolchain, source)) = script_toolchain(scripts, script) {
+        details.push(format!("{}: {} ({})", "Toolchain".yellow(), toolchain, source));
+    }
+    let workdir = script_workdir(script).map(|dir| options.resolve_path(dir)).unwrap_or_else(|| options.base_dir().to_path_buf());
+    let workdir = fs::canonicalize(&workdir).unwrap_or(workdir);
+    let state = if workdir.is_dir() { "" } else { " (not found)" };
+    details.push(format!("{}: {}{}", "Workdir".yellow(), workdir.display(), state));
+
+    let final_env = get_final_env(&script_env_vars(scripts, script, options)?, &options.env_overrides);
+    let diff = env_diff(ambient_env, &child_env(scripts, ambient_env, &final_env));
+    let mask = SecretMask::new(scripts, options.show_secrets);
+    let mut removed = 0;
+    for (key, change) in &diff {
+        match change {
+            EnvChange::Added { value } => details.push(format!("{} {} = {}", "+".green(), key, mask.apply(key, value))),
+            EnvChange::Changed { old, new } => details.push(format!("{} {}: {} -> {}", "~".yellow(), key, mask.apply(key, old), mask.apply(key, new))),
+            EnvChange::Removed => removed += 1,
+        }
+    }
+    // `env_passthrough` can drop most of the environment, so the dropped names are only counted.
+    if removed > 0 {
+        details.push(format!("{} {} inherited variable(s) not passed through", "-".red(), removed));
+    }
+    Ok(details)
+}
+
+/// Print a node of the plan with tree connectors, then its details and children.
+fn print_node(node: &PlanNode, prefix: &str, connector: &str) {
+    println!("{}{}{}", prefix, connector, node.label);
+    let child_prefix = match connector {
+        "" => prefix.to_string(),
+        "└─ " => format!("{}   ", prefix),
+        _ => format!("{}│  ", prefix),
+    };
+    for detail in &node.details {
+        println!("{}  {}", child_prefix, detail);
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        let connector = if i + 1 == node.children.len() { "└─ " } else { "├─ " };
+        print_node(child, &child_prefix, connector);
+    }
+}
@@ -0,0 +1,47 @@
+//! This module reprints command output line by line with the name of the script it came from,
+//! for `run --prefix`.
+
+use std::{hash::{DefaultHasher, Hash, Hasher}, io::{self, BufRead, BufReader, Read, Write}, thread};
+use colored::*;
+
+/// Colors the prefixes cycle through, picked by script name so a script keeps its color.
+const PREFIX_COLORS: [Color; 6] = [Color::Cyan, Color::Magenta, Color::Blue, Color::Green, Color::Yellow, Color::BrightCyan];
+
+/// Return the colored `[script]` prefix of a script's output lines.
+pub fn prefix(script: &str) -> ColoredString {
+    let mut hasher = DefaultHasher::new();
+    script.hash(&mut hasher);
+    let color = PREFIX_COLORS[(hasher.finish() % PREFIX_COLORS.len() as u64) as usize];
+    format!("[{}]", script).color(color)
+}
+
+/// Read a child's output pipe on a separate thread, reprinting each line with the script's
+/// prefix as soon as it arrives.
+///
+/// Each line is written whole, so lines of one stream keep their order and are never split by
+/// output from other threads.
+///
+/// # Arguments
+///
+/// * `pipe` - The child's stdout or stderr.
+/// * `script` - The name of the script the command belongs to.
+/// * `to_stderr` - Whether to write to stderr instead of stdout.
+pub fn prefix_lines<R: Read + Send + 'static>(pipe: Option<R>, script: &str, to_stderr: bool) -> thread::JoinHandle<Vec<String>> {
+    let prefix = prefix(script);
+    thread::spawn(move || {
+        let Some(pipe) = pipe else { return Vec::new() };
+        let mut reader = BufReader::new(pipe);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line).is_ok_and(|read| read > 0) {
+            let text = String::from_utf8_lossy(&line);
+            let text = text.trim_end_matches(['\n', '\r']);
+            let _ = if to_stderr {
+                writeln!(io::stderr().lock(), "{} {}", prefix, text)
+            } else {
+                writeln!(io::stdout().lock(), "{} {}", prefix, text)
+            };
+            line.clear();
+        }
+        Vec::new()
+    })
+}
@@ -0,0 +1,50 @@
+//! This module prints the environment a script runs with, for `env`.
+
+use std::collections::BTreeMap;
+use clap::ValueEnum;
+use crate::commands::{secrets::SecretMask, script::{get_final_env, quote_arg, script_env_vars, RunOptions, Scripts}};
+use crate::error::CargoScriptError;
+
+/// Output format of the `env` command.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EnvFormat {
+    /// `KEY=VALUE` lines, with values as they are.
+    #[default]
+    Plain,
+    /// `export KEY=VALUE` lines with quoted values, for `eval` in a POSIX shell.
+    Export,
+}
+
+/// Print the variables a script's command is run with, sorted by name.
+///
+/// The variables are layered like in `run`: the top-level `env_file`, `global_env`, the
+/// selected profile, the script's `env_file` and `env`, then the `--env` overrides. The
+/// inherited process environment is not listed. Secret values are masked unless
+/// `options.show_secrets` is set.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script whose environment is printed.
+/// * `options` - The options controlling the run, with the overrides and profile to apply.
+/// * `format` - The output format.
+///
+/// # Errors
+///
+/// This function will return an error if the script does not exist, an env file is missing or
+/// malformed, or the selected profile does not exist.
+pub fn print_env(scripts: &Scripts, script_name: &str, options: &RunOptions, format: EnvFormat) -> Result<(), CargoScriptError> {
+    let script = scripts.scripts.get(script_name)
+        .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+    let env_vars = script_env_vars(scripts, script, options)?;
+    let final_env: BTreeMap<String, String> = get_final_env(&env_vars, &options.env_overrides).into_iter().collect();
+    let mask = SecretMask::new(scripts, options.show_secrets);
+    for (key, value) in &final_env {
+        let value = mask.apply(key, value);
+        match format {
+            EnvFormat::Plain => println!("{}={}", key, value),
+            EnvFormat::Export => println!("export {}={}", key, quote_arg("sh", value)),
+        }
+    }
+    Ok(())
+}
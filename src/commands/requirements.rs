@@ -0,0 +1,132 @@
+//! This module lists the tools and toolchains declared by scripts, without probing the host.
+
+use std::collections::BTreeMap;
+use crate::commands::{OutputFormat, script::{script_toolchain, Requirement, Script, Scripts}};
+use colored::*;
+use serde::Serialize;
+
+/// A tool as written in `requires`, split into its name and optional version.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ToolSpec {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl ToolSpec {
+    /// Split `docker`, `rustup 1.27`, or `docker>=19.03` into the tool and its version constraint.
+    pub(crate) fn parse(requirement: &str) -> Self {
+        let requirement = requirement.trim();
+        match requirement.find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '=' | '~' | '^')) {
+            Some(split) => ToolSpec {
+                name: requirement[..split].to_string(),
+                version: Some(requirement[split..].trim().to_string()),
+            },
+            None => ToolSpec { name: requirement.to_string(), version: None },
+        }
+    }
+}
+
+impl std::fmt::Display for ToolSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{} {}", self.name, version),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+/// A single declared need. `all_of` groups are split into their tools.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Need {
+    Tool(ToolSpec),
+    Toolchain(String),
+    AnyOf(Vec<ToolSpec>),
+}
+
+impl std::fmt::Display for Need {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Need::Tool(tool) => write!(f, "{}", tool),
+            Need::Toolchain(toolchain) => write!(f, "toolchain {}", toolchain),
+            Need::AnyOf(tools) => {
+                let tools: Vec<String> = tools.iter().map(ToolSpec::to_string).collect();
+                write!(f, "any of ({})", tools.join(" | "))
+            }
+        }
+    }
+}
+
+/// A deduplicated need with the scripts declaring it.
+#[derive(Serialize, Debug)]
+pub struct RequirementInfo {
+    #[serde(flatten)]
+    pub need: Need,
+    pub required_by: Vec<String>,
+}
+
+/// Aggregate the `requires` and `toolchain` declarations of every script.
+///
+/// Nothing is probed; the result only reflects the scripts file.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+pub fn collect_requirements(scripts: &Scripts) -> Vec<RequirementInfo> {
+    let mut required_by: BTreeMap<Need, Vec<String>> = BTreeMap::new();
+    for (name, script) in &scripts.scripts {
+        let requires = match script {
+            Script::Default(_) => &None,
+            Script::Inline { requires, .. } | Script::CILike { requires, .. } => requires,
+        };
+        let mut needs = Vec::new();
+        for requirement in requires.iter().flatten() {
+            match requirement {
+                Requirement::Tool(tool) => needs.push(Need::Tool(ToolSpec::parse(tool))),
+                Requirement::AllOf { all_of } => needs.extend(all_of.iter().map(|tool| Need::Tool(ToolSpec::parse(tool)))),
+                Requirement::AnyOf { any_of } => needs.push(Need::AnyOf(any_of.iter().map(|tool| ToolSpec::parse(tool)).collect())),
+            }
+        }
+        if let Some((toolchain, _)) = script_toolchain(scripts, script) {
+            needs.push(Need::Toolchain(toolchain.to_string()));
+        }
+        for need in needs {
+            let names = required_by.entry(need).or_default();
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+
+    required_by.into_iter().map(|(need, mut scripts)| {
+        scripts.sort();
+        RequirementInfo { need, required_by: scripts }
+    }).collect()
+}
+
+/// Print the aggregated requirements as JSON or as a table.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `format` - The output format.
+pub fn list_requirements(scripts: &Scripts, format: OutputFormat) {
+    let requirements = collect_requirements(scripts);
+
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&requirements).expect("Failed to serialize requirements"));
+        return;
+    }
+
+    if requirements.is_empty() {
+        println!("No requirements declared");
+        return;
+    }
+    let needs: Vec<String> = requirements.iter().map(|r| r.need.to_string()).collect();
+    let width = needs.iter().map(String::len).max().unwrap_or(0).max("Requirement".len()) + 2;
+    println!("{:<width$} {}", "Requirement".yellow(), "Required by".yellow(), width = width);
+    println!("{}", "-".repeat(width + 40).yellow());
+    for (requirement, need) in requirements.iter().zip(&needs) {
+        println!("{:<width$} {}", need.green(), requirement.required_by.join(", "), width = width);
+    }
+}
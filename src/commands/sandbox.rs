@@ -0,0 +1,82 @@
+//! This module provides a best-effort, Linux-only sandbox for script commands built on `unshare`.
+//!
+//! Sandboxing is strictly opt-in: it only applies when `run --sandbox` is passed. Each command then
+//! runs in new user and mount namespaces, and in a new network namespace unless the script's
+//! `sandbox` table allows network access. Paths listed in `readonly` are remounted read-only
+//! inside the namespace before the command starts.
+
+use std::process::Command;
+use colored::*;
+use emoji::symbols;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use crate::commands::script::find_in_path;
+
+/// Per-script sandbox settings, read from the `sandbox` table of a script.
+#[derive(Deserialize, JsonSchema, Debug, Clone, Default)]
+pub struct Sandbox {
+    /// Whether the command may use the network. Defaults to `false`.
+    #[serde(default)]
+    pub network: bool,
+    /// Paths remounted read-only for the command.
+    #[serde(default)]
+    pub readonly: Vec<String>,
+}
+
+impl Sandbox {
+    /// Return a one-line description of the settings, used by dry-run.
+    pub fn describe(&self) -> String {
+        let network = if self.network { "on" } else { "off" };
+        if self.readonly.is_empty() {
+            format!("network {}", network)
+        } else {
+            format!("network {}, read-only {}", network, self.readonly.join(", "))
+        }
+    }
+}
+
+/// Wrap a command so it runs inside the sandbox.
+///
+/// Read-only paths that are not mount points yet are bind-mounted onto themselves first, since
+/// only mounts can be remounted read-only.
+/// The original program and arguments are passed through as positional parameters of a small
+/// `sh` wrapper, so they are never re-quoted. On platforms other than Linux, or when `unshare`
+/// is not installed, a warning is printed and the command is returned unchanged.
+///
+/// # Arguments
+///
+/// * `cmd` - The command to wrap.
+/// * `sandbox` - The sandbox settings to apply.
+pub fn wrap_command(cmd: Command, sandbox: &Sandbox) -> Command {
+    if !cfg!(target_os = "linux") {
+        eprintln!("{}  {}: sandboxing is only supported on Linux, running without it", symbols::warning::WARNING.glyph, "Sandbox".yellow());
+        return cmd;
+    }
+    if find_in_path("unshare").is_none() {
+        eprintln!("{}  {}: `unshare` was not found, running without it", symbols::warning::WARNING.glyph, "Sandbox".yellow());
+        return cmd;
+    }
+
+    let mut setup: Vec<String> = sandbox.readonly.iter()
+        .map(|path| {
+            let path = shell_quote(path);
+            format!("{{ mountpoint -q {path} || mount --bind {path} {path}; }} && mount -o remount,bind,ro {path}")
+        })
+        .collect();
+    setup.push("exec \"$@\"".to_string());
+
+    let mut wrapped = Command::new("unshare");
+    wrapped.args(["--user", "--map-root-user", "--mount", "--propagation", "private"]);
+    if !sandbox.network {
+        wrapped.arg("--net");
+    }
+    wrapped.args(["--", "sh", "-c", &setup.join(" && "), "cargo-script-sandbox"]);
+    wrapped.arg(cmd.get_program());
+    wrapped.args(cmd.get_args());
+    wrapped
+}
+
+/// Quote a string for use as a single `sh` word.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
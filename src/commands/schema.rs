@@ -0,0 +1,13 @@
+//! This module provides the functionality to print a JSON Schema describing `Scripts.toml`.
+//!
+//! The schema is generated from the same structs used to deserialize the file, so it stays in
+//! sync with the accepted fields.
+
+use schemars::schema_for;
+use crate::commands::script::Scripts;
+
+/// Print the JSON Schema of the scripts file.
+pub fn print_schema() {
+    let schema = schema_for!(Scripts);
+    println!("{}", serde_json::to_string_pretty(&schema).expect("Failed to serialize schema"));
+}
@@ -1,43 +1,889 @@
 //! This module provides the functionality to run scripts defined in `Scripts.toml`.
 
-use std::{collections::HashMap, env, process::{Command, Stdio}, sync::{Arc, Mutex}, time::{Duration, Instant}};
-use serde::Deserialize;
+use std::{borrow::Cow, collections::{hash_map::Entry, BTreeMap, HashMap}, env, fs, io::{self, IsTerminal, Read, Write}, path::{Path, PathBuf}, process::{Command, ExitStatus, Stdio}, sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex}, thread, time::{Duration, Instant}};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use emoji::symbols;
 use colored::*;
+use unicode_width::UnicodeWidthStr;
+use crate::commands::{OutputFormat, analysis, cache, changed::ChangedFiles, confirm::confirm, format::{self, ScriptsFormat}, env_file::load_env_file, env_diff::{env_diff, format_env_diff}, events::{self, Event}, explain::{self, FailureReport}, interpolate::interpolate, interrupt, isolate::create_isolated_dir, log_file::{self, LogFile}, metrics::{slow_scripts, write_metrics, Metrics}, notify::LongRunTimer, pipefail, prefix, requirements::ToolSpec, sandbox::{self, Sandbox}, secrets::SecretMask, show, tail::LogTailer, timeout, umask, version};
+use crate::error::CargoScriptError;
 
 /// Enum representing a script, which can be either a default command or a detailed script with additional metadata.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, JsonSchema, Debug)]
 #[serde(untagged)]
 pub enum Script {
     Default(String),
-    Inline {
-        command: Option<String>,
-        requires: Option<Vec<String>>,
+    // Tried first, since an `Inline` script would take the `script` key of a `CILike` one as unknown.
+    CILike {
+        script: String,
+        #[serde(default, deserialize_with = "deserialize_command")]
+        #[schemars(with = "Option<CommandValue>")]
+        command: Option<ScriptCommand>,
+        command_file: Option<String>,
+        requires: Option<Vec<Requirement>>,
+        #[serde(default, deserialize_with = "deserialize_toolchain")]
+        #[schemars(with = "Option<ToolchainValue>")]
         toolchain: Option<String>,
+        #[serde(alias = "description", alias = "desc")]
         info: Option<String>,
+        long_info: Option<String>,
+        #[serde(default, deserialize_with = "deserialize_env")]
+        #[schemars(with = "Option<HashMap<String, EnvValue>>")]
         env: Option<HashMap<String, String>>,
         include: Option<Vec<String>>,
+        tags: Option<Vec<String>>,
         interpreter: Option<String>,
+        sandbox: Option<Sandbox>,
+        success_codes: Option<Vec<i32>>,
+        timeout: Option<u64>,
+        retries: Option<u32>,
+        retry_delay: Option<u64>,
+        confirm: Option<Confirmation>,
+        stdin: Option<String>,
+        umask: Option<String>,
+        shell_args: Option<Vec<String>>,
+        workdir: Option<String>,
+        env_file: Option<String>,
+        inputs: Option<Vec<String>>,
+        outputs: Option<Vec<String>>,
+        windows: Option<String>,
+        macos: Option<String>,
+        linux: Option<String>,
+        #[serde(default, deserialize_with = "deserialize_exit_codes")]
+        #[schemars(with = "Option<HashMap<String, String>>")]
+        exit_codes: Option<HashMap<i32, String>>,
+        /// Keys that match no field, reported by [`analysis::check_unknown_fields`].
+        #[serde(flatten)]
+        #[schemars(skip)]
+        unknown: BTreeMap<String, serde_json::Value>,
     },
-    CILike {
-        script: String,
-        command: Option<String>,
-        requires: Option<Vec<String>>,
+    Inline {
+        #[serde(default, deserialize_with = "deserialize_command")]
+        #[schemars(with = "Option<CommandValue>")]
+        command: Option<ScriptCommand>,
+        command_file: Option<String>,
+        requires: Option<Vec<Requirement>>,
+        #[serde(default, deserialize_with = "deserialize_toolchain")]
+        #[schemars(with = "Option<ToolchainValue>")]
         toolchain: Option<String>,
+        #[serde(alias = "description", alias = "desc")]
         info: Option<String>,
+        long_info: Option<String>,
+        #[serde(default, deserialize_with = "deserialize_env")]
+        #[schemars(with = "Option<HashMap<String, EnvValue>>")]
         env: Option<HashMap<String, String>>,
         include: Option<Vec<String>>,
+        tags: Option<Vec<String>>,
         interpreter: Option<String>,
+        sandbox: Option<Sandbox>,
+        success_codes: Option<Vec<i32>>,
+        timeout: Option<u64>,
+        retries: Option<u32>,
+        retry_delay: Option<u64>,
+        confirm: Option<Confirmation>,
+        stdin: Option<String>,
+        umask: Option<String>,
+        shell_args: Option<Vec<String>>,
+        workdir: Option<String>,
+        env_file: Option<String>,
+        inputs: Option<Vec<String>>,
+        outputs: Option<Vec<String>>,
+        windows: Option<String>,
+        macos: Option<String>,
+        linux: Option<String>,
+        #[serde(default, deserialize_with = "deserialize_exit_codes")]
+        #[schemars(with = "Option<HashMap<String, String>>")]
+        exit_codes: Option<HashMap<i32, String>>,
+        /// Keys that match no field, reported by [`analysis::check_unknown_fields`].
+        #[serde(flatten)]
+        #[schemars(skip)]
+        unknown: BTreeMap<String, serde_json::Value>,
+    }
+}
+
+/// A `command` as written: a single string, or an array of lines run in one shell session.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum CommandValue {
+    Single(String),
+    Lines(Vec<String>),
+}
+
+/// A script's `command`, keeping the lines of an array apart so they can be joined for the
+/// interpreter that runs them.
+#[derive(Debug)]
+pub struct ScriptCommand {
+    /// The command, with the lines of an array joined by newlines.
+    pub text: String,
+    /// The lines of an array, which run in order and stop at the first failure.
+    pub lines: Option<Vec<String>>,
+}
+
+/// Deserialize a `command` written as a string or as an array of lines.
+fn deserialize_command<'de, D>(deserializer: D) -> Result<Option<ScriptCommand>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<CommandValue>::deserialize(deserializer)?.map(|command| match command {
+        CommandValue::Single(text) => ScriptCommand { text, lines: None },
+        CommandValue::Lines(lines) => ScriptCommand { text: lines.join("\n"), lines: Some(lines) },
+    }))
+}
+
+/// A requirement listed in `requires`: a single tool, or a group of alternatives or tools.
+///
+/// Plain strings are always required. `{ any_of = [...] }` passes if any listed tool is present,
+/// and `{ all_of = [...] }` needs every listed tool.
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+#[serde(untagged)]
+pub enum Requirement {
+    Tool(String),
+    AnyOf { any_of: Vec<String> },
+    AllOf { all_of: Vec<String> },
+}
+
+impl std::fmt::Display for Requirement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Requirement::Tool(tool) => write!(f, "{}", tool),
+            Requirement::AnyOf { any_of } => write!(f, "any of ({})", any_of.join(" | ")),
+            Requirement::AllOf { all_of } => write!(f, "all of ({})", all_of.join(", ")),
+        }
+    }
+}
+
+/// An environment variable value as written in TOML.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum EnvValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+impl From<EnvValue> for String {
+    fn from(value: EnvValue) -> Self {
+        match value {
+            EnvValue::String(s) => s,
+            EnvValue::Integer(i) => i.to_string(),
+            // Keep `1.0` as written instead of Rust's `1`.
+            EnvValue::Float(f) if f.fract() == 0.0 && f.is_finite() => format!("{:.1}", f),
+            EnvValue::Float(f) => f.to_string(),
+            EnvValue::Boolean(b) => b.to_string(),
+        }
+    }
+}
+
+/// Deserialize an environment table, coercing integers, floats, and booleans to strings.
+///
+/// This lets `env = { PORT = 8080, DEBUG = true }` be written without quoting the values.
+fn deserialize_env<'de, D>(deserializer: D) -> Result<Option<HashMap<String, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let env: Option<HashMap<String, EnvValue>> = Option::deserialize(deserializer)?;
+    Ok(env.map(|env| env.into_iter().map(|(key, value)| (key, value.into())).collect()))
+}
+
+/// A `confirm` setting: `true` to ask before running the script, or the question to ask.
+#[derive(Deserialize, JsonSchema, Debug)]
+#[serde(untagged)]
+pub enum Confirmation {
+    Enabled(bool),
+    Prompt(String),
+}
+
+/// A `toolchain` as written in TOML: a toolchain name, or `false` to opt out of `default_toolchain`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum ToolchainValue {
+    Name(String),
+    Disabled(bool),
+}
+
+/// Deserialize a `toolchain`, turning `false` into an empty name.
+///
+/// An empty toolchain opts the script out of `default_toolchain`.
+fn deserialize_toolchain<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    match Option::<ToolchainValue>::deserialize(deserializer)? {
+        Some(ToolchainValue::Name(name)) => Ok(Some(name)),
+        Some(ToolchainValue::Disabled(false)) => Ok(Some(String::new())),
+        Some(ToolchainValue::Disabled(true)) => Err(serde::de::Error::custom("toolchain must be a toolchain name or `false`")),
+        None => Ok(None),
+    }
+}
+
+/// Deserialize an `exit_codes` table, whose keys are exit codes written as TOML keys.
+///
+/// This lets `exit_codes = { 2 = "config invalid" }` be written with bare integer keys, which are
+/// strings in TOML and JSON but integers in YAML.
+fn deserialize_exit_codes<'de, D>(deserializer: D) -> Result<Option<HashMap<i32, String>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize, PartialEq, Eq, Hash)]
+    #[serde(untagged)]
+    enum Code {
+        Int(i64),
+        Str(String),
     }
+
+    let codes: Option<HashMap<Code, String>> = Option::deserialize(deserializer)?;
+    codes.map(|codes| {
+        codes.into_iter().map(|(code, meaning)| {
+            let code = match code {
+                Code::Int(code) => code.to_string(),
+                Code::Str(code) => code,
+            };
+            code.trim().parse::<i32>()
+                .map(|code| (code, meaning))
+                .map_err(|_| serde::de::Error::custom(format!("invalid exit code `{}` in exit_codes", code)))
+        }).collect()
+    }).transpose()
+}
+
+/// The `[settings]` table, holding options that apply to the whole scripts file.
+#[derive(Deserialize, JsonSchema, Debug, Default)]
+pub struct Settings {
+    /// Extra variable name patterns (e.g. `*_CREDENTIALS`) whose values are masked when printed.
+    #[serde(default)]
+    pub secret_patterns: Vec<String>,
+    /// The toolchain for scripts without a `toolchain` field.
+    pub default_toolchain: Option<String>,
+    /// Arguments passed to the shell before its command flag (e.g. `["-e"]`), for scripts without `shell_args`.
+    pub default_shell_args: Option<Vec<String>>,
+    /// A regular expression every script name must match, checked by `validate`.
+    pub name_pattern: Option<String>,
+    /// Run scripts without an interpreter with the shell in `$SHELL`, when it exists.
+    #[serde(default)]
+    pub use_shell_env: bool,
+    /// Prefix every line of command output with the name of the script it came from.
+    #[serde(default)]
+    pub prefix_output: bool,
+    /// Seconds after which a script is flagged as slow in the performance summary.
+    pub slow_threshold: Option<f64>,
+}
+
+/// State shared while merging the imports of a scripts file.
+struct ImportMerge {
+    /// The file each script name was first defined in.
+    origins: HashMap<String, String>,
+    /// The file each `global_env` variable was first set in.
+    env_origins: HashMap<String, String>,
+    /// The files being imported, from the main file down, with their names for messages.
+    stack: Vec<(PathBuf, String)>,
+    /// The files already merged.
+    merged: Vec<PathBuf>,
+    warnings: Vec<String>,
+}
+
+/// Return the canonical form of a path, or the path itself if it cannot be resolved.
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// A `[profiles.<name>]` table, selected at runtime with `--profile <name>`.
+#[derive(Deserialize, JsonSchema, Debug, Default, Clone)]
+pub struct Profile {
+    /// Variables applied on top of `global_env`, below each script's own `env`.
+    #[serde(default, deserialize_with = "deserialize_env")]
+    #[schemars(with = "Option<HashMap<String, EnvValue>>")]
+    pub env: Option<HashMap<String, String>>,
 }
 
 /// Struct representing the collection of scripts defined in Scripts.toml.
-#[derive(Deserialize)]
+#[derive(Deserialize, JsonSchema)]
 pub struct Scripts {
+    /// The interpreter for scripts without an `interpreter` field, instead of `sh` (`cmd` on Windows).
+    pub default_interpreter: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_env")]
+    #[schemars(with = "Option<HashMap<String, EnvValue>>")]
     pub global_env: Option<HashMap<String, String>>,
+    /// A `.env` file whose variables apply to every script, below `global_env`.
+    pub env_file: Option<String>,
+    /// Named sets of variables, such as `dev` or `prod`, selected with `--profile`.
+    pub profiles: Option<HashMap<String, Profile>>,
+    #[serde(default)]
+    pub settings: Settings,
+    /// Ambient variables (glob patterns) commands inherit; when unset, they inherit everything.
+    #[serde(default)]
+    pub env_passthrough: Option<Vec<String>>,
+    /// Partial files whose `[scripts]` are merged into this file at load time.
+    #[serde(default)]
+    pub import: Vec<String>,
+    #[serde(default)]
     pub scripts: HashMap<String, Script>
 }
 
+impl Scripts {
+    /// Return the interpreter for scripts without an `interpreter` field.
+    pub fn shell(&self) -> &str {
+        self.default_interpreter.as_deref().unwrap_or(default_interpreter())
+    }
+
+    /// Return the variables of the profile selected with `--profile`, or none without one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error listing the defined profiles if the profile does not exist.
+    pub fn profile_env(&self, profile: Option<&str>) -> Result<HashMap<String, String>, CargoScriptError> {
+        let Some(name) = profile else {
+            return Ok(HashMap::new());
+        };
+        match self.profiles.as_ref().and_then(|profiles| profiles.get(name)) {
+            Some(profile) => Ok(profile.env.clone().unwrap_or_default()),
+            None => {
+                let mut available: Vec<String> = self.profiles.iter().flatten().map(|(name, _)| name.clone()).collect();
+                available.sort();
+                Err(CargoScriptError::ProfileNotFound { profile: name.to_string(), available })
+            }
+        }
+    }
+
+    /// Add the profiles of another file that are not defined yet, returning the names of those
+    /// that were already defined.
+    fn merge_profiles(&mut self, profiles: Option<HashMap<String, Profile>>) -> Vec<String> {
+        let mut names: Vec<(String, Profile)> = profiles.unwrap_or_default().into_iter().collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut duplicates = Vec::new();
+        for (name, profile) in names {
+            match self.profiles.get_or_insert_with(HashMap::new).entry(name) {
+                Entry::Occupied(entry) => duplicates.push(entry.key().clone()),
+                Entry::Vacant(entry) => { entry.insert(profile); }
+            }
+        }
+        duplicates
+    }
+
+    /// Load every scripts file of a directory, in lexicographic order, and merge them.
+    ///
+    /// Each file's `import` list is resolved relative to that file. Script names must be unique
+    /// across files. `global_env` tables are merged, and a variable set by several files keeps
+    /// its first value. Other top-level settings come from the first file that sets them, and
+    /// `secret_patterns` are combined.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The scripts directory.
+    ///
+    /// # Returns
+    ///
+    /// The merged scripts, and a warning for each setting that was ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a file cannot be read or parsed, or if two files
+    /// define the same script.
+    pub fn load_dir(dir: &Path) -> Result<(Scripts, Vec<String>), CargoScriptError> {
+        let mut merged: Option<Scripts> = None;
+        let mut origins: HashMap<String, String> = HashMap::new();
+        let mut warnings = Vec::new();
+
+        for path in scripts_dir_files(dir)? {
+            let file = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            let import_failed = |message: String| CargoScriptError::ImportFailed { path: path.display().to_string(), message };
+            let content = fs::read_to_string(&path).map_err(|e| import_failed(e.to_string()))?;
+            let mut partial: Scripts = ScriptsFormat::from_path(&path).parse(&content).map_err(import_failed)?;
+            warnings.extend(partial.merge_imports(&path.display().to_string())?);
+
+            let mut names: Vec<&String> = partial.scripts.keys().collect();
+            names.sort();
+            for name in names {
+                if let Some(first) = origins.insert(name.clone(), file.clone()) {
+                    return Err(CargoScriptError::DuplicateScript { script: name.clone(), first, second: file });
+                }
+            }
+            let Some(scripts) = merged.as_mut() else {
+                merged = Some(partial);
+                continue;
+            };
+
+            let mut ignored = |setting: &str| warnings.push(format!("{} from {} is already set by an earlier file, keeping that one", setting, file));
+            for (key, value) in partial.global_env.unwrap_or_default() {
+                let global_env = scripts.global_env.get_or_insert_with(HashMap::new);
+                match global_env.get(&key) {
+                    Some(existing) if *existing != value => ignored(&format!("global_env.{}", key)),
+                    Some(_) => {}
+                    None => { global_env.insert(key, value); }
+                }
+            }
+            keep_first(&mut scripts.default_interpreter, partial.default_interpreter, "default_interpreter", &mut ignored);
+            keep_first(&mut scripts.env_file, partial.env_file, "env_file", &mut ignored);
+            keep_first(&mut scripts.env_passthrough, partial.env_passthrough, "env_passthrough", &mut ignored);
+            keep_first(&mut scripts.settings.default_toolchain, partial.settings.default_toolchain, "settings.default_toolchain", &mut ignored);
+            keep_first(&mut scripts.settings.name_pattern, partial.settings.name_pattern, "settings.name_pattern", &mut ignored);
+            keep_first(&mut scripts.settings.default_shell_args, partial.settings.default_shell_args, "settings.default_shell_args", &mut ignored);
+            keep_first(&mut scripts.settings.slow_threshold, partial.settings.slow_threshold, "settings.slow_threshold", &mut ignored);
+            for name in scripts.merge_profiles(partial.profiles) {
+                ignored(&format!("profiles.{}", name));
+            }
+            scripts.settings.secret_patterns.extend(partial.settings.secret_patterns);
+            scripts.settings.use_shell_env |= partial.settings.use_shell_env;
+            scripts.settings.prefix_output |= partial.settings.prefix_output;
+            scripts.scripts.extend(partial.scripts);
+        }
+
+        let scripts = merged.ok_or_else(|| CargoScriptError::ImportFailed {
+            path: dir.display().to_string(),
+            message: "the directory contains no .toml, .yaml, or .json files".to_string(),
+        })?;
+        Ok((scripts, warnings))
+    }
+    /// Merge the `[scripts]` tables of the files listed in `import` into this collection.
+    ///
+    /// The `[scripts]` and `global_env` tables of each imported file are merged, and the imports
+    /// of imported files are followed, each resolved relative to the file that lists it. Local
+    /// definitions win over imported ones, and earlier imports win over later ones. A file
+    /// imported twice is only merged once.
+    ///
+    /// # Arguments
+    ///
+    /// * `scripts_path` - The path of the main scripts file.
+    ///
+    /// # Returns
+    ///
+    /// A warning for each script name or `global_env` variable that was defined more than once.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if an imported file cannot be read or parsed, or if
+    /// files import each other in a cycle.
+    pub fn merge_imports(&mut self, scripts_path: &str) -> Result<Vec<String>, CargoScriptError> {
+        let mut merge = ImportMerge {
+            origins: self.scripts.keys().map(|name| (name.clone(), scripts_path.to_string())).collect(),
+            env_origins: self.global_env.iter().flatten().map(|(key, _)| (key.clone(), scripts_path.to_string())).collect(),
+            stack: vec![(canonical_path(Path::new(scripts_path)), scripts_path.to_string())],
+            merged: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let imports = self.import.clone();
+        self.merge_import_list(&scripts_dir(scripts_path), Path::new(""), &imports, &mut merge)?;
+        Ok(merge.warnings)
+    }
+
+    /// Merge the files of one `import` list, then the files they import in turn.
+    ///
+    /// `base_dir` is the directory of the file listing the imports, and `label_dir` the same
+    /// directory relative to the main file, used to name files in messages.
+    fn merge_import_list(&mut self, base_dir: &Path, label_dir: &Path, imports: &[String], merge: &mut ImportMerge) -> Result<(), CargoScriptError> {
+        for import in imports {
+            let path = base_dir.join(import);
+            let label = label_dir.join(import).display().to_string();
+            let import_failed = |message: String| CargoScriptError::ImportFailed { path: path.display().to_string(), message };
+            let key = canonical_path(&path);
+            if let Some(start) = merge.stack.iter().position(|(p, _)| *p == key) {
+                let mut chain: Vec<&str> = merge.stack[start..].iter().map(|(_, l)| l.as_str()).collect();
+                chain.push(&label);
+                return Err(import_failed(format!("import cycle: {}", chain.join(" -> "))));
+            }
+            if merge.merged.contains(&key) {
+                continue;
+            }
+            let content = fs::read_to_string(&path).map_err(|e| import_failed(e.to_string()))?;
+            let partial: Scripts = ScriptsFormat::from_path(&path).parse(&content).map_err(import_failed)?;
+            merge.merged.push(key.clone());
+
+            let mut names: Vec<String> = partial.scripts.keys().cloned().collect();
+            names.sort();
+            let mut partial_scripts = partial.scripts;
+            for name in names {
+                if let Some(origin) = merge.origins.get(&name) {
+                    merge.warnings.push(format!("[ {} ] from {} is already defined in {}, keeping that one", name, label, origin));
+                    continue;
+                }
+                let script = partial_scripts.remove(&name).expect("name comes from the map");
+                merge.origins.insert(name.clone(), label.clone());
+                self.scripts.insert(name, script);
+            }
+
+            let mut env: Vec<(String, String)> = partial.global_env.unwrap_or_default().into_iter().collect();
+            env.sort();
+            for (key, value) in env {
+                let global_env = self.global_env.get_or_insert_with(HashMap::new);
+                match (global_env.get(&key), merge.env_origins.get(&key)) {
+                    (Some(existing), Some(origin)) if *existing != value => {
+                        merge.warnings.push(format!("global_env.{} from {} is already set in {}, keeping that one", key, label, origin));
+                    }
+                    (Some(_), _) => {}
+                    (None, _) => {
+                        merge.env_origins.insert(key.clone(), label.clone());
+                        global_env.insert(key, value);
+                    }
+                }
+            }
+
+            for name in self.merge_profiles(partial.profiles) {
+                merge.warnings.push(format!("profiles.{} from {} is already defined, keeping that one", name, label));
+            }
+
+            let nested_dir = path.parent().unwrap_or(base_dir).to_path_buf();
+            let nested_label_dir = Path::new(&label).parent().unwrap_or(Path::new("")).to_path_buf();
+            merge.stack.push((key, label));
+            self.merge_import_list(&nested_dir, &nested_label_dir, &partial.import, merge)?;
+            merge.stack.pop();
+        }
+        Ok(())
+    }
+}
+
+/// Set a setting from a later scripts file only if no earlier file set it.
+///
+/// # Arguments
+///
+/// * `target` - The merged setting.
+/// * `value` - The setting from the later file.
+/// * `setting` - The setting name, used in the warning.
+/// * `ignored` - Called with `setting` when `value` is dropped in favor of `target`.
+fn keep_first<T>(target: &mut Option<T>, value: Option<T>, setting: &str, ignored: &mut impl FnMut(&str)) {
+    match (target.is_some(), value) {
+        (true, Some(_)) => ignored(setting),
+        (false, value) => *target = value,
+        _ => {}
+    }
+}
+
+/// Return the scripts files (`*.toml`, `*.yaml`, `*.yml`, and `*.json`) of a scripts directory,
+/// in lexicographic order.
+///
+/// # Errors
+///
+/// This function will return an error if the directory cannot be read.
+pub fn scripts_dir_files(dir: &Path) -> Result<Vec<PathBuf>, CargoScriptError> {
+    let entries = fs::read_dir(dir).map_err(|e| CargoScriptError::ImportFailed { path: dir.display().to_string(), message: e.to_string() })?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| format::EXTENSIONS.contains(&ext)))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Options controlling a single `run` invocation.
+#[derive(Debug, Default, Clone)]
+pub struct RunOptions {
+    /// Command line environment variable overrides in `KEY=VALUE` form.
+    pub env_overrides: Vec<String>,
+    /// Buffer each script's messages and output, printing them only if it fails.
+    pub quiet_success: bool,
+    /// Directory containing the scripts file.
+    pub scripts_dir: PathBuf,
+    /// Explicit root for relative path resolution; commands also run from this directory.
+    pub working_set: Option<PathBuf>,
+    /// Print each requirement probe, its output, and its result to stderr.
+    pub trace_requirements: bool,
+    /// Run commands inside the Linux sandbox described by each script's `sandbox` table.
+    pub sandbox: bool,
+    /// Run each step in a fresh temporary copy of the base directory.
+    pub isolate_cwd: bool,
+    /// Keep the isolated directories after each step instead of removing them.
+    pub keep_isolated: bool,
+    /// Print the elapsed time of each step as soon as it finishes.
+    pub print_stage_timings: bool,
+    /// On failure in `--quiet-success` mode, replay only the last N buffered lines plus error lines.
+    pub error_context: Option<usize>,
+    /// Free-form tag shown in the performance summary and recorded in the history.
+    pub label: Option<String>,
+    /// Exit codes accepted as success for every command, in addition to each script's own.
+    pub ignore_exit_codes: Vec<i32>,
+    /// Notify when a step is still running after this long.
+    pub notify_on_long: Option<Duration>,
+    /// Print a diagnostic report about the failed command when a run fails.
+    pub explain_failure: bool,
+    /// Extra arguments passed after `--`, appended to the command of the script being run.
+    pub args: Vec<String>,
+    /// Seconds a step may run before it is killed, overriding each script's `timeout`.
+    pub timeout: Option<u64>,
+    /// Times a failing command is retried, overriding each script's `retries`.
+    pub retries: Option<u32>,
+    /// Run only this step (1-based) of the flattened chain, without its includes.
+    pub step: Option<usize>,
+    /// Included scripts to run exclusively, with `--only`.
+    pub only: Vec<String>,
+    /// Included scripts to bypass, with `--skip`.
+    pub skip: Vec<String>,
+    /// A log file whose new lines are printed while the run is in progress.
+    pub tail: Option<PathBuf>,
+    /// The file the output of every command is also written to, with `--log-file`.
+    pub log_file: Option<LogFile>,
+    /// Format of the performance summary printed after a successful run.
+    pub metrics_format: OutputFormat,
+    /// File the performance metrics are written to as JSON.
+    pub metrics_output: Option<PathBuf>,
+    /// Skip the performance summary and metrics file entirely.
+    pub no_metrics: bool,
+    /// Run scripts with `inputs` even when their inputs are unchanged.
+    pub no_cache: bool,
+    /// Files changed since the `--changed` ref; scripts with `inputs` matching none of them are skipped.
+    pub changed: Option<ChangedFiles>,
+    /// Echo each line of a command array before running it.
+    pub verbose: bool,
+    /// Skip the `pre<name>` and `post<name>` hook scripts.
+    pub no_hooks: bool,
+    /// The `[profiles.<name>]` whose variables apply on top of `global_env`.
+    pub profile: Option<String>,
+    /// Flag scripts running longer than this as slow, overriding `[settings] slow_threshold`.
+    pub warn_slow: Option<Duration>,
+    /// Fail the run when a script is slower than the slow threshold.
+    pub fail_slow: bool,
+    /// Print the values of variables matching secret patterns instead of masking them.
+    pub show_secrets: bool,
+    /// Print how each command's environment differs from the parent process environment.
+    pub env_diff: bool,
+    /// Run scripts without an interpreter with the shell in `$SHELL`, like `[settings] use_shell_env`.
+    pub use_shell_env: bool,
+    /// Run scripts with `confirm` without asking.
+    pub yes: bool,
+    /// Prefix every line of command output with its script name, like `[settings] prefix_output`.
+    pub prefix_output: bool,
+    /// Record failed steps and keep running the rest of the chain, failing at the end.
+    pub keep_going: bool,
+    /// Report which pipeline stage failed when a piped command fails.
+    pub pipefail_report: bool,
+    /// Print progress as newline-delimited JSON events on stdout instead of human-readable output.
+    pub json_stream: bool,
+    /// With `json_stream`, emit command output as `command_output` events instead of on stderr.
+    pub include_output: bool,
+}
+
+impl RunOptions {
+    /// Return the exit codes accepted as success for a script.
+    ///
+    /// This is the script's `success_codes` (or just `0`) plus the `--ignore-exit-codes` list.
+    pub fn success_codes(&self, script: &Script) -> Vec<i32> {
+        let mut codes = match script {
+            Script::Default(_) => None,
+            Script::Inline { success_codes, .. } | Script::CILike { success_codes, .. } => success_codes.clone(),
+        }
+        .unwrap_or_else(|| vec![0]);
+        for code in &self.ignore_exit_codes {
+            if !codes.contains(code) {
+                codes.push(*code);
+            }
+        }
+        codes
+    }
+
+    /// Return the number of seconds a script may run, if limited.
+    ///
+    /// `--timeout` wins over the script's own `timeout` field.
+    pub fn timeout(&self, script: &Script) -> Option<u64> {
+        self.timeout.or(match script {
+            Script::Default(_) => None,
+            Script::Inline { timeout, .. } | Script::CILike { timeout, .. } => *timeout,
+        })
+    }
+
+    /// Return how many times a failing command of a script is retried.
+    ///
+    /// `--retries` wins over the script's own `retries` field.
+    pub fn retries(&self, script: &Script) -> u32 {
+        self.retries.or(match script {
+            Script::Default(_) => None,
+            Script::Inline { retries, .. } | Script::CILike { retries, .. } => *retries,
+        }).unwrap_or(0)
+    }
+
+    /// Return the running time after which a script counts as slow, if any.
+    ///
+    /// `--warn-slow` wins over `[settings] slow_threshold`.
+    pub fn slow_threshold(&self, scripts: &Scripts) -> Option<Duration> {
+        self.warn_slow.or_else(|| scripts.settings.slow_threshold.and_then(|seconds| Duration::try_from_secs_f64(seconds).ok()))
+    }
+
+    /// Return the directory relative paths are resolved against.
+    ///
+    /// This is the `--working-set` directory when given, otherwise the directory of the scripts file.
+    pub fn base_dir(&self) -> &Path {
+        self.working_set.as_deref().unwrap_or(&self.scripts_dir)
+    }
+
+    /// Resolve a path from the scripts file against the base directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to resolve. Absolute paths are returned unchanged.
+    pub fn resolve_path(&self, path: &str) -> PathBuf {
+        self.base_dir().join(path)
+    }
+}
+
+/// A line of output produced during a run, kept for replay in `--quiet-success` mode.
+enum BufferedLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+impl BufferedLine {
+    fn text(&self) -> &str {
+        match self {
+            BufferedLine::Stdout(text) | BufferedLine::Stderr(text) => text,
+        }
+    }
+}
+
+/// Seconds waited before retrying a failed command when the script sets no `retry_delay`.
+const DEFAULT_RETRY_DELAY: u64 = 1;
+
+/// Number of lines of a `command_file` shown by dry-run.
+const COMMAND_FILE_PREVIEW_LINES: usize = 20;
+
+/// Patterns marking lines that are always replayed with `--error-context`.
+const ERROR_PATTERNS: [&str; 4] = ["error:", "error[", "panicked", "FAILED"];
+
+/// State shared across the scripts executed by a single `run` invocation.
+struct RunContext<'a> {
+    scripts: &'a Scripts,
+    options: &'a RunOptions,
+    script_durations: Arc<Mutex<HashMap<String, Duration>>>,
+    buffer: Vec<BufferedLine>,
+    /// The environment cargo-script was started with, before any variable was applied.
+    ambient_env: Vec<(String, String)>,
+    /// The variables configured for the command about to run.
+    configured_env: HashMap<String, String>,
+    /// Diagnostics about the failed command, gathered with `--explain-failure`.
+    failure_report: Option<FailureReport>,
+    /// Failures recorded with `--keep-going`, in the order they happened.
+    failures: Vec<CargoScriptError>,
+    /// The scripts currently running, from the top-level script down to the current include.
+    include_path: Vec<String>,
+    /// The shell from `$SHELL` used for scripts without an interpreter, with `use_shell_env`.
+    env_shell: Option<String>,
+    /// Whether the running include was selected by `--only`, so everything below it runs.
+    only_selected: bool,
+}
+
+impl<'a> RunContext<'a> {
+    /// Creates the state of a new run, before any variable is applied.
+    fn new(scripts: &'a Scripts, options: &'a RunOptions) -> Self {
+        RunContext {
+            scripts,
+            options,
+            script_durations: Arc::new(Mutex::new(HashMap::new())),
+            buffer: Vec::new(),
+            ambient_env: env::vars().collect(),
+            configured_env: HashMap::new(),
+            failure_report: None,
+            failures: Vec::new(),
+            include_path: Vec::new(),
+            only_selected: false,
+            env_shell: match env_shell(scripts, options) {
+                Ok(shell) => shell.map(|(shell, _)| shell),
+                Err(shell) => {
+                    eprintln!("{}  {}: $SHELL [ {} ] was not found, falling back to {}", symbols::warning::WARNING.glyph, "Shell".yellow(), shell, default_interpreter());
+                    None
+                }
+            },
+        }
+    }
+
+    /// Prints a message, or buffers it when running with `--quiet-success`.
+    fn say(&mut self, message: String) {
+        if self.options.json_stream {
+            // Stdout only carries events; progress is reported through them instead.
+        } else if self.options.quiet_success {
+            self.buffer.push(BufferedLine::Stdout(message));
+        } else {
+            println!("{}", message);
+        }
+    }
+
+    /// Return the interpreter a script's command runs with, including the shell from `$SHELL`
+    /// used with `use_shell_env`.
+    fn shell(&self, script: &'a Script) -> &str {
+        let own_interpreter = matches!(script, Script::Inline { interpreter: Some(_), .. } | Script::CILike { interpreter: Some(_), .. });
+        match &self.env_shell {
+            Some(shell) if !own_interpreter && self.scripts.default_interpreter.is_none() && script_toolchain(self.scripts, script).is_none() => shell,
+            _ => effective_interpreter(self.scripts, script),
+        }
+    }
+
+    /// Return the command to run for a script: the lines of a command array joined for the
+    /// interpreter that runs them, or the command as it is.
+    fn session_command<'c>(&self, script: &'a Script, command: Cow<'c, str>) -> Cow<'c, str> {
+        match script_command_lines(script) {
+            Some(lines) => Cow::Owned(join_command_lines(self.shell(script), lines)),
+            None => command,
+        }
+    }
+
+    /// Prints how the environment of the next command differs from the one cargo-script was
+    /// started with, for `--env-diff`.
+    fn say_env_diff(&mut self, script_name: &str) {
+        // Without `env_passthrough`, commands inherit the current environment, which includes the
+        // variables set for earlier steps.
+        let inherited: Vec<(String, String)> = match self.scripts.env_passthrough {
+            Some(_) => self.ambient_env.clone(),
+            None => env::vars().collect(),
+        };
+        let diff = env_diff(&self.ambient_env, &child_env(self.scripts, &inherited, &self.configured_env));
+        for line in format_env_diff(script_name, &diff, &SecretMask::new(self.scripts, self.options.show_secrets)) {
+            self.say(line);
+        }
+    }
+
+    /// With `--keep-going`, records a failed step and lets the run continue; otherwise passes
+    /// the result through.
+    fn keep_going(&mut self, result: Result<(), CargoScriptError>) -> Result<(), CargoScriptError> {
+        match result {
+            // A cycle or a self-invocation is a mistake in the scripts file, so it stops the run even with --keep-going.
+            Err(e) if self.options.keep_going && !matches!(e, CargoScriptError::CircularInclude { .. } | CargoScriptError::SelfInvocation { .. }) => {
+                self.flush();
+                if let Some(report) = self.failure_report.take() {
+                    report.print();
+                }
+                eprintln!("{} {}", symbols::other_symbol::CROSS_MARK.glyph, e.to_string().red());
+                self.failures.push(e);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Prints the buffered lines to their original streams and clears the buffer.
+    ///
+    /// With `--error-context N`, only the last N lines and the lines matching an error pattern
+    /// are printed, and each run of skipped lines is replaced by a marker.
+    fn flush(&mut self) {
+        let total = self.buffer.len();
+        let tail_start = self.options.error_context.map_or(0, |n| total.saturating_sub(n));
+        let mut skipped = 0;
+        for (i, line) in self.buffer.drain(..).enumerate() {
+            if i < tail_start && !ERROR_PATTERNS.iter().any(|p| line.text().contains(p)) {
+                skipped += 1;
+                continue;
+            }
+            if skipped > 0 {
+                eprintln!("{}", format!("... {} line(s) omitted ...", skipped).dimmed());
+                skipped = 0;
+            }
+            match line {
+                BufferedLine::Stdout(text) => println!("{}", text),
+                BufferedLine::Stderr(text) => eprintln!("{}", text),
+            }
+        }
+    }
+}
+
+/// Return the directory containing the scripts file, or the scripts directory itself.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file or directory.
+pub fn scripts_dir(scripts_path: &str) -> PathBuf {
+    if Path::new(scripts_path).is_dir() {
+        return PathBuf::from(scripts_path);
+    }
+    match Path::new(scripts_path).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
 /// Run a script by name, executing any included scripts in sequence.
 ///
 /// This function runs a script and any scripts it includes, measuring the execution time
@@ -47,159 +893,369 @@ pub struct Scripts {
 ///
 /// * `scripts` - A reference to the collection of scripts.
 /// * `script_name` - The name of the script to run.
-/// * `env_overrides` - A vector of command line environment variable overrides.
+/// * `options` - The options controlling this run.
 ///
-/// # Panics
+/// # Returns
+///
+/// The running time of each executed script.
+///
+/// # Errors
 ///
-/// This function will panic if it fails to execute the script commands.
-pub fn run_script(scripts: &Scripts, script_name: &str, env_overrides: Vec<String>) {
-    let script_durations = Arc::new(Mutex::new(HashMap::new()));
+/// This function will return an error if a script is missing or includes itself, its
+/// requirements are not met, or its command fails.
+pub fn run_script(scripts: &Scripts, script_name: &str, options: &RunOptions) -> Result<HashMap<String, Duration>, CargoScriptError> {
+    let mut ctx = RunContext::new(scripts, options);
 
-    fn run_script_with_level(
-        scripts: &Scripts,
-        script_name: &str,
-        env_overrides: Vec<String>,
-        level: usize,
-        script_durations: Arc<Mutex<HashMap<String, Duration>>>,
-    ) {
-        let mut env_vars = scripts.global_env.clone().unwrap_or_default();
+    /// Run a script between its `pre<name>` and `post<name>` hooks, when they are defined.
+    ///
+    /// The post hook only runs when the script succeeded. Hooks run through the usual include
+    /// and env logic, but never get hooks of their own.
+    fn run_script_with_level(ctx: &mut RunContext, script_name: &str, level: usize) -> Result<(), CargoScriptError> {
+        let (pre, post) = script_hooks(ctx.scripts, script_name, ctx.options);
+        // With --keep-going, failures are recorded rather than returned.
+        let failures_mark = ctx.failures.len();
+        if let Some(pre) = pre {
+            run_script_events(ctx, &pre, level)?;
+            if ctx.failures.len() > failures_mark {
+                return Ok(());
+            }
+        }
+        run_script_events(ctx, script_name, level)?;
+        if let Some(post) = post.filter(|_| ctx.failures.len() == failures_mark) {
+            run_script_events(ctx, &post, level)?;
+        }
+        Ok(())
+    }
+
+    fn run_script_events(ctx: &mut RunContext, script_name: &str, level: usize) -> Result<(), CargoScriptError> {
+        if !ctx.options.json_stream {
+            return run_script_body(ctx, script_name, level);
+        }
+        events::emit(&Event::ScriptStarted { script: script_name, level });
+        let start_time = Instant::now();
+        let failures_mark = ctx.failures.len();
+        let result = run_script_body(ctx, script_name, level);
+        // With --keep-going, failures of the script or its includes are recorded rather than returned.
+        let error = result.as_ref().err().or(ctx.failures[failures_mark..].first()).map(ToString::to_string);
+        events::emit(&Event::ScriptFinished {
+            script: script_name,
+            success: error.is_none(),
+            duration_ms: start_time.elapsed().as_millis(),
+            error,
+        });
+        result
+    }
+
+    fn run_script_body(ctx: &mut RunContext, script_name: &str, level: usize) -> Result<(), CargoScriptError> {
+        let scripts = ctx.scripts;
         let indent = "  ".repeat(level);
+        let buffer_mark = ctx.buffer.len();
 
         let script_start_time = Instant::now();
 
-        if let Some(script) = scripts.scripts.get(script_name) {
-            match script {
-                Script::Default(cmd) => {
+        if let Some(start) = ctx.include_path.iter().position(|name| name == script_name) {
+            let mut chain = ctx.include_path[start..].to_vec();
+            chain.push(script_name.to_string());
+            return Err(CargoScriptError::CircularInclude { chain });
+        }
+        let Some(script) = scripts.scripts.get(script_name) else {
+            return Err(CargoScriptError::ScriptNotFound { script: script_name.to_string() });
+        };
+        if let Some(prompt) = script_confirm_prompt(script_name, script) {
+            if !ctx.options.yes && !confirm(&prompt) {
+                return Err(CargoScriptError::ConfirmationDeclined { script: script_name.to_string() });
+            }
+        }
+        let env_vars = script_env_vars(scripts, script, ctx.options)?;
+        let toolchain = script_toolchain(scripts, script).map(|(toolchain, _)| toolchain);
+
+        match script {
+            Script::Default(cmd) => {
+                if toolchain.is_some() {
+                    let trace = ctx.options.trace_requirements.then_some(script_name);
+                    check_requirements(&[], toolchain, trace)
+                        .map_err(|message| CargoScriptError::RequirementFailed { script: script_name.to_string(), message })?;
+                }
+                let msg = format!(
+                    "{}{}  {}: [ {} ]",
+                    indent,
+                    symbols::other_symbol::CHECK_MARK.glyph,
+                    "Running script".green(),
+                    script_name
+                );
+                ctx.say(format!("{}\n", msg));
+                ctx.configured_env = apply_env_vars(&env_vars, &ctx.options.env_overrides);
+                if ctx.options.env_diff {
+                    ctx.say_env_diff(script_name);
+                }
+                let args = if level == 0 { ctx.options.args.as_slice() } else { &[] };
+                let result = expand_command(scripts, script_name, script, Cow::Borrowed(cmd), &ctx.configured_env)
+                    .and_then(|cmd| execute_with_retries(ctx, script_name, None, &cmd, toolchain, args));
+                ctx.keep_going(result)?;
+            }
+            Script::Inline {
+                info,
+                include,
+                interpreter,
+                requires,
+                ..
+            } | Script::CILike {
+                info,
+                include,
+                interpreter,
+                requires,
+                ..
+            } => {
+                check_platform_command(script_name, script)?;
+                let trace = ctx.options.trace_requirements.then_some(script_name);
+                check_requirements(requires.as_deref().unwrap_or(&[]), toolchain, trace)
+                    .map_err(|message| CargoScriptError::RequirementFailed { script: script_name.to_string(), message })?;
+
+                let description = format!(
+                    "{}  {}: {}",
+                    emoji::objects::book_paper::BOOKMARK_TABS.glyph,
+                    "Description".green(),
+                    info.as_deref().unwrap_or("No description provided")
+                );
+
+                // With `--step`, only the selected script's own command runs.
+                if let Some(include_scripts) = include.as_ref().filter(|_| ctx.options.step.is_none()) {
                     let msg = format!(
-                        "{}{}  {}: [ {} ]",
+                        "{}{}  {}: [ {} ]  {}",
                         indent,
                         symbols::other_symbol::CHECK_MARK.glyph,
-                        "Running script".green(),
-                        script_name
+                        "Running include script".green(),
+                        script_name,
+                        description
                     );
-                    println!("{}\n", msg);
-                    apply_env_vars(&env_vars, &env_overrides);
-                    execute_command(None, cmd, None);
-                }
-                Script::Inline {
-                    command,
-                    info,
-                    env,
-                    include,
-                    interpreter,
-                    requires,
-                    toolchain,
-                    ..
-                } | Script::CILike {
-                    command,
-                    info,
-                    env,
-                    include,
-                    interpreter,
-                    requires,
-                    toolchain,
-                    ..
-                } => {
-                    if let Err(e) = check_requirements(requires.as_deref().unwrap_or(&[]), toolchain.as_ref()) {
-                        eprintln!("{} {}: {}", symbols::other_symbol::CROSS_MARK.glyph, "Requirement check failed".red(), e);
-                        return;
+                    ctx.say(format!("{}\n", msg));
+                    ctx.include_path.push(script_name.to_string());
+                    for include_script in include_scripts {
+                        let selected = match include_selection(ctx.scripts, ctx.options, ctx.only_selected, include_script) {
+                            IncludeSelection::Skipped(reason) => {
+                                let msg = format!("{}  {}  {}: [ {} ] ({})", indent, symbols::other_symbol::CHECK_MARK.glyph, "Skipping include script".yellow(), include_script, reason);
+                                ctx.say(format!("{}\n", msg));
+                                continue;
+                            }
+                            IncludeSelection::Selected => true,
+                            IncludeSelection::Traversed => false,
+                        };
+                        let outer_selected = ctx.only_selected;
+                        ctx.only_selected |= selected;
+                        let result = run_script_with_level(ctx, include_script, level + 1);
+                        ctx.only_selected = outer_selected;
+                        ctx.keep_going(result)?;
                     }
+                    ctx.include_path.pop();
+                }
 
-                    let description = format!(
-                        "{}  {}: {}",
-                        emoji::objects::book_paper::BOOKMARK_TABS.glyph,
-                        "Description".green(),
-                        info.as_deref().unwrap_or("No description provided")
+                // An include reached by `--only` on the way to a selected script doesn't run its own command.
+                let passed_through = level > 0 && !ctx.options.only.is_empty() && !ctx.only_selected;
+                let command = match resolve_command(script_name, script, ctx.options.base_dir()) {
+                    Ok(_) if passed_through => None,
+                    Ok(command) => command,
+                    Err(e) => {
+                        ctx.keep_going(Err(e))?;
+                        None
+                    }
+                };
+                if let Some(cmd) = command {
+                    let msg = format!(
+                        "{}{}  {}: [ {} ]  {}",
+                        indent,
+                        symbols::other_symbol::CHECK_MARK.glyph,
+                        "Running script".green(),
+                        script_name,
+                        description
                     );
+                    ctx.say(format!("{}\n", msg));
 
-                    if let Some(include_scripts) = include {
-                        let msg = format!(
-                            "{}{}  {}: [ {} ]  {}",
-                            indent,
-                            symbols::other_symbol::CHECK_MARK.glyph,
-                            "Running include script".green(),
-                            script_name,
-                            description
-                        );
-                        println!("{}\n", msg);
-                        for include_script in include_scripts {
-                            run_script_with_level(
-                                scripts,
-                                include_script,
-                                env_overrides.clone(),
-                                level + 1,
-                                script_durations.clone(),
-                            );
-                        }
+                    ctx.configured_env = apply_env_vars(&env_vars, &ctx.options.env_overrides);
+                    if ctx.options.env_diff {
+                        ctx.say_env_diff(script_name);
                     }
-
-                    if let Some(cmd) = command {
-                        let msg = format!(
-                            "{}{}  {}: [ {} ]  {}",
-                            indent,
-                            symbols::other_symbol::CHECK_MARK.glyph,
-                            "Running script".green(),
-                            script_name,
-                            description
-                        );
-                        println!("{}\n", msg);
-
-                        if let Some(script_env) = env {
-                            env_vars.extend(script_env.clone());
+                    if ctx.options.verbose {
+                        for line in script_command_lines(script).unwrap_or_default() {
+                            ctx.say(format!("{}  {} {}", indent, "$".dimmed(), line));
                         }
-                        apply_env_vars(&env_vars, &env_overrides);
-                        execute_command(interpreter.as_deref(), cmd, toolchain.as_deref());
                     }
+                    let cmd = ctx.session_command(script, cmd);
+                    let args = if level == 0 { ctx.options.args.as_slice() } else { &[] };
+                    let result = expand_command(scripts, script_name, script, cmd, &ctx.configured_env)
+                        .and_then(|cmd| execute_cached(ctx, script_name, script, interpreter.as_deref(), &cmd, toolchain, args));
+                    ctx.keep_going(result)?;
                 }
             }
+        }
 
-            let script_duration = script_start_time.elapsed();
-            if level > 0 || scripts.scripts.get(script_name).map_or(false, |s| matches!(s, Script::Default(_) | Script::Inline { command: Some(_), .. } | Script::CILike { command: Some(_), .. })) {
-                script_durations
-                    .lock()
-                    .unwrap()
-                    .insert(script_name.to_string(), script_duration);
+        let script_duration = script_start_time.elapsed();
+        if level > 0 || script_command(script).is_some() || script_command_file(script).is_some() {
+            ctx.script_durations
+                .lock()
+                .unwrap()
+                .insert(script_name.to_string(), script_duration);
+            if ctx.options.print_stage_timings {
+                let msg = format!("{}{}  {} ({:.2?})", indent, symbols::other_symbol::CHECK_MARK.glyph, script_name.green(), script_duration);
+                ctx.say(msg);
+            }
+        }
+
+        // The script succeeded, so its buffered messages and output are no longer needed.
+        ctx.buffer.truncate(buffer_mark);
+        Ok(())
+    }
+
+    fn run_chain(ctx: &mut RunContext, script_name: &str) -> Result<HashMap<String, Duration>, CargoScriptError> {
+        let options = ctx.options;
+        let script_name = &match options.step {
+            Some(step) => select_step(ctx.scripts, script_name, step, options)?,
+            None => script_name.to_string(),
+        };
+        warn_unknown_filters(ctx.scripts, script_name, options)?;
+        let tailer = options.tail.as_deref().map(LogTailer::start);
+        let result = run_script_with_level(ctx, script_name, 0);
+        drop(tailer);
+        if let Err(e) = result {
+            ctx.flush();
+            if let Some(report) = &ctx.failure_report {
+                report.print();
+            }
+            return Err(e);
+        }
+
+        let durations = ctx.script_durations.lock().unwrap().clone();
+        let slow_threshold = options.slow_threshold(ctx.scripts);
+        let table_printed = !options.no_metrics && !options.json_stream && options.metrics_format == OutputFormat::Table;
+        if !durations.is_empty() && !options.no_metrics {
+            print_metrics(&durations, options, slow_threshold)?;
+        }
+        let slow = slow_threshold.map(|threshold| slow_scripts(&durations, threshold)).unwrap_or_default();
+        if !table_printed {
+            // The performance table marks slow scripts itself.
+            for script in &slow {
+                eprintln!("{}  {}: [ {} ] ran for {:.2?}", symbols::warning::WARNING.glyph, "Slow script".yellow(), script, durations[script]);
             }
-        } else {
-            println!(
-                "{}{} {}: [ {} ]",
-                indent,
-                symbols::other_symbol::CROSS_MARK.glyph,
-                "Script not found".red(),
-                script_name
-            );
         }
+        if !ctx.failures.is_empty() {
+            eprintln!("\n{}", "Failed scripts".bold().red());
+            eprintln!("{}", "-".repeat(80).red());
+            for failure in &ctx.failures {
+                eprintln!("{} {}", symbols::other_symbol::CROSS_MARK.glyph, failure);
+            }
+            eprintln!();
+            return Err(CargoScriptError::ScriptsFailed { failures: std::mem::take(&mut ctx.failures) });
+        }
+        if let Some(threshold) = slow_threshold.filter(|_| options.fail_slow && !slow.is_empty()) {
+            return Err(CargoScriptError::SlowScripts { scripts: slow, threshold });
+        }
+        Ok(durations)
     }
 
-    run_script_with_level(scripts, script_name, env_overrides, 0, script_durations.clone());
+    let result = run_chain(&mut ctx, script_name);
+    if options.json_stream {
+        let durations = ctx.script_durations.lock().unwrap().clone();
+        events::emit(&Event::RunFinished {
+            success: result.is_ok(),
+            error: result.as_ref().err().map(ToString::to_string),
+            metrics: Metrics::from_durations(&durations),
+        });
+    }
+    result
+}
 
-    let durations = script_durations.lock().unwrap();
-    if !durations.is_empty() {
+/// Print the performance summary of a run, or write it as JSON with `--metrics-format json`.
+///
+/// # Arguments
+///
+/// * `durations` - The running time of each executed script.
+/// * `options` - The options controlling this run.
+/// * `slow_threshold` - The running time after which a script is marked as slow in the table.
+///
+/// # Errors
+///
+/// This function will return an error if the `--metrics-output` file cannot be written.
+fn print_metrics(durations: &HashMap<String, Duration>, options: &RunOptions, slow_threshold: Option<Duration>) -> Result<(), CargoScriptError> {
+    if let Some(path) = &options.metrics_output {
+        write_metrics(path, durations)?;
+    }
+    if options.json_stream {
+        // The `run_finished` event carries the durations instead.
+        return Ok(());
+    }
+    if options.metrics_format == OutputFormat::Json {
+        // With --metrics-output the JSON goes to the file only, keeping stdout clean.
+        if options.metrics_output.is_none() {
+            let metrics = serde_json::to_string_pretty(&Metrics::from_durations(durations)).expect("Failed to serialize metrics");
+            println!("{}", metrics);
+        }
+    } else {
         let total_duration: Duration = durations.values().cloned().sum();
         
         println!("\n");
-        println!("{}", "Scripts Performance".bold().yellow());
+        match &options.label {
+            Some(label) => println!("{} [{}]", "Scripts Performance".bold().yellow(), label),
+            None => println!("{}", "Scripts Performance".bold().yellow()),
+        }
         println!("{}", "-".repeat(80).yellow());
+        let name_width = durations.keys().map(|script| script.width()).max().unwrap_or(0).max(25);
         for (script, duration) in durations.iter() {
-            println!("✔️  Script: {:<25}  🕒 Running time: {:.2?}", script.green(), duration);
+            let padding = show::padding(script, name_width);
+            if slow_threshold.is_some_and(|threshold| *duration > threshold) {
+                println!("✔️  Script: {}{}  🕒 Running time: {:.2?}  {}", script.yellow(), padding, duration, "⚠ slow".yellow());
+            } else {
+                println!("✔️  Script: {}{}  🕒 Running time: {:.2?}", script.green(), padding, duration);
+            }
         }
         if !durations.is_empty() {
             println!("\n🕒 Total running time: {:.2?}", total_duration);
         }
     }
+    Ok(())
 }
 
 
-/// Apply environment variables from global, script-specific, and command line overrides.
+/// Collect the variables configured for a script, before command line overrides.
 ///
-/// This function sets the environment variables for the script execution, giving precedence
-/// to command line overrides over script-specific variables, and script-specific variables over global variables.
+/// From lowest to highest precedence: the top-level `env_file`, `global_env`, the profile
+/// selected with `--profile`, the script's `env_file`, and the script's `env`. Env file paths are
+/// resolved against the base directory.
 ///
 /// # Arguments
 ///
-/// * `env_vars` - A reference to the global environment variables.
-/// * `env_overrides` - A vector of command line environment variable overrides.
-fn apply_env_vars(env_vars: &HashMap<String, String>, env_overrides: &[String]) {
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script` - The script whose variables are collected.
+/// * `options` - The options controlling this run.
+///
+/// # Errors
+///
+/// This function will return an error if an env file is missing or malformed, or the selected
+/// profile does not exist.
+pub(crate) fn script_env_vars(scripts: &Scripts, script: &Script, options: &RunOptions) -> Result<HashMap<String, String>, CargoScriptError> {
+    let mut env_vars = match &scripts.env_file {
+        Some(path) => load_env_file(&options.resolve_path(path))?,
+        None => HashMap::new(),
+    };
+    env_vars.extend(scripts.global_env.clone().unwrap_or_default());
+    env_vars.extend(scripts.profile_env(options.profile.as_deref())?);
+    if let Script::Inline { env_file, env, .. } | Script::CILike { env_file, env, .. } = script {
+        if let Some(path) = env_file {
+            env_vars.extend(load_env_file(&options.resolve_path(path))?);
+        }
+        env_vars.extend(env.clone().unwrap_or_default());
+    }
+    Ok(env_vars)
+}
+
+/// Merge environment variables with command line overrides.
+///
+/// Command line overrides take precedence over script-specific variables, and script-specific
+/// variables over global variables (which are expected to be already merged into `env_vars`).
+///
+/// # Arguments
+///
+/// * `env_vars` - A reference to the merged global and script-specific environment variables.
+/// * `env_overrides` - A slice of command line environment variable overrides.
+pub(crate) fn get_final_env(env_vars: &HashMap<String, String>, env_overrides: &[String]) -> HashMap<String, String> {
     let mut final_env = env_vars.clone();
 
     for override_str in env_overrides {
@@ -207,94 +1263,1455 @@ fn apply_env_vars(env_vars: &HashMap<String, String>, env_overrides: &[String])
             final_env.insert(key.to_string(), value.to_string());
         }
     }
-
-    for (key, value) in &final_env {
-        env::set_var(key, value);
-    }
+    final_env
+}
+
+/// Apply environment variables from global, script-specific, and command line overrides.
+///
+/// This function sets the environment variables for the script execution, giving precedence
+/// to command line overrides over script-specific variables, and script-specific variables over global variables.
+///
+/// # Arguments
+///
+/// * `env_vars` - A reference to the global environment variables.
+/// * `env_overrides` - A vector of command line environment variable overrides.
+///
+/// # Returns
+///
+/// The variables that were set.
+fn apply_env_vars(env_vars: &HashMap<String, String>, env_overrides: &[String]) -> HashMap<String, String> {
+    let final_env = get_final_env(env_vars, env_overrides);
+    for (key, value) in &final_env {
+        env::set_var(key, value);
+    }
+    final_env
+}
+
+/// Return the environment a command runs with: the inherited variables, filtered by
+/// `env_passthrough`, plus the configured ones.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `inherited` - The environment the command would inherit.
+/// * `configured` - The variables configured for the command.
+pub(crate) fn child_env(scripts: &Scripts, inherited: &[(String, String)], configured: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut env: HashMap<String, String> = match &scripts.env_passthrough {
+        Some(patterns) => passthrough_env(inherited, patterns).into_iter().cloned().collect(),
+        None => inherited.iter().cloned().collect(),
+    };
+    env.extend(configured.iter().map(|(k, v)| (k.clone(), v.clone())));
+    env
+}
+
+/// Return the ambient variables allowed through by `env_passthrough` patterns such as `CARGO_*`.
+///
+/// # Arguments
+///
+/// * `ambient_env` - The environment cargo-script was started with.
+/// * `patterns` - The glob patterns naming the variables to inherit.
+fn passthrough_env<'a>(ambient_env: &'a [(String, String)], patterns: &[String]) -> Vec<&'a (String, String)> {
+    let patterns: Vec<glob::Pattern> = patterns.iter().filter_map(|p| glob::Pattern::new(p).ok()).collect();
+    ambient_env.iter()
+        .filter(|(key, _)| patterns.iter().any(|p| p.matches(key)))
+        .collect()
+}
+
+/// Dry run a script by name, printing what would be executed without running anything.
+///
+/// The plan lists every script in the chain with its interpreter, toolchain, requirements,
+/// final environment, and the full command body exactly as it would be passed to the interpreter.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to dry run.
+/// * `options` - The options controlling this run.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist, or
+/// if the chain contains a cycle.
+pub fn dry_run_script(scripts: &Scripts, script_name: &str, options: &RunOptions) -> Result<(), CargoScriptError> {
+    fn dry_run_with_level(scripts: &Scripts, script_name: &str, options: &RunOptions, level: usize) -> Result<(), CargoScriptError> {
+        let script = scripts.scripts.get(script_name)
+            .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+        let indent = "  ".repeat(level);
+        let detail = "  ".repeat(level + 2);
+        let env_vars = script_env_vars(scripts, script, options)?;
+        check_platform_command(script_name, script)?;
+
+        let (info, include, interpreter, requires) = match script {
+            Script::Default(_) => (None, None, None, None),
+            Script::Inline { info, include, interpreter, requires, .. }
+            | Script::CILike { info, include, interpreter, requires, .. } => {
+                (info.as_deref(), include.as_ref(), interpreter.as_deref(), requires.as_ref())
+            }
+        };
+        let toolchain = script_toolchain(scripts, script);
+
+        println!(
+            "{}{}  {}: [ {} ]  {}  {}: {}",
+            indent,
+            symbols::other_symbol::CHECK_MARK.glyph,
+            "Script".green(),
+            script_name,
+            emoji::objects::book_paper::BOOKMARK_TABS.glyph,
+            "Description".green(),
+            info.unwrap_or("No description provided")
+        );
+        if let Some(prompt) = script_confirm_prompt(script_name, script) {
+            let status = if options.yes { "skipped with --yes" } else { "required" };
+            println!("{}{}: {} (\"{}\")", detail, "Confirmation".yellow(), status, prompt);
+        }
+        if let Some(requires) = requires {
+            let requires: Vec<String> = requires.iter().map(Requirement::to_string).collect();
+            println!("{}{}: {}", detail, "Requires".yellow(), requires.join(", "));
+        }
+        if let Some((toolchain, source)) = toolchain {
+            println!("{}{}: {} ({})", detail, "Toolchain".yellow(), toolchain, source);
+        }
+        let toolchain = toolchain.map(|(toolchain, _)| toolchain);
+
+        let (pre, post) = script_hooks(scripts, script_name, options);
+        let hooks: Vec<String> = pre.into_iter().chain(post).collect();
+        if !hooks.is_empty() {
+            println!("{}{}: {}", detail, "Hooks".yellow(), hooks.join(", "));
+        }
+        if let Some(include_scripts) = include.filter(|_| options.step.is_none()) {
+            println!("{}{}: {}", detail, "Includes".yellow(), include_scripts.join(", "));
+            for include_script in include_scripts {
+                dry_run_with_level(scripts, include_script, options, level + 1)?;
+            }
+        }
+
+        if let Some(command) = resolve_command(script_name, script, options.base_dir())? {
+            let platforms = platform_commands(script);
+            if !platforms.is_empty() {
+                let os = env::consts::OS;
+                let selected = if platforms.iter().any(|(p, _)| *p == os) { os } else { "base command" };
+                let platforms: Vec<&str> = platforms.iter().map(|(p, _)| *p).collect();
+                println!("{}{}: {} (variants: {})", detail, "Platform".yellow(), selected, platforms.join(", "));
+            }
+            println!("{}{}: {}", detail, "Interpreter".yellow(), describe_interpreter(scripts, script, options));
+            let (shell_args, source) = script_shell_args(scripts, script);
+            if !shell_args.is_empty() {
+                println!("{}{}: {} ({})", detail, "Shell args".yellow(), shell_args.join(" "), source);
+            }
+            let final_env = get_final_env(&env_vars, &options.env_overrides);
+            if !final_env.is_empty() {
+                let mut keys: Vec<&String> = final_env.keys().collect();
+                keys.sort();
+                let mask = SecretMask::new(scripts, options.show_secrets);
+                println!("{}{}:", detail, "Env".yellow());
+                for key in keys {
+                    let value = mask.apply(key, &final_env[key]);
+                    // Values replaced by `--env` show what they override.
+                    match env_vars.get(key).filter(|old| **old != final_env[key]) {
+                        Some(old) => println!("{}  {} = {} {}", detail, key, value.magenta(), format!("(was: {})", mask.apply(key, old)).dimmed()),
+                        None => println!("{}  {} = {}", detail, key, value),
+                    }
+                }
+            }
+            if options.env_diff {
+                let ambient_env: Vec<(String, String)> = env::vars().collect();
+                let diff = env_diff(&ambient_env, &child_env(scripts, &ambient_env, &final_env));
+                for line in format_env_diff(script_name, &diff, &SecretMask::new(scripts, options.show_secrets)) {
+                    println!("{}{}", detail, line);
+                }
+            }
+            if let Some(settings) = script_sandbox(script).cloned().or_else(|| options.sandbox.then(Sandbox::default)) {
+                let state = if options.sandbox { "" } else { " (inactive without --sandbox)" };
+                println!("{}{}: {}{}", detail, "Sandbox".yellow(), settings.describe(), state);
+            }
+            if let Some(dir) = script_workdir(script) {
+                let path = options.resolve_path(dir);
+                let path = fs::canonicalize(&path).unwrap_or(path);
+                let state = if path.is_dir() { "" } else { " (not found)" };
+                println!("{}{}: {}{}", detail, "Workdir".yellow(), path.display(), state);
+            }
+            if let Some(value) = script_umask(script) {
+                match umask::parse_umask(value) {
+                    Ok(_) => println!("{}{}: {}", detail, "Umask".yellow(), value),
+                    Err(e) => println!("{}{}: {} ({})", detail, "Umask".yellow(), value, e),
+                }
+            }
+            if let Some(seconds) = options.timeout(script) {
+                println!("{}{}: {}s", detail, "Timeout".yellow(), seconds);
+            }
+            let retries = options.retries(script);
+            if retries > 0 {
+                let delay = script_retry_delay(script).unwrap_or(DEFAULT_RETRY_DELAY);
+                println!("{}{}: {} ({}s apart)", detail, "Retries".yellow(), retries, delay);
+            }
+            let success_codes = options.success_codes(script);
+            if success_codes != [0] {
+                let codes: Vec<String> = success_codes.iter().map(i32::to_string).collect();
+                println!("{}{}: {}", detail, "Success codes".yellow(), codes.join(", "));
+            }
+            if let Some(exit_codes) = script_exit_codes(script) {
+                let mut codes: Vec<(&i32, &String)> = exit_codes.iter().collect();
+                codes.sort();
+                println!("{}{}:", detail, "Exit codes".yellow());
+                for (code, meaning) in codes {
+                    println!("{}  {} = {}", detail, code, meaning);
+                }
+            }
+            let args = if level == 0 { options.args.as_slice() } else { &[] };
+            let language = interpreter.filter(|interp| toolchain.is_none() && script_file_extension(interp).is_some());
+            let from_file = script_command(script).is_none();
+            if let Some(path) = script_command_file(script).filter(|_| from_file) {
+                println!("{}{}: {}", detail, "Command file".yellow(), options.resolve_path(path).display());
+            }
+            if let Some(lines) = script_command_lines(script) {
+                println!("{}{}: {} line(s), run in one session, stopping at the first failure", detail, "Command lines".yellow(), lines.len());
+            }
+            match script_stdin(script).map(|value| value.strip_prefix('@').ok_or(value)) {
+                Some(Ok(path)) => println!("{}{}: {}", detail, "Stdin".yellow(), options.resolve_path(path).display()),
+                Some(Err(text)) => println!("{}{}: {} byte(s) of text", detail, "Stdin".yellow(), text.len()),
+                None => {}
+            }
+            let command = expand_command(scripts, script_name, script, command, &final_env)?;
+            let inputs = script_inputs(script);
+            if !inputs.is_empty() {
+                let state = match cache::input_hash(options.base_dir(), inputs, &command, args, &final_env) {
+                    _ if options.no_cache => "disabled with --no-cache".to_string(),
+                    Ok(hash) if cache::is_fresh(options.base_dir(), script_name, &hash, script_outputs(script)) => "inputs unchanged, would be skipped".to_string(),
+                    Ok(_) => "inputs changed, would run".to_string(),
+                    Err(e) => e,
+                };
+                println!("{}{}: {} ({})", detail, "Cache".yellow(), inputs.join(", "), state);
+                if let Some(changed) = &options.changed {
+                    let state = if changed.matches(inputs) { "would run" } else { "would be skipped" };
+                    println!("{}{}: {} file(s) changed since {}, {}", detail, "Changed".yellow(), changed.files.len(), changed.git_ref, state);
+                }
+            }
+            let command = match language {
+                Some(_) => command,
+                None => append_args(effective_interpreter(scripts, script), &command, args),
+            };
+            if language.is_some() && !args.is_empty() {
+                println!("{}{}: {}", detail, "Arguments".yellow(), args.join(" "));
+            }
+            let command = SecretMask::new(scripts, options.show_secrets).redact(&command, &final_env).into_owned();
+            println!("{}{}:", detail, "Command".yellow());
+            let shown = if from_file { COMMAND_FILE_PREVIEW_LINES } else { usize::MAX };
+            for line in command.lines().take(shown) {
+                println!("{}  {}", detail, line);
+            }
+            let hidden = command.lines().count().saturating_sub(shown);
+            if hidden > 0 {
+                println!("{}  ... ({} more line(s))", detail, hidden);
+            }
+        }
+        println!();
+        Ok(())
+    }
+
+    // Checked up front, since the plan below recurses into includes.
+    flatten_chain(scripts, script_name)?;
+    println!("{}: [ {} ]", "Dry run".bold().yellow(), script_name);
+    let script_name = &match options.step {
+        Some(step) => {
+            let name = select_step(scripts, script_name, step, options)?;
+            println!("{}: {} [ {} ]", "Step".yellow(), step, name);
+            name
+        }
+        None => script_name.to_string(),
+    };
+    let base_dir = fs::canonicalize(options.base_dir()).unwrap_or_else(|_| options.base_dir().to_path_buf());
+    println!("{}: {}", "Working set".yellow(), base_dir.display());
+    if let Some(patterns) = &scripts.env_passthrough {
+        let ambient_env: Vec<(String, String)> = env::vars().collect();
+        let mut names: Vec<&str> = passthrough_env(&ambient_env, patterns).into_iter().map(|(key, _)| key.as_str()).collect();
+        names.sort();
+        println!("{}: {} -> {}", "Env passthrough".yellow(), patterns.join(", "), names.join(", "));
+    }
+    if let Some(threshold) = options.notify_on_long {
+        println!("{}: steps running longer than {:.0?}", "Notify on long".yellow(), threshold);
+    }
+    if let Some(profile) = &options.profile {
+        println!("{}: {}", "Profile".yellow(), profile);
+    }
+    if let Some(threshold) = options.slow_threshold(scripts) {
+        let action = if options.fail_slow { "fail" } else { "warn" };
+        println!("{}: {} on scripts running longer than {:.2?}", "Slow threshold".yellow(), action, threshold);
+    }
+    println!();
+    dry_run_with_level(scripts, script_name, options, 0)
+}
+
+/// Build the process used to run a command with the specified interpreter, or the default shell if none is specified.
+///
+/// # Arguments
+///
+/// * `interpreter` - An optional string representing the interpreter to use.
+/// * `command` - The command to execute.
+/// * `toolchain` - An optional string representing the toolchain to use.
+/// * `shell_args` - Arguments passed to the interpreter before its command flag, or a template
+///   containing `{command}` that replaces the flag and the command.
+fn build_command(interpreter: Option<&str>, command: &str, toolchain: Option<&str>, shell_args: &[String]) -> Command {
+    let (program, command) = match (toolchain, interpreter) {
+        (Some(tc), _) => ("sh", Cow::Owned(format!("cargo +{} {}", tc, command))),
+        (None, Some(interpreter)) => (interpreter, Cow::Borrowed(command)),
+        (None, None) => (default_interpreter(), Cow::Borrowed(command)),
+    };
+    let mut cmd = Command::new(program);
+    if has_command_template(shell_args) {
+        cmd.args(shell_args.iter().map(|arg| arg.replace(COMMAND_PLACEHOLDER, &command)));
+    } else {
+        cmd.args(shell_args).arg(command_flag(program)).arg(command.as_ref());
+    }
+    cmd
+}
+
+/// Placeholder in `shell_args` replaced by the command, for interpreters with their own invocation.
+const COMMAND_PLACEHOLDER: &str = "{command}";
+
+/// Check whether `shell_args` are a full argument template containing the command placeholder,
+/// rather than arguments placed before the command flag.
+fn has_command_template(shell_args: &[String]) -> bool {
+    shell_args.iter().any(|arg| arg.contains(COMMAND_PLACEHOLDER))
+}
+
+/// Return the flag an interpreter takes before an inline command, such as `-c` for shells and
+/// Python or `-e` for Node and Ruby.
+fn command_flag(interpreter: &str) -> &'static str {
+    match interpreter {
+        "node" | "ruby" | "perl" => "-e",
+        "powershell" | "pwsh" => "-Command",
+        "cmd" => "/C",
+        _ => "-c",
+    }
 }
 
 /// Execute a command using the specified interpreter, or the default shell if none is specified.
 ///
 /// This function runs the command with the appropriate interpreter, depending on the operating system
-/// and the specified interpreter.
+/// and the specified interpreter. In `--quiet-success` mode the command output is captured into the
+/// run buffer instead of being inherited.
 ///
 /// # Arguments
 ///
+/// * `ctx` - The state of the current run.
+/// * `script_name` - The name of the script the command belongs to.
 /// * `interpreter` - An optional string representing the interpreter to use.
 /// * `command` - The command to execute.
 /// * `toolchain` - An optional string representing the toolchain to use.
+/// * `args` - Extra arguments forwarded to the command.
 ///
-/// # Panics
-///
-/// This function will panic if it fails to execute the command.
-fn execute_command(interpreter: Option<&str>, command: &str, toolchain: Option<&str>) {
-    let mut cmd = if let Some(tc) = toolchain {
-        let mut command_with_toolchain = format!("cargo +{} ", tc);
-        command_with_toolchain.push_str(command);
-        Command::new("sh")
-            .arg("-c")
-            .arg(command_with_toolchain)
-            .stdout(Stdio::inherit())
-            .stderr(Stdio::inherit())
-            .spawn()
-            .expect("Failed to execute command")
+/// # Errors
+///
+/// This function will return an error if the command cannot be started or exits unsuccessfully.
+fn execute_command(
+    ctx: &mut RunContext,
+    script_name: &str,
+    interpreter: Option<&str>,
+    command: &str,
+    toolchain: Option<&str>,
+    args: &[String],
+) -> Result<(), CargoScriptError> {
+    if analysis::is_self_invocation(command, script_name) {
+        return Err(CargoScriptError::SelfInvocation { script: script_name.to_string() });
+    }
+    let env_shell = ctx.env_shell.clone();
+    let interpreter = interpreter.or(ctx.scripts.default_interpreter.as_deref()).or(env_shell.as_deref());
+    let shell = if toolchain.is_some() {
+        "sh"
     } else {
-        match interpreter {
-            Some("bash") => Command::new("bash")
-                .arg("-c")
-                .arg(command)
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .spawn()
-                .expect("Failed to execute script using bash"),
-            Some("zsh") => Command::new("zsh")
-                .arg("-c")
-                .arg(command)
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .spawn()
-                .expect("Failed to execute script using zsh"),
-            Some("powershell") => Command::new("powershell")
-                .args(&["-Command", command])
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .spawn()
-                .expect("Failed to execute script using PowerShell"),
-            Some("cmd") => Command::new("cmd")
-                .args(&["/C", command])
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .spawn()
-                .expect("Failed to execute script using cmd"),
-            Some(other) => Command::new(other)
-                .arg("-c")
-                .arg(command)
-                .stdout(Stdio::inherit())
-                .stderr(Stdio::inherit())
-                .spawn()
-                .expect(&format!("Failed to execute script using {}", other)),
-            None => {
-                if cfg!(target_os = "windows") {
-                    Command::new("cmd")
-                        .args(&["/C", command])
-                        .stdout(Stdio::inherit())
-                        .stderr(Stdio::inherit())
-                        .spawn()
-                        .expect("Failed to execute script using cmd")
-                } else {
-                    Command::new("sh")
-                        .arg("-c")
-                        .arg(command)
-                        .stdout(Stdio::inherit())
-                        .stderr(Stdio::inherit())
-                        .spawn()
-                        .expect("Failed to execute script using sh")
+        interpreter.unwrap_or(default_interpreter())
+    };
+    let language = interpreter.filter(|interp| toolchain.is_none() && script_file_extension(interp).is_some());
+    let command = &if language.is_some() { command.to_string() } else { append_args(shell, command, args) };
+    let execution_error = |message: String| CargoScriptError::ExecutionError {
+        script: script_name.to_string(),
+        command: command.to_string(),
+        message,
+    };
+
+    let shell_args = ctx.scripts.scripts.get(script_name).map_or(&[][..], |script| script_shell_args(ctx.scripts, script).0);
+    let workdir = ctx.scripts.scripts.get(script_name).and_then(script_workdir);
+    if let Some(dir) = workdir.map(|dir| ctx.options.resolve_path(dir)).filter(|dir| !dir.is_dir()) {
+        return Err(CargoScriptError::DirectoryNotFound { path: dir.display().to_string() });
+    }
+
+    let pipefail_file = if ctx.options.pipefail_report && language.is_none() && pipefail::has_pipeline(command) {
+        if toolchain.is_none() && pipefail::supports(shell) {
+            let path = env::temp_dir().join(format!("cargo-script-pipestatus-{}-{}", std::process::id(), script_name));
+            let _ = fs::remove_file(&path);
+            Some(path)
+        } else {
+            eprintln!("{}  {}: [ {} ] pipeline statuses are only reported for bash and zsh, not `{}`", symbols::warning::WARNING.glyph, "Pipefail report".yellow(), script_name, shell);
+            None
+        }
+    } else {
+        None
+    };
+
+    // Language interpreters get multi-line bodies through a file to avoid `-c` quoting issues.
+    let script_file = match interpreter {
+        Some(interp) if toolchain.is_none() && command.contains('\n') && script_file_extension(interp).is_some() && !has_command_template(shell_args) => Some(
+            write_script_file(interp, command)
+                .map_err(|e| execution_error(format!("Failed to write temporary script file: {}", e)))?,
+        ),
+        _ => None,
+    };
+    let mut cmd = match (&script_file, interpreter) {
+        (Some(path), Some(interp)) => {
+            let mut cmd = Command::new(interp);
+            cmd.args(shell_args).arg(path);
+            cmd
+        }
+        _ => match &pipefail_file {
+            Some(_) => build_command(interpreter, &pipefail::wrap_command(shell, command), toolchain, shell_args),
+            None => build_command(interpreter, command, toolchain, shell_args),
+        },
+    };
+    if language.is_some() {
+        // Language interpreters receive the extra arguments as their own argv.
+        cmd.args(args);
+    }
+    if ctx.options.sandbox {
+        let settings = ctx.scripts.scripts.get(script_name).and_then(script_sandbox).cloned().unwrap_or_default();
+        cmd = sandbox::wrap_command(cmd, &settings);
+    }
+    if let Some(value) = ctx.scripts.scripts.get(script_name).and_then(script_umask) {
+        let mask = umask::parse_umask(value).map_err(execution_error)?;
+        if !umask::apply_umask(&mut cmd, mask) {
+            eprintln!("{}  {}: [ {} ] umask is not supported on this platform and is ignored", symbols::warning::WARNING.glyph, "Umask".yellow(), script_name);
+        }
+    }
+    if let Some(patterns) = &ctx.scripts.env_passthrough {
+        // Only the allowed ambient variables and the configured ones reach the command.
+        cmd.env_clear();
+        for (key, value) in passthrough_env(&ctx.ambient_env, patterns) {
+            cmd.env(key, value);
+        }
+        cmd.envs(&ctx.configured_env);
+    }
+    if let Some(path) = &pipefail_file {
+        cmd.env(pipefail::STATUS_FILE_VAR, path);
+    }
+    if let Some(working_set) = &ctx.options.working_set {
+        cmd.current_dir(working_set);
+    }
+    if let Some(dir) = workdir {
+        cmd.current_dir(ctx.options.resolve_path(dir));
+    }
+    let isolated_dir = if ctx.options.isolate_cwd {
+        let dir = create_isolated_dir(ctx.options.base_dir(), script_name)
+            .map_err(|e| execution_error(format!("Failed to create isolated directory: {}", e)))?;
+        // A relative workdir points into the isolated copy of the project.
+        cmd.current_dir(dir.join(workdir.unwrap_or(".")));
+        Some(dir)
+    } else {
+        None
+    };
+
+    let timeout = ctx.scripts.scripts.get(script_name).and_then(|script| ctx.options.timeout(script));
+    // Outside a terminal, Ctrl-C and SIGTERM only reach cargo-script, which then stops the
+    // command's whole group. In a terminal, the command stays in cargo-script's group unless a
+    // timeout needs to kill its whole tree; its own group then takes the terminal's foreground.
+    let own_group = timeout.is_some() || !io::stdin().is_terminal();
+    let foreground = if own_group { timeout::use_own_process_group(&mut cmd) } else { None };
+    let stream_output = ctx.options.json_stream && ctx.options.include_output;
+    let prefix_output = (ctx.options.prefix_output || ctx.scripts.settings.prefix_output) && !ctx.options.quiet_success && !ctx.options.json_stream;
+    let log = ctx.options.log_file.clone();
+    if ctx.options.quiet_success || stream_output || prefix_output || log.is_some() {
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    } else if ctx.options.json_stream {
+        // Stdout only carries events, so the command's output goes to stderr.
+        cmd.stdout(io::stderr()).stderr(Stdio::inherit());
+    } else {
+        cmd.stdout(Stdio::inherit()).stderr(Stdio::inherit());
+    }
+
+    // Without a `stdin` setting, the command reads whatever is piped into cargo-script.
+    let input = match ctx.scripts.scripts.get(script_name).and_then(script_stdin) {
+        Some(value) => {
+            let input = read_stdin_input(value, ctx.options)
+                .map_err(|e| execution_error(format!("Failed to read stdin file {}: {}", value.trim_start_matches('@'), e)))?;
+            cmd.stdin(Stdio::piped());
+            Some(input)
+        }
+        None => {
+            cmd.stdin(Stdio::inherit());
+            None
+        }
+    };
+
+    let _timer = ctx.options.notify_on_long.map(|threshold| LongRunTimer::start(script_name, threshold));
+    let output_mark = ctx.buffer.len();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| execution_error(format!("Failed to execute script using {}: {}", shell, e)))?;
+    let running = interrupt::track(child.id(), own_group);
+    if let Some(log) = &log {
+        log.header(script_name);
+    }
+    if let (Some(input), Some(mut child_stdin)) = (input, child.stdin.take()) {
+        // Written on its own thread so a command that doesn't read its input can't block the run.
+        thread::spawn(move || {
+            let _ = child_stdin.write_all(&input);
+        });
+    }
+    let stdout_pipe = log_file::tee_pipe(log.as_ref(), child.stdout.take());
+    let stderr_pipe = log_file::tee_pipe(log.as_ref(), child.stderr.take());
+    let (stdout, stderr) = if stream_output {
+        (events::stream_lines(stdout_pipe, script_name, "stdout"), events::stream_lines(stderr_pipe, script_name, "stderr"))
+    } else if prefix_output {
+        (prefix::prefix_lines(stdout_pipe, script_name, false), prefix::prefix_lines(stderr_pipe, script_name, true))
+    } else if log.is_some() && !ctx.options.quiet_success {
+        // Piped only for the log file; with --json-stream, stdout only carries events.
+        (log_file::pass_through(stdout_pipe, ctx.options.json_stream), log_file::pass_through(stderr_pipe, true))
+    } else {
+        (read_lines(stdout_pipe), read_lines(stderr_pipe))
+    };
+    let status = timeout::wait_with_timeout(&mut child, timeout.map(Duration::from_secs))
+        .map_err(|e| execution_error(format!("Command wasn't running: {}", e)))?;
+    drop(foreground);
+    drop(running);
+    for line in stdout.join().unwrap_or_default() {
+        ctx.buffer.push(BufferedLine::Stdout(line));
+    }
+    for line in stderr.join().unwrap_or_default() {
+        ctx.buffer.push(BufferedLine::Stderr(line));
+    }
+
+    if let Some(path) = script_file {
+        let _ = fs::remove_file(path);
+    }
+    let pipeline_report = pipefail_file.as_deref().and_then(|path| pipefail::read_report(path, command));
+    if let Some(path) = pipefail_file {
+        let _ = fs::remove_file(path);
+    }
+    if let Some(dir) = isolated_dir {
+        if ctx.options.keep_isolated {
+            eprintln!("{}: [ {} ] {}", "Isolated directory kept".yellow(), script_name, dir.display());
+        } else {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+    interrupt::exit_if_interrupted();
+
+    let Some(status) = status else {
+        return Err(CargoScriptError::Timeout { script: script_name.to_string(), seconds: timeout.unwrap_or_default() });
+    };
+    let success_codes = match ctx.scripts.scripts.get(script_name) {
+        Some(script) => ctx.options.success_codes(script),
+        None => vec![0],
+    };
+    if status.code().is_some_and(|code| success_codes.contains(&code)) {
+        return Ok(());
+    }
+    if ctx.options.explain_failure {
+        let output = &ctx.buffer[output_mark..];
+        let tail_start = output.len().saturating_sub(explain::EXPLAIN_TAIL_LINES);
+        let mask = SecretMask::new(ctx.scripts, ctx.options.show_secrets);
+        ctx.failure_report = Some(FailureReport {
+            script: script_name.to_string(),
+            command: mask.redact(command, &ctx.configured_env).into_owned(),
+            interpreter: shell.to_string(),
+            exit_code: status.code(),
+            // Output is only captured with --quiet-success; otherwise it was already shown.
+            output_tail: ctx.options.quiet_success.then(|| output[tail_start..].iter().map(|line| line.text().to_string()).collect()),
+            env: explain::sorted_env(&ctx.configured_env, &mask),
+            tool_versions: explain::tool_versions(command, shell, toolchain),
+        });
+    }
+    let meaning = ctx.scripts.scripts.get(script_name)
+        .and_then(script_exit_codes)
+        .and_then(|codes| codes.get(&status.code()?));
+    let mut message = match meaning {
+        Some(meaning) => format!("exited with {}: {}", status, meaning),
+        None => format!("exited with {}", status),
+    };
+    if let Some(report) = pipeline_report {
+        message = format!("{}; {}", message, report);
+    }
+    Err(CargoScriptError::ExecutionFailed {
+        script: script_name.to_string(),
+        command: command.to_string(),
+        code: exit_code(&status),
+        message,
+    })
+}
+
+/// Run a script's own command repeatedly, for `bench`.
+///
+/// The command runs like in `run`, with the script's variables and interpreter, but without the
+/// per-run messages: its output is captured and only replayed when a run fails. Includes and
+/// hooks are not run. A script with `confirm` is confirmed once, before the first run.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to benchmark.
+/// * `options` - The options controlling the runs.
+/// * `runs` - The number of measured runs.
+/// * `warmup` - The number of runs before the measured ones, whose durations are discarded.
+/// * `ignore_failures` - Whether to keep going when a run fails.
+///
+/// # Returns
+///
+/// The duration of each measured run, and the number of runs that failed.
+///
+/// # Errors
+///
+/// This function will return an error if the script is missing or has no command of its own,
+/// or if a run fails without `ignore_failures`.
+pub fn bench_script(scripts: &Scripts, script_name: &str, options: &RunOptions, runs: u32, warmup: u32, ignore_failures: bool) -> Result<(Vec<Duration>, u32), CargoScriptError> {
+    let script = scripts.scripts.get(script_name)
+        .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+    check_platform_command(script_name, script)?;
+    if let Some(prompt) = script_confirm_prompt(script_name, script) {
+        if !options.yes && !confirm(&prompt) {
+            return Err(CargoScriptError::ConfirmationDeclined { script: script_name.to_string() });
+        }
+    }
+    let command = resolve_command(script_name, script, options.base_dir())?
+        .ok_or_else(|| CargoScriptError::NothingToBenchmark { script: script_name.to_string() })?;
+    let env_vars = script_env_vars(scripts, script, options)?;
+    let toolchain = script_toolchain(scripts, script).map(|(toolchain, _)| toolchain);
+    let interpreter = match script {
+        Script::Default(_) => None,
+        Script::Inline { interpreter, .. } | Script::CILike { interpreter, .. } => interpreter.as_deref(),
+    };
+
+    let options = RunOptions { quiet_success: true, ..options.clone() };
+    let mut ctx = RunContext::new(scripts, &options);
+    ctx.configured_env = apply_env_vars(&env_vars, &options.env_overrides);
+    let command = ctx.session_command(script, command);
+    let command = expand_command(scripts, script_name, script, command, &ctx.configured_env)?;
+
+    let mut durations = Vec::new();
+    let mut failed = 0;
+    for run in 0..warmup + runs {
+        let start_time = Instant::now();
+        let result = execute_command(&mut ctx, script_name, interpreter, &command, toolchain, &options.args);
+        let elapsed = start_time.elapsed();
+        let label = match run.checked_sub(warmup) {
+            Some(measured) => format!("Run {}/{}", measured + 1, runs),
+            None => format!("Warmup {}/{}", run + 1, warmup),
+        };
+        match result {
+            Ok(()) => println!("  {}  {:.2?}", label.dimmed(), elapsed),
+            Err(e) if ignore_failures => {
+                ctx.flush();
+                eprintln!("{}  {}: {}", symbols::warning::WARNING.glyph, label.yellow(), e);
+                failed += 1;
+            }
+            Err(e) => {
+                ctx.flush();
+                return Err(e);
+            }
+        }
+        ctx.buffer.clear();
+        if run >= warmup {
+            durations.push(elapsed);
+        }
+    }
+    Ok((durations, failed))
+}
+
+/// Whether an included script runs under `--only` and `--skip`.
+enum IncludeSelection {
+    /// Runs, with everything it includes.
+    Selected,
+    /// Runs only the includes leading to scripts selected by `--only`, not its own command.
+    Traversed,
+    /// Does not run, for the given reason.
+    Skipped(&'static str),
+}
+
+/// Decide whether an included script runs under `--only` and `--skip`.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `options` - The options holding the `--only` and `--skip` names.
+/// * `only_selected` - Whether a script including this one was selected by `--only`.
+/// * `include_script` - The name of the included script.
+fn include_selection(scripts: &Scripts, options: &RunOptions, only_selected: bool, include_script: &str) -> IncludeSelection {
+    if options.skip.iter().any(|name| name == include_script) {
+        return IncludeSelection::Skipped("--skip");
+    }
+    if options.only.is_empty() || only_selected {
+        return IncludeSelection::Selected;
+    }
+    if options.only.iter().any(|name| name == include_script) {
+        return IncludeSelection::Selected;
+    }
+    let leads_to_selected = flatten_chain(scripts, include_script)
+        .is_ok_and(|chain| chain.iter().any(|name| options.only.contains(name)));
+    if leads_to_selected {
+        IncludeSelection::Traversed
+    } else {
+        IncludeSelection::Skipped("not selected by --only")
+    }
+}
+
+/// Warn about `--only` and `--skip` names that are not included anywhere in a script's chain.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist.
+fn warn_unknown_filters(scripts: &Scripts, script_name: &str, options: &RunOptions) -> Result<(), CargoScriptError> {
+    if options.only.is_empty() && options.skip.is_empty() {
+        return Ok(());
+    }
+    let chain = flatten_chain(scripts, script_name)?;
+    let included = &chain[..chain.len() - 1];
+    for (flag, name) in options.only.iter().map(|name| ("--only", name)).chain(options.skip.iter().map(|name| ("--skip", name))) {
+        if !included.contains(name) {
+            eprintln!("{}  {}: [ {} ] is not included by [ {} ], so {} has no effect on it", symbols::warning::WARNING.glyph, flag.yellow(), name, script_name, flag);
+        }
+    }
+    Ok(())
+}
+
+/// The exit code of a finished command, or 128 + the signal number when a signal killed it,
+/// as shells report it.
+fn exit_code(status: &ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+/// Execute a command with [`execute_command`], retrying it while it exits with a failure status.
+///
+/// The script's `retries` (or `--retries`) is the number of extra attempts, and its
+/// `retry_delay` the seconds waited before each of them. Failed attempts are reported with
+/// `--verbose`. Commands that cannot be started or time out are not retried.
+///
+/// # Errors
+///
+/// This function will return the error of the last attempt, noting how many attempts were made.
+fn execute_with_retries(
+    ctx: &mut RunContext,
+    script_name: &str,
+    interpreter: Option<&str>,
+    command: &str,
+    toolchain: Option<&str>,
+    args: &[String],
+) -> Result<(), CargoScriptError> {
+    let script = ctx.scripts.scripts.get(script_name);
+    let retries = script.map_or(0, |script| ctx.options.retries(script));
+    let delay = Duration::from_secs(script.and_then(script_retry_delay).unwrap_or(DEFAULT_RETRY_DELAY));
+    let mut attempt = 1;
+    loop {
+        match execute_command(ctx, script_name, interpreter, command, toolchain, args) {
+            Err(CargoScriptError::ExecutionFailed { script, command, code, message }) if retries > 0 => {
+                if attempt > retries {
+                    let message = format!("{} (after {} attempts)", message, attempt);
+                    return Err(CargoScriptError::ExecutionFailed { script, command, code, message });
+                }
+                if ctx.options.verbose {
+                    ctx.say(format!(
+                        "{}  {}: [ {} ] attempt {} of {} failed ({}), retrying in {}s",
+                        symbols::warning::WARNING.glyph, "Retry".yellow(), script_name, attempt, retries + 1, message, delay.as_secs()
+                    ));
+                }
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Execute a command with [`execute_with_retries`], unless the script declares `inputs` that
+/// are unchanged since its last successful run and its `outputs` still exist.
+///
+/// After a successful run, the hash of the inputs is recorded for the next run. `--no-cache`
+/// always runs the command, but still records the hash.
+///
+/// # Errors
+///
+/// This function will return an error if an input pattern is invalid or an input file cannot be
+/// read, or if the command fails.
+fn execute_cached(
+    ctx: &mut RunContext,
+    script_name: &str,
+    script: &Script,
+    interpreter: Option<&str>,
+    command: &str,
+    toolchain: Option<&str>,
+    args: &[String],
+) -> Result<(), CargoScriptError> {
+    let inputs = script_inputs(script);
+    if inputs.is_empty() {
+        return execute_with_retries(ctx, script_name, interpreter, command, toolchain, args);
+    }
+    if let Some(changed) = ctx.options.changed.as_ref().filter(|changed| !changed.matches(inputs)) {
+        let message = format!("{}  {}: [ {} ] no inputs changed since {}, skipping\n", symbols::other_symbol::CHECK_MARK.glyph, "Unchanged".green(), script_name, changed.git_ref);
+        ctx.say(message);
+        return Ok(());
+    }
+    let base_dir = ctx.options.base_dir().to_path_buf();
+    let hash = cache::input_hash(&base_dir, inputs, command, args, &ctx.configured_env)
+        .map_err(|message| CargoScriptError::CacheFailed { script: script_name.to_string(), message })?;
+    if !ctx.options.no_cache && cache::is_fresh(&base_dir, script_name, &hash, script_outputs(script)) {
+        ctx.say(format!("{}  {}: [ {} ] inputs unchanged, skipping\n", symbols::other_symbol::CHECK_MARK.glyph, "Cached".green(), script_name));
+        return Ok(());
+    }
+    execute_with_retries(ctx, script_name, interpreter, command, toolchain, args)?;
+    if let Err(e) = cache::record(&base_dir, script_name, &hash) {
+        eprintln!("{}  {}: [ {} ] could not record its inputs: {}", symbols::warning::WARNING.glyph, "Cache".yellow(), script_name, e);
+    }
+    Ok(())
+}
+
+/// Read a child's output pipe to the end on a separate thread, returning its lines.
+///
+/// Missing pipes (when the output is inherited) yield no lines.
+fn read_lines<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<Vec<String>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        String::from_utf8_lossy(&bytes).lines().map(str::to_string).collect()
+    })
+}
+
+/// Append extra arguments to a command, quoted for the interpreter that runs it.
+///
+/// Each argument survives as a single word: `cmd` arguments are wrapped in double quotes,
+/// PowerShell and POSIX shell arguments in single quotes. Without arguments, the command is
+/// returned as written, so trailing newlines (such as those of a heredoc) are kept.
+///
+/// # Arguments
+///
+/// * `interpreter` - The interpreter the command runs with.
+/// * `command` - The command to extend.
+/// * `args` - The arguments to append.
+pub(crate) fn append_args(interpreter: &str, command: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        return command.to_string();
+    }
+    let mut full_command = command.trim_end().to_string();
+    for arg in args {
+        full_command.push(' ');
+        full_command.push_str(&quote_arg(interpreter, arg));
+    }
+    full_command
+}
+
+/// Quote an argument for an interpreter, leaving plain words unchanged.
+pub(crate) fn quote_arg(interpreter: &str, arg: &str) -> String {
+    let plain = !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@".contains(c));
+    match interpreter {
+        _ if plain => arg.to_string(),
+        "cmd" => format!("\"{}\"", arg.replace('"', "\"\"")),
+        "powershell" | "pwsh" => format!("'{}'", arg.replace('\'', "''")),
+        _ => format!("'{}'", arg.replace('\'', "'\\''")),
+    }
+}
+
+/// Return the file extension used for script files of a language interpreter.
+///
+/// Shells are not listed since they receive their commands through `-c`.
+fn script_file_extension(interpreter: &str) -> Option<&'static str> {
+    match interpreter {
+        "python" | "python3" => Some("py"),
+        "node" => Some("js"),
+        "ruby" => Some("rb"),
+        "perl" => Some("pl"),
+        _ => None,
+    }
+}
+
+/// Write a multi-line command body verbatim to a temporary script file.
+///
+/// # Arguments
+///
+/// * `interpreter` - The language interpreter the file is written for.
+/// * `command` - The command body.
+fn write_script_file(interpreter: &str, command: &str) -> std::io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let file_name = format!(
+        "cargo-script-{}-{}.{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst),
+        script_file_extension(interpreter).unwrap_or("txt")
+    );
+    let path = env::temp_dir().join(file_name);
+    fs::write(&path, command)?;
+    Ok(path)
+}
+
+/// Flatten a script and its includes into the order in which they are visited.
+///
+/// Included scripts appear before the script that includes them, matching the order in which
+/// their commands run.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to flatten.
+///
+/// # Errors
+///
+/// This function will return an error if the script or one of its includes does not exist, or if
+/// a script includes itself through its chain.
+pub(crate) fn flatten_chain(scripts: &Scripts, script_name: &str) -> Result<Vec<String>, CargoScriptError> {
+    fn visit(scripts: &Scripts, script_name: &str, path: &mut Vec<String>, order: &mut Vec<String>) -> Result<(), CargoScriptError> {
+        if let Some(start) = path.iter().position(|name| name == script_name) {
+            let mut chain = path[start..].to_vec();
+            chain.push(script_name.to_string());
+            return Err(CargoScriptError::CircularInclude { chain });
+        }
+        let script = scripts.scripts.get(script_name)
+            .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+        path.push(script_name.to_string());
+        if let Script::Inline { include: Some(include), .. } | Script::CILike { include: Some(include), .. } = script {
+            for include_script in include {
+                visit(scripts, include_script, path, order)?;
+            }
+        }
+        path.pop();
+        order.push(script_name.to_string());
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    visit(scripts, script_name, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+/// Return the name of the script at a 1-based position of a chain.
+///
+/// Positions match the lines printed by `--print-includes-order`, hooks included, so a script
+/// included more than once can be selected at each of its positions.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script whose chain is listed.
+/// * `step` - The 1-based position of the step.
+/// * `options` - The options controlling the run.
+///
+/// # Errors
+///
+/// This function will return an error if the chain cannot be listed or the step is out of range.
+pub(crate) fn select_step(scripts: &Scripts, script_name: &str, step: usize, options: &RunOptions) -> Result<String, CargoScriptError> {
+    let mut order = run_order(scripts, script_name, options)?;
+    if step == 0 || step > order.len() {
+        return Err(CargoScriptError::StepOutOfRange { script: script_name.to_string(), step, steps: order.len() });
+    }
+    Ok(order.swap_remove(step - 1))
+}
+
+/// Print the order in which a script chain runs, one script name per line.
+///
+/// Nothing is executed. A script included more than once is listed each time it would run,
+/// and `pre<name>` and `post<name>` hooks are listed around the scripts they run for. Includes
+/// bypassed by `--skip` or `--only` are left out, and so are the scripts `--only` passes
+/// through without running their own command.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script whose chain is printed.
+/// * `options` - The options holding the `--only` and `--skip` filters.
+///
+/// # Errors
+///
+/// This function will return an error if a script in the chain does not exist, or if the
+/// chain contains a cycle.
+pub fn print_includes_order(scripts: &Scripts, script_name: &str, options: &RunOptions) -> Result<(), CargoScriptError> {
+    warn_unknown_filters(scripts, script_name, options)?;
+    for name in run_order(scripts, script_name, options)? {
+        println!("{}", name);
+    }
+    Ok(())
+}
+
+/// Return the scripts of a chain in the order `run` goes through them, with their hooks, and
+/// applying `--only` and `--skip` the same way.
+///
+/// Hooks are listed even with `--step`, which doesn't run them, so step positions match the
+/// list printed by `--print-includes-order`.
+///
+/// # Errors
+///
+/// This function will return an error if a script in the chain does not exist, or if the
+/// chain contains a cycle.
+fn run_order(scripts: &Scripts, script_name: &str, options: &RunOptions) -> Result<Vec<String>, CargoScriptError> {
+    fn visit_with_hooks(
+        scripts: &Scripts,
+        options: &RunOptions,
+        script_name: &str,
+        only_selected: bool,
+        path: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), CargoScriptError> {
+        let (pre, post) = if options.no_hooks { (None, None) } else { hook_names(scripts, script_name) };
+        if let Some(pre) = pre {
+            visit(scripts, options, &pre, only_selected, path, order)?;
+        }
+        visit(scripts, options, script_name, only_selected, path, order)?;
+        if let Some(post) = post {
+            visit(scripts, options, &post, only_selected, path, order)?;
+        }
+        Ok(())
+    }
+
+    fn visit(
+        scripts: &Scripts,
+        options: &RunOptions,
+        script_name: &str,
+        only_selected: bool,
+        path: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), CargoScriptError> {
+        if let Some(start) = path.iter().position(|name| name == script_name) {
+            let mut chain = path[start..].to_vec();
+            chain.push(script_name.to_string());
+            return Err(CargoScriptError::CircularInclude { chain });
+        }
+        let script = scripts.scripts.get(script_name)
+            .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+        path.push(script_name.to_string());
+        if let Script::Inline { include: Some(include), .. } | Script::CILike { include: Some(include), .. } = script {
+            for include_script in include {
+                let selected = match include_selection(scripts, options, only_selected, include_script) {
+                    IncludeSelection::Skipped(_) => continue,
+                    IncludeSelection::Selected => true,
+                    IncludeSelection::Traversed => false,
+                };
+                visit_with_hooks(scripts, options, include_script, only_selected || selected, path, order)?;
+            }
+        }
+        path.pop();
+        // An include reached by `--only` on the way to a selected script doesn't run its own command.
+        let passed_through = !path.is_empty() && !options.only.is_empty() && !only_selected && !matches!(script, Script::Default(_));
+        if !passed_through {
+            order.push(script_name.to_string());
+        }
+        Ok(())
+    }
+
+    let mut order = Vec::new();
+    visit_with_hooks(scripts, options, script_name, false, &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+/// Return the command string of a script, if it has one.
+///
+/// A `windows`, `macos`, or `linux` variant matching the current OS replaces `command`.
+pub(crate) fn script_command(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(cmd) => Some(cmd),
+        Script::Inline { command, .. } | Script::CILike { command, .. } => platform_commands(script)
+            .into_iter()
+            .find(|(os, _)| *os == env::consts::OS)
+            .map(|(_, cmd)| cmd)
+            .or(command.as_ref().map(|command| command.text.as_str())),
+    }
+}
+
+/// Return the lines of a script's `command` written as an array, unless a platform variant
+/// replaces it on the current OS.
+pub(crate) fn script_command_lines(script: &Script) -> Option<&[String]> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { command, .. } | Script::CILike { command, .. } => {
+            if platform_commands(script).iter().any(|(os, _)| *os == env::consts::OS) {
+                return None;
+            }
+            command.as_ref().and_then(|command| command.lines.as_deref())
+        }
+    }
+}
+
+/// Join the lines of a command array into one command for an interpreter, so they run in the
+/// same session and stop at the first failure.
+///
+/// POSIX shells get the lines under `set -e`, language interpreters such as `python` get them
+/// as they are, PowerShell checks `$?` after each line, and other shells chain them with `&&`.
+pub(crate) fn join_command_lines(interpreter: &str, lines: &[String]) -> String {
+    match interpreter {
+        "sh" | "bash" | "dash" | "ksh" | "zsh" => format!("set -e\n{}", lines.join("\n")),
+        "powershell" => lines.join("\nif (-not $?) { exit 1 }\n"),
+        _ if script_file_extension(interpreter).is_some() => lines.join("\n"),
+        _ => lines.join(" && "),
+    }
+}
+
+/// Return the `command_file` of a script, if it has one.
+pub(crate) fn script_command_file(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { command_file, .. } | Script::CILike { command_file, .. } => command_file.as_deref(),
+    }
+}
+
+/// Return the command a script runs: the command of [`script_command`], or else the contents
+/// of its `command_file`, resolved against `base_dir`.
+///
+/// # Arguments
+///
+/// * `script_name` - The name of the script, for error messages.
+/// * `script` - The script.
+/// * `base_dir` - The directory relative paths are resolved against (see [`RunOptions::base_dir`]).
+///
+/// # Errors
+///
+/// This function will return an error if the script has both `command` and `command_file`, or
+/// if the file cannot be read.
+pub(crate) fn resolve_command<'a>(script_name: &str, script: &'a Script, base_dir: &Path) -> Result<Option<Cow<'a, str>>, CargoScriptError> {
+    let Some(path) = script_command_file(script) else {
+        return Ok(script_command(script).map(Cow::Borrowed));
+    };
+    let error = |message: String| CargoScriptError::CommandFileFailed { script: script_name.to_string(), path: path.to_string(), message };
+    if matches!(script, Script::Inline { command: Some(_), .. } | Script::CILike { command: Some(_), .. }) {
+        return Err(error("the script also has a `command`; use only one of them".to_string()));
+    }
+    if let Some(command) = script_command(script) {
+        return Ok(Some(Cow::Borrowed(command)));
+    }
+    fs::read_to_string(base_dir.join(path))
+        .map(|content| Some(Cow::Owned(content)))
+        .map_err(|e| error(format!("could not be read: {}", e)))
+}
+
+/// Expand the references of a command with [`interpolate`].
+///
+/// Commands read from a `command_file` run as written, since `${VAR}` in a shell script is
+/// usually meant for the shell. So do commands for language interpreters like `node`, where
+/// `${x}` is part of the language (a JavaScript template literal, for instance).
+fn expand_command(scripts: &Scripts, script_name: &str, script: &Script, command: Cow<str>, env_vars: &HashMap<String, String>) -> Result<String, CargoScriptError> {
+    if script_command(script).is_none() || script_file_extension(effective_interpreter(scripts, script)).is_some() {
+        return Ok(command.into_owned());
+    }
+    interpolate(scripts, script_name, &command, env_vars)
+}
+
+/// Return the platform-specific command variants of a script, as `(os, command)` pairs.
+pub(crate) fn platform_commands(script: &Script) -> Vec<(&'static str, &str)> {
+    match script {
+        Script::Default(_) => Vec::new(),
+        Script::Inline { windows, macos, linux, .. } | Script::CILike { windows, macos, linux, .. } => {
+            [("windows", windows), ("macos", macos), ("linux", linux)]
+                .into_iter()
+                .filter_map(|(os, cmd)| cmd.as_deref().map(|cmd| (os, cmd)))
+                .collect()
+        }
+    }
+}
+
+/// Check that a script with platform-specific variants has a command for the current OS.
+///
+/// # Errors
+///
+/// This function will return an error if the script has variants, none of them is for the
+/// current OS, and it has no base `command`.
+pub(crate) fn check_platform_command(script_name: &str, script: &Script) -> Result<(), CargoScriptError> {
+    let platforms = platform_commands(script);
+    if platforms.is_empty() || script_command(script).is_some() || script_command_file(script).is_some() {
+        return Ok(());
+    }
+    Err(CargoScriptError::NoCommandForPlatform {
+        script: script_name.to_string(),
+        os: env::consts::OS.to_string(),
+        platforms: platforms.iter().map(|(os, _)| os.to_string()).collect(),
+    })
+}
+
+/// Return the script-specific environment variables of a script, if it declares any.
+pub(crate) fn script_env(script: &Script) -> Option<&HashMap<String, String>> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { env, .. } | Script::CILike { env, .. } => env.as_ref(),
+    }
+}
+
+/// Return the toolchain a script runs with, and where it comes from.
+///
+/// The script's `toolchain` wins over `settings.default_toolchain`. An empty toolchain
+/// (`toolchain = false`) runs the script without one.
+pub(crate) fn script_toolchain<'a>(scripts: &'a Scripts, script: &'a Script) -> Option<(&'a str, &'static str)> {
+    let own = match script {
+        Script::Default(_) => None,
+        Script::Inline { toolchain, .. } | Script::CILike { toolchain, .. } => toolchain.as_deref(),
+    };
+    match own {
+        Some(toolchain) => Some((toolchain, "script")),
+        None => scripts.settings.default_toolchain.as_deref().map(|toolchain| (toolchain, "settings.default_toolchain")),
+    }
+    .filter(|(toolchain, _)| !toolchain.is_empty())
+}
+
+/// Return the arguments passed to a script's interpreter before its command, and where they
+/// come from.
+///
+/// The script's `shell_args` win over `settings.default_shell_args`, which only apply to shells,
+/// not to language interpreters such as `python`.
+pub(crate) fn script_shell_args<'a>(scripts: &'a Scripts, script: &'a Script) -> (&'a [String], &'static str) {
+    let own = match script {
+        Script::Default(_) => None,
+        Script::Inline { shell_args, .. } | Script::CILike { shell_args, .. } => shell_args.as_deref(),
+    };
+    if let Some(args) = own {
+        return (args, "script");
+    }
+    match &scripts.settings.default_shell_args {
+        Some(args) if script_file_extension(effective_interpreter(scripts, script)).is_none() => (args, "settings.default_shell_args"),
+        _ => (&[], "none"),
+    }
+}
+
+/// Return the tags a script is grouped under by `show`.
+pub(crate) fn script_tags(script: &Script) -> &[String] {
+    match script {
+        Script::Default(_) => &[],
+        Script::Inline { tags, .. } | Script::CILike { tags, .. } => tags.as_deref().unwrap_or(&[]),
+    }
+}
+
+/// Return the `long_info` of a script, shown by `info` and verbose `show`.
+pub(crate) fn script_long_info(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { long_info, .. } | Script::CILike { long_info, .. } => long_info.as_deref(),
+    }
+}
+
+/// Return the `stdin` of a script: text fed to its command, or `@path` for a file's contents.
+pub(crate) fn script_stdin(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { stdin, .. } | Script::CILike { stdin, .. } => stdin.as_deref(),
+    }
+}
+
+/// Read the input a `stdin` setting feeds to a command.
+///
+/// A value starting with `@` names a file, resolved with [`RunOptions::resolve_path`]; any
+/// other value is fed as written.
+///
+/// # Errors
+///
+/// This function will return an error if the file cannot be read.
+fn read_stdin_input(value: &str, options: &RunOptions) -> io::Result<Vec<u8>> {
+    match value.strip_prefix('@') {
+        Some(path) => fs::read(options.resolve_path(path)),
+        None => Ok(value.as_bytes().to_vec()),
+    }
+}
+
+/// Return the `pre<name>` and `post<name>` scripts to run around a script, when they are defined.
+///
+/// No hooks run with `--no-hooks`, or with `--step`, which only runs the selected script's command.
+pub(crate) fn script_hooks(scripts: &Scripts, script_name: &str, options: &RunOptions) -> (Option<String>, Option<String>) {
+    if options.no_hooks || options.step.is_some() {
+        return (None, None);
+    }
+    hook_names(scripts, script_name)
+}
+
+/// Return the `pre<name>` and `post<name>` scripts of a script that are defined, whatever the options.
+fn hook_names(scripts: &Scripts, script_name: &str) -> (Option<String>, Option<String>) {
+    let hook = |prefix: &str| Some(format!("{}{}", prefix, script_name)).filter(|name| scripts.scripts.contains_key(name));
+    (hook("pre"), hook("post"))
+}
+
+/// Return the question asked before running a script with `confirm`, if it has one.
+pub(crate) fn script_confirm_prompt(script_name: &str, script: &Script) -> Option<String> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { confirm, .. } | Script::CILike { confirm, .. } => match confirm.as_ref()? {
+            Confirmation::Enabled(false) => None,
+            Confirmation::Enabled(true) => Some(format!("Are you sure you want to run {}?", script_name)),
+            Confirmation::Prompt(prompt) => Some(prompt.clone()),
+        },
+    }
+}
+
+/// Return the seconds to wait before retrying a failed command of a script, if set.
+pub(crate) fn script_retry_delay(script: &Script) -> Option<u64> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { retry_delay, .. } | Script::CILike { retry_delay, .. } => *retry_delay,
+    }
+}
+
+/// Return the glob patterns of the files a script's result depends on.
+pub(crate) fn script_inputs(script: &Script) -> &[String] {
+    match script {
+        Script::Default(_) => &[],
+        Script::Inline { inputs, .. } | Script::CILike { inputs, .. } => inputs.as_deref().unwrap_or(&[]),
+    }
+}
+
+/// Return the glob patterns of the files a script produces.
+pub(crate) fn script_outputs(script: &Script) -> &[String] {
+    match script {
+        Script::Default(_) => &[],
+        Script::Inline { outputs, .. } | Script::CILike { outputs, .. } => outputs.as_deref().unwrap_or(&[]),
+    }
+}
+
+/// Return the `workdir` of a script, if it has one.
+pub(crate) fn script_workdir(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { workdir, .. } | Script::CILike { workdir, .. } => workdir.as_deref(),
+    }
+}
+
+/// Return the meanings a script gives to its exit codes, if any.
+pub(crate) fn script_exit_codes(script: &Script) -> Option<&HashMap<i32, String>> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { exit_codes, .. } | Script::CILike { exit_codes, .. } => exit_codes.as_ref(),
+    }
+}
+
+/// Return the `umask` requested by a script, if any.
+pub(crate) fn script_umask(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { umask, .. } | Script::CILike { umask, .. } => umask.as_deref(),
+    }
+}
+
+/// Return the sandbox settings of a script, if it has any.
+pub(crate) fn script_sandbox(script: &Script) -> Option<&Sandbox> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { sandbox, .. } | Script::CILike { sandbox, .. } => sandbox.as_ref(),
+    }
+}
+
+/// Scan a command string for referenced environment variables.
+///
+/// Recognizes `$VAR` and `${VAR}` (including `${VAR:-default}` forms) and ignores text inside
+/// single quotes, where shells do not expand variables. Names are returned once, in order of appearance.
+///
+/// # Arguments
+///
+/// * `command` - The command string to scan.
+pub(crate) fn referenced_env_vars(command: &str) -> Vec<String> {
+    let chars: Vec<char> = command.chars().collect();
+    let mut names: Vec<String> = Vec::new();
+    let mut in_single_quotes = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if !in_single_quotes => i += 1,
+            '\'' => in_single_quotes = !in_single_quotes,
+            '$' if !in_single_quotes => {
+                let braced = chars.get(i + 1) == Some(&'{');
+                let start = if braced { i + 2 } else { i + 1 };
+                let mut end = start;
+                while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_') {
+                    end += 1;
                 }
+                let starts_with_letter = chars.get(start).is_some_and(|c| c.is_ascii_alphabetic() || *c == '_');
+                if end > start && starts_with_letter {
+                    let name: String = chars[start..end].iter().collect();
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+                i = end.max(i + 1);
+                continue;
             }
+            _ => {}
         }
+        i += 1;
+    }
+    names
+}
+
+/// Return the interpreter used by the OS when neither a script nor `default_interpreter` specify one.
+pub(crate) fn default_interpreter() -> &'static str {
+    if cfg!(target_os = "windows") { "cmd" } else { "sh" }
+}
+
+/// Describe the interpreter a script's command runs with, and where it comes from when it is
+/// not set on the script or by `default_interpreter`, for dry-run and `explain`.
+pub(crate) fn describe_interpreter(scripts: &Scripts, script: &Script, options: &RunOptions) -> String {
+    let interpreter = match script {
+        Script::Default(_) => None,
+        Script::Inline { interpreter, .. } | Script::CILike { interpreter, .. } => interpreter.as_deref(),
+    };
+    match (interpreter.or(scripts.default_interpreter.as_deref()), env_shell(scripts, options)) {
+        (Some(interpreter), _) => interpreter.to_string(),
+        (None, Ok(Some((shell, source)))) => format!("{} (from $SHELL, {})", shell, source),
+        (None, Err(shell)) => format!("{} ($SHELL [ {} ] was not found)", default_interpreter(), shell),
+        (None, Ok(None)) => default_interpreter().to_string(),
+    }
+}
+
+/// Return the shell in `$SHELL`, when `--use-shell-env` or `[settings] use_shell_env` asks for
+/// it to run scripts without an interpreter.
+///
+/// # Returns
+///
+/// The shell and the option that selected it, or `None` when neither option is set or `$SHELL`
+/// is empty.
+///
+/// # Errors
+///
+/// This function will return the value of `$SHELL` if the shell cannot be found.
+pub(crate) fn env_shell(scripts: &Scripts, options: &RunOptions) -> Result<Option<(String, &'static str)>, String> {
+    let source = match (options.use_shell_env, scripts.settings.use_shell_env) {
+        (true, _) => "--use-shell-env",
+        (false, true) => "settings.use_shell_env",
+        (false, false) => return Ok(None),
     };
+    match env::var("SHELL").ok().filter(|shell| !shell.trim().is_empty()) {
+        None => Ok(None),
+        Some(shell) if find_in_path(&shell).is_some() => Ok(Some((shell, source))),
+        Some(shell) => Err(shell),
+    }
+}
 
-    cmd.wait().expect("Command wasn't running");
+/// Return the interpreter a script's command is run with.
+///
+/// The script's `interpreter` wins over the top-level `default_interpreter`, which wins over
+/// the OS default. Scripts with a toolchain run through `sh` as `cargo +<toolchain> <command>`.
+pub(crate) fn effective_interpreter<'a>(scripts: &'a Scripts, script: &'a Script) -> &'a str {
+    if script_toolchain(scripts, script).is_some() {
+        return "sh";
+    }
+    match script {
+        Script::Inline { interpreter: Some(interpreter), .. } | Script::CILike { interpreter: Some(interpreter), .. } => interpreter,
+        _ => scripts.shell(),
+    }
+}
+
+/// Look up a program on `PATH`, returning its full path if found.
+///
+/// Paths containing a separator are checked directly. On Windows the usual executable
+/// extensions are tried as well.
+///
+/// # Arguments
+///
+/// * `program` - The program name to look up.
+pub(crate) fn find_in_path(program: &str) -> Option<PathBuf> {
+    let candidate = Path::new(program);
+    if candidate.components().count() > 1 {
+        return candidate.is_file().then(|| candidate.to_path_buf());
+    }
+    let extensions: &[&str] = if cfg!(target_os = "windows") { &["", ".exe", ".cmd", ".bat"] } else { &[""] };
+    env::var_os("PATH").and_then(|paths| {
+        env::split_paths(&paths).find_map(|dir| {
+            extensions.iter()
+                .map(|ext| dir.join(format!("{}{}", program, ext)))
+                .find(|path| path.is_file())
+        })
+    })
 }
 
 /// Check if the required tools and toolchain are installed.
@@ -304,8 +2721,9 @@ fn execute_command(interpreter: Option<&str>, command: &str, toolchain: Option<&
 /// 
 /// # Arguments
 /// 
-/// * `requires` - A slice of strings representing the required tools.
+/// * `requires` - A slice of requirements: tools, or `any_of`/`all_of` groups of tools.
 /// * `toolchain` - An optional string representing the required toolchain.
+/// * `trace` - The name of the script being checked, when each probe should be traced to stderr.
 /// 
 /// # Returns
 /// 
@@ -313,42 +2731,114 @@ fn execute_command(interpreter: Option<&str>, command: &str, toolchain: Option<&
 /// 
 /// # Errors
 /// 
-/// This function will return an error message if any of the requirements are not met.
-fn check_requirements(requires: &[String], toolchain: Option<&String>) -> Result<(), String> {
-    for req in requires {
-        if let Some((tool, version)) = req.split_once(' ') {
-            let output = Command::new(tool)
-                .arg("--version")
-                .output()
-                .map_err(|e| format!("Failed to execute {}: {}", tool, e))?;
-            let output_str = String::from_utf8_lossy(&output.stdout);
-
-            if !output_str.contains(version) {
-                return Err(format!(
-                    "Required version for {} is {}, but found {}",
-                    tool, version, output_str
-                ));
+/// This function will return an error message if any of the requirements are not met. For an
+/// `any_of` group, the message lists every alternative that was tried.
+pub(crate) fn check_requirements(requires: &[Requirement], toolchain: Option<&str>, trace: Option<&str>) -> Result<(), String> {
+    for requirement in requires {
+        match requirement {
+            Requirement::Tool(req) => check_tool(req, trace)?,
+            Requirement::AllOf { all_of } => {
+                for req in all_of {
+                    check_tool(req, trace)?;
+                }
+            }
+            Requirement::AnyOf { any_of } => {
+                let mut failures = Vec::new();
+                for req in any_of {
+                    match check_tool(req, trace) {
+                        Ok(()) => break,
+                        Err(e) => failures.push(e),
+                    }
+                }
+                if failures.len() == any_of.len() {
+                    return Err(format!("None of the alternatives {} is available: {}", requirement, failures.join("; ")));
+                }
             }
-        } else {
-            // Just check if the tool is installed
-            Command::new(req)
-                .output()
-                .map_err(|e| format!("Failed to execute {}: {}", req, e))?;
         }
     }
 
     if let Some(toolchain) = toolchain {
+        let requirement = format!("toolchain {}", toolchain);
+        let probe = "rustup toolchain list";
         let output = Command::new("rustup")
             .arg("toolchain")
             .arg("list")
             .output()
-            .map_err(|e| format!("Failed to execute rustup: {}", e))?;
+            .map_err(|e| format!("Failed to execute rustup: {}", e))
+            .inspect_err(|e| trace_requirement(trace, &requirement, probe, e, false))?;
         let output_str = String::from_utf8_lossy(&output.stdout);
+        let passed = output_str.contains(toolchain);
+        trace_requirement(trace, &requirement, probe, &output_str, passed);
 
-        if !output_str.contains(toolchain) {
+        if !passed {
             return Err(format!("Required toolchain {} is not installed", toolchain));
         }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Check a single tool requirement such as `docker`, `rustup 1.27`, or `cargo >=1.70`.
+///
+/// Versions are compared as semantic versions against the version found in the tool's
+/// `--version` output. A bare version matches the releases it is a prefix of.
+///
+/// # Arguments
+///
+/// * `req` - The tool, optionally followed by a version constraint.
+/// * `trace` - The name of the script being checked, when the probe should be traced to stderr.
+///
+/// # Errors
+///
+/// This function will return an error message if the tool cannot be run or its version does not match.
+fn check_tool(req: &str, trace: Option<&str>) -> Result<(), String> {
+    let spec = ToolSpec::parse(req);
+    if let Some(constraint) = &spec.version {
+        let tool = spec.name.as_str();
+        let probe = format!("{} --version", tool);
+        let output = Command::new(tool)
+            .arg("--version")
+            .output()
+            .map_err(|e| format!("Failed to execute {}: {}", tool, e))
+            .inspect_err(|e| trace_requirement(trace, req, &probe, e, false))?;
+        let mut output_str = String::from_utf8_lossy(&output.stdout).into_owned();
+        if output_str.trim().is_empty() {
+            output_str = String::from_utf8_lossy(&output.stderr).into_owned();
+        }
+        let (passed, found) = version::satisfies(constraint, &output_str);
+        trace_requirement(trace, req, &probe, &output_str, passed);
+
+        if !passed {
+            return Err(format!(
+                "Required version for {} is {}, but found {}",
+                tool, constraint, found
+            ));
+        }
+    } else {
+        // Just check if the tool is installed
+        let output = Command::new(req)
+            .output()
+            .map_err(|e| format!("Failed to execute {}: {}", req, e))
+            .inspect_err(|e| trace_requirement(trace, req, req, e, false))?;
+        trace_requirement(trace, req, req, &String::from_utf8_lossy(&output.stdout), true);
+    }
+    Ok(())
+}
+
+/// Print one requirement probe to stderr when tracing is enabled.
+///
+/// # Arguments
+///
+/// * `trace` - The name of the script being checked, or `None` when tracing is disabled.
+/// * `requirement` - The requirement as written in the scripts file.
+/// * `probe` - The command run to check the requirement.
+/// * `output` - The output of the probe, or the error if it could not be run.
+/// * `passed` - Whether the requirement is met.
+fn trace_requirement(trace: Option<&str>, requirement: &str, probe: &str, output: &str, passed: bool) {
+    let Some(script_name) = trace else { return };
+    let result = if passed { "pass".green() } else { "fail".red() };
+    eprintln!("{}: [ {} ] {} -> `{}` -> {}", "Requirement".yellow(), script_name, requirement, probe, result);
+    for line in output.trim().lines() {
+        eprintln!("    {}", line);
+    }
+}
@@ -0,0 +1,70 @@
+//! This module masks the values of sensitive variables in printed output.
+
+use std::{borrow::Cow, collections::HashMap};
+use glob::Pattern;
+use crate::commands::script::Scripts;
+
+/// Variable name patterns that are always treated as secrets.
+pub const DEFAULT_SECRET_PATTERNS: [&str; 4] = ["*_TOKEN", "*_SECRET", "*_PASSWORD", "*_KEY"];
+
+/// Text printed in place of a secret value.
+pub const MASK: &str = "***";
+
+/// Decides which variable values are hidden when printed.
+pub struct SecretMask {
+    patterns: Vec<Pattern>,
+    reveal: bool,
+}
+
+impl SecretMask {
+    /// Build the mask from the default patterns plus `[settings] secret_patterns`.
+    ///
+    /// # Arguments
+    ///
+    /// * `scripts` - A reference to the collection of scripts.
+    /// * `reveal` - Whether values should be shown anyway (`--show-secrets`).
+    pub fn new(scripts: &Scripts, reveal: bool) -> Self {
+        let patterns = DEFAULT_SECRET_PATTERNS.iter().map(|p| p.to_string())
+            .chain(scripts.settings.secret_patterns.iter().cloned())
+            .filter_map(|p| Pattern::new(&p.to_uppercase()).ok())
+            .collect();
+        SecretMask { patterns, reveal }
+    }
+
+    /// Check whether a variable name matches a secret pattern, ignoring case.
+    pub fn is_secret(&self, key: &str) -> bool {
+        let key = key.to_uppercase();
+        self.patterns.iter().any(|pattern| pattern.matches(&key))
+    }
+
+    /// Return the value to print for a variable.
+    pub fn apply<'a>(&self, key: &str, value: &'a str) -> &'a str {
+        if !self.reveal && self.is_secret(key) { MASK } else { value }
+    }
+
+    /// Replace the values of secret variables wherever they appear in a text, such as a command
+    /// with `${VAR}` references already expanded.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to print.
+    /// * `env` - The variables whose secret values are hidden.
+    pub fn redact<'a>(&self, text: &'a str, env: &HashMap<String, String>) -> Cow<'a, str> {
+        if self.reveal {
+            return Cow::Borrowed(text);
+        }
+        // Longer values first, so a secret containing another one is hidden whole.
+        let mut values: Vec<&str> = env.iter()
+            .filter(|(key, value)| !value.is_empty() && self.is_secret(key))
+            .map(|(_, value)| value.as_str())
+            .collect();
+        values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+        let mut text = Cow::Borrowed(text);
+        for value in values {
+            if text.contains(value) {
+                text = Cow::Owned(text.replace(value, MASK));
+            }
+        }
+        text
+    }
+}
@@ -0,0 +1,100 @@
+//! This module lets the user pick the script to run when `run` is given no script name.
+
+use std::io::{self, IsTerminal};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+use crate::commands::script::{Script, Scripts};
+use crate::error::CargoScriptError;
+
+/// A script picked interactively, with the options chosen for it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Selection {
+    pub script: String,
+    pub dry_run: bool,
+    /// `KEY=VALUE` overrides entered after picking the script.
+    pub env: Vec<String>,
+}
+
+/// Prompt for the script to run, then whether to dry-run it and which variables to override.
+///
+/// With `quiet`, only the script is asked for.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `quiet` - Whether to skip the prompts for options.
+///
+/// # Returns
+///
+/// The selection, or `None` when the picker was cancelled.
+///
+/// # Errors
+///
+/// This function will return an error when no scripts are defined, or when not running in a terminal.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read user input.
+pub fn interactive_select_script(scripts: &Scripts, scripts_path: &str, quiet: bool) -> Result<Option<Selection>, CargoScriptError> {
+    // Checked first, so an empty file points to `init` even when there is nobody to prompt.
+    if scripts.scripts.is_empty() {
+        return Err(CargoScriptError::NoScriptsDefined { path: scripts_path.to_string() });
+    }
+    if !io::stdin().is_terminal() {
+        return Err(CargoScriptError::ScriptNameRequired);
+    }
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    let items: Vec<String> = names.iter().map(|name| match info(&scripts.scripts[name.as_str()]) {
+        Some(info) => format!("{}  {}", name, info),
+        None => name.to_string(),
+    }).collect();
+
+    let theme = ColorfulTheme::default();
+    let Some(index) = Select::with_theme(&theme)
+        .with_prompt("Script to run")
+        .items(&items)
+        .default(0)
+        .interact_opt()
+        .expect("Failed to read input")
+    else {
+        return Ok(None);
+    };
+    let mut selection = Selection { script: names[index].clone(), ..Selection::default() };
+    if quiet {
+        return Ok(Some(selection));
+    }
+
+    selection.dry_run = Confirm::with_theme(&theme)
+        .with_prompt("Dry run only?")
+        .default(false)
+        .interact()
+        .expect("Failed to read input");
+    let env: String = Input::with_theme(&theme)
+        .with_prompt("Environment overrides (KEY=VALUE, separated by spaces; leave empty for none)")
+        .allow_empty(true)
+        .validate_with(|input: &String| parse_env_overrides(input).map(|_| ()))
+        .interact_text()
+        .expect("Failed to read input");
+    selection.env = parse_env_overrides(&env).expect("validated above");
+    Ok(Some(selection))
+}
+
+/// Split space-separated `KEY=VALUE` overrides.
+///
+/// # Errors
+///
+/// This function will return an error naming the first entry that is not `KEY=VALUE`.
+pub fn parse_env_overrides(input: &str) -> Result<Vec<String>, String> {
+    input.split_whitespace().map(|entry| match entry.split_once('=') {
+        Some((key, _)) if !key.is_empty() => Ok(entry.to_string()),
+        _ => Err(format!("`{}` is not KEY=VALUE", entry)),
+    }).collect()
+}
+
+/// Return the description of a script, if it has one.
+fn info(script: &Script) -> Option<&str> {
+    match script {
+        Script::Default(_) => None,
+        Script::Inline { info, .. } | Script::CILike { info, .. } => info.as_deref(),
+    }
+}
@@ -1,39 +1,345 @@
 //! This module provides the functionality to display all script names and descriptions.
 
-use crate::commands::script::{Scripts, Script};
+use std::{collections::{BTreeMap, HashSet}, io::{self, Write}};
+use crate::commands::script::{effective_interpreter, platform_commands, script_command, script_long_info, script_tags, script_toolchain, Requirement, Scripts, Script};
+use crate::error::CargoScriptError;
 use colored::*;
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+/// Rows of script names and descriptions, under an optional group header.
+type Group<'a> = (Option<&'a str>, Vec<(String, String)>);
+
+/// A script as listed by `show --format json`.
+#[derive(Serialize, Debug)]
+pub struct ScriptInfo<'a> {
+    pub name: &'a str,
+    pub description: Option<&'a str>,
+    pub long_description: Option<&'a str>,
+    pub command: Option<&'a str>,
+    /// The interpreter the command runs with, or `null` for scripts without a command.
+    pub interpreter: Option<&'a str>,
+    pub toolchain: Option<&'a str>,
+    pub requires: &'a [Requirement],
+    pub includes: &'a [String],
+    pub tags: &'a [String],
+    /// The OSes with a platform-specific command variant.
+    pub platforms: Vec<&'static str>,
+}
+
+/// Return the description of a script, or an empty string if it has none.
+fn description(script: &Script) -> &str {
+    match script {
+        Script::Default(_) => "",
+        Script::Inline { info, .. } | Script::CILike { info, .. } => info.as_deref().unwrap_or(""),
+    }
+}
+
+/// Return the description shown in tables, noting platform-specific command variants.
+fn table_description(script: &Script) -> String {
+    let platforms: Vec<&str> = platform_commands(script).into_iter().map(|(os, _)| os).collect();
+    match (description(script), platforms.is_empty()) {
+        (description, true) => description.to_string(),
+        ("", false) => format!("[{}]", platforms.join(", ")),
+        (description, false) => format!("{} [{}]", description, platforms.join(", ")),
+    }
+}
+
+/// Return the scripts included by a script.
+pub(crate) fn includes(script: &Script) -> &[String] {
+    match script {
+        Script::Default(_) => &[],
+        Script::Inline { include, .. } | Script::CILike { include, .. } => include.as_deref().unwrap_or(&[]),
+    }
+}
+
+/// The scripts `show` lists: both the text and the tag must match when given.
+#[derive(Debug, Default)]
+pub struct ShowFilter<'a> {
+    /// Text that script names or descriptions must contain (case-insensitive).
+    pub text: Option<&'a str>,
+    /// A tag the scripts must carry.
+    pub tag: Option<&'a str>,
+}
+
+impl ShowFilter<'_> {
+    /// Check whether a script passes the filter.
+    fn matches(&self, name: &str, script: &Script) -> bool {
+        let text_matches = self.text.map_or(true, |text| {
+            let text = text.to_lowercase();
+            name.to_lowercase().contains(&text) || description(script).to_lowercase().contains(&text)
+        });
+        text_matches && self.tag.map_or(true, |tag| script_tags(script).iter().any(|t| t == tag))
+    }
+
+    /// Check whether no filter is applied.
+    fn is_empty(&self) -> bool {
+        self.text.is_none() && self.tag.is_none()
+    }
+}
 
 /// Show all script names and descriptions in a table format.
 ///
 /// This function prints a table with script names and their descriptions.
 /// It calculates the maximum width for the script names and descriptions
-/// to format the table neatly.
+/// to format the table neatly. When scripts have tags and no filter is applied,
+/// the table is grouped by tag, with untagged scripts last.
 ///
 /// # Arguments
 ///
 /// * `scripts` - A reference to the collection of scripts.
+/// * `filter` - The text and tag the listed scripts must match.
+/// * `verbose` - Whether to print each script's `long_info` beneath its row.
 ///
-pub fn show_scripts(scripts: &Scripts) {
-    let mut max_script_name_len = "Script".len();
-    let mut max_description_len = "Description".len();
+pub fn show_scripts(scripts: &Scripts, filter: &ShowFilter, verbose: bool) {
+    let long_info = verbose.then_some(scripts);
+    let tagged = scripts.scripts.values().any(|script| !script_tags(script).is_empty());
+    if !tagged || !filter.is_empty() {
+        let rows: Vec<(String, String)> = scripts.scripts.iter()
+            .filter(|(name, script)| filter.matches(name, script))
+            .map(|(name, script)| (name.clone(), table_description(script)))
+            .collect();
+        print_groups(&[(None, rows)], long_info);
+        return;
+    }
 
+    let mut groups: BTreeMap<Option<&str>, Vec<(String, String)>> = BTreeMap::new();
     for (name, script) in &scripts.scripts {
-        max_script_name_len = max_script_name_len.max(name.len() + 2);
-        let description = match script {
-            Script::Default(_) => "",
-            Script::Inline { info, .. } | Script::CILike { info, .. } => info.as_deref().unwrap_or(""),
+        let row = (name.clone(), table_description(script));
+        match script_tags(script) {
+            [] => groups.entry(None).or_default().push(row),
+            tags => {
+                for tag in tags {
+                    groups.entry(Some(tag.as_str())).or_default().push(row.clone());
+                }
+            }
+        }
+    }
+    // `None` sorts first, but untagged scripts are listed last.
+    let untagged = groups.remove(&None);
+    let mut groups: Vec<Group> = groups.into_iter().collect();
+    groups.extend(untagged.map(|rows| (Some("untagged"), rows)));
+    for (_, rows) in &mut groups {
+        rows.sort();
+    }
+    print_groups(&groups, long_info);
+}
+
+/// Print the script names, sorted, one per line and without colors.
+///
+/// Nothing is printed when there are no scripts. Output errors (such as a closed pipe) are ignored.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+///
+pub fn list_scripts(scripts: &Scripts) {
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    let mut stdout = io::stdout().lock();
+    for name in names {
+        if writeln!(stdout, "{}", name).is_err() {
+            return;
+        }
+    }
+}
+
+/// Show all scripts as a JSON array, sorted by name.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `filter` - The text and tag the listed scripts must match.
+///
+pub fn show_scripts_json(scripts: &Scripts, filter: &ShowFilter) {
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    let infos: Vec<ScriptInfo> = names.into_iter()
+        .map(|name| (name, &scripts.scripts[name]))
+        .filter(|(name, script)| filter.matches(name, script))
+        .map(|(name, script)| {
+            let requires = match script {
+                Script::Default(_) => None,
+                Script::Inline { requires, .. } | Script::CILike { requires, .. } => requires.as_deref(),
+            };
+            let toolchain = script_toolchain(scripts, script).map(|(toolchain, _)| toolchain);
+            let command = script_command(script);
+            ScriptInfo {
+                name,
+                description: Some(description(script)).filter(|d| !d.is_empty()),
+                long_description: script_long_info(script),
+                command,
+                interpreter: command.map(|_| effective_interpreter(scripts, script)),
+                toolchain,
+                requires: requires.unwrap_or(&[]),
+                includes: includes(script),
+                tags: script_tags(script),
+                platforms: platform_commands(script).into_iter().map(|(os, _)| os).collect(),
+            }
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&infos).expect("Failed to serialize scripts"));
+}
+
+/// Show scripts as a tree, with included scripts indented beneath the scripts that include them.
+///
+/// Only scripts that are not included by any other script appear at the top level, plus any
+/// script unreachable from them (which happens for scripts that only include each other).
+/// A script that includes itself through its chain is marked as a cycle instead of being expanded again.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `filter` - The text and tag a top-level script or one of its descendants must match.
+///
+pub fn show_scripts_tree(scripts: &Scripts, filter: &ShowFilter) {
+    fn add_rows(scripts: &Scripts, name: &str, prefix: &str, connector: &str, path: &mut Vec<String>, rows: &mut Vec<(String, String)>) {
+        let Some(script) = scripts.scripts.get(name) else {
+            rows.push((format!("{}{}{}", prefix, connector, name), "(not found)".to_string()));
+            return;
         };
-        max_description_len = max_description_len.max(description.len() + 2);
+        if path.iter().any(|p| p == name) {
+            rows.push((format!("{}{}{}", prefix, connector, name), "(cycle)".to_string()));
+            return;
+        }
+        rows.push((format!("{}{}{}", prefix, connector, name), table_description(script)));
+
+        let child_prefix = match connector {
+            "" => prefix.to_string(),
+            "└─ " => format!("{}   ", prefix),
+            _ => format!("{}│  ", prefix),
+        };
+        path.push(name.to_string());
+        let children = includes(script);
+        for (i, child) in children.iter().enumerate() {
+            let connector = if i + 1 == children.len() { "└─ " } else { "├─ " };
+            add_rows(scripts, child, &child_prefix, connector, path, rows);
+        }
+        path.pop();
+    }
+
+    fn subtree_matches(scripts: &Scripts, name: &str, filter: &ShowFilter, seen: &mut HashSet<String>) -> bool {
+        if !seen.insert(name.to_string()) {
+            return false;
+        }
+        let Some(script) = scripts.scripts.get(name) else { return false };
+        filter.matches(name, script)
+            || includes(script).iter().any(|child| subtree_matches(scripts, child, filter, seen))
+    }
+
+    let mut rows = Vec::new();
+    for root in tree_roots(scripts) {
+        if subtree_matches(scripts, root, filter, &mut HashSet::new()) {
+            add_rows(scripts, root, "", "", &mut Vec::new(), &mut rows);
+        }
+    }
+    print_groups(&[(None, rows)], None);
+}
+
+/// Return the scripts shown at the top level of an include tree, sorted by name.
+///
+/// These are the scripts not included by any other script, plus one script of each group that
+/// is unreachable from them (which happens for scripts that only include each other).
+pub(crate) fn tree_roots(scripts: &Scripts) -> Vec<&String> {
+    fn reach(scripts: &Scripts, name: &str, reached: &mut HashSet<String>) {
+        if reached.insert(name.to_string()) {
+            for child in scripts.scripts.get(name).map(includes).unwrap_or_default() {
+                reach(scripts, child, reached);
+            }
+        }
+    }
+
+    let included: HashSet<&String> = scripts.scripts.values().flat_map(includes).collect();
+    let mut names: Vec<&String> = scripts.scripts.keys().collect();
+    names.sort();
+    let (mut roots, others): (Vec<&String>, Vec<&String>) = names.into_iter().partition(|name| !included.contains(name));
+
+    let mut reached = HashSet::new();
+    for root in &roots {
+        reach(scripts, root, &mut reached);
+    }
+    for name in others {
+        if !reached.contains(name) {
+            reach(scripts, name, &mut reached);
+            roots.push(name);
+        }
+    }
+    roots
+}
+
+/// Return the spaces that pad text to a column width.
+///
+/// Widths are counted in terminal columns, so wide characters such as CJK and emoji take two.
+pub(crate) fn padding(text: &str, width: usize) -> String {
+    " ".repeat(width.saturating_sub(text.width()))
+}
+
+/// Print groups of script names and descriptions as one aligned table, with a header line
+/// before each named group.
+///
+/// With `long_info`, the `long_info` of each script is printed beneath its row, aligned with
+/// the descriptions.
+fn print_groups(groups: &[Group], long_info: Option<&Scripts>) {
+    let mut max_script_name_len = "Script".len();
+    let mut max_description_len = "Description".len();
+
+    for (name, description) in groups.iter().flat_map(|(_, rows)| rows) {
+        max_script_name_len = max_script_name_len.max(name.width() + 2);
+        max_description_len = max_description_len.max(description.width() + 2);
     }
 
     println!("{:<width1$} {:<width2$}", "Script".yellow(), "Description".yellow(), width1 = max_script_name_len, width2 = max_description_len);
     println!("{:<width1$} {:<width2$}", "-".repeat(max_script_name_len).yellow(), "-".repeat(max_description_len).yellow(), width1 = max_script_name_len, width2 = max_description_len);
 
-    for (name, script) in &scripts.scripts {
-        let description = match script {
-            Script::Default(_) => "".to_string(),
-            Script::Inline { info, .. } | Script::CILike { info, .. } => info.clone().unwrap_or_else(|| "".to_string()),
-        };
-        println!("{:<width1$} {:<width2$}", name.green(), description, width1 = max_script_name_len, width2 = max_description_len);
+    for (group, rows) in groups {
+        if let Some(group) = group {
+            println!("{}", format!("[{}]", group).bold().cyan());
+        }
+        for (name, description) in rows {
+            println!(
+                "{}{} {}{}",
+                name.green(),
+                padding(name, max_script_name_len),
+                description,
+                padding(description, max_description_len)
+            );
+            let text = long_info.and_then(|scripts| scripts.scripts.get(name)).and_then(script_long_info);
+            for line in text.unwrap_or_default().trim_end().lines() {
+                match line.trim_end() {
+                    "" => println!(),
+                    line => println!("{} {}", " ".repeat(max_script_name_len), line.dimmed()),
+                }
+            }
+        }
+    }
+}
+
+/// Print everything a script says about itself: its description, tags, includes, and
+/// `long_info`.
+///
+/// # Arguments
+///
+/// * `scripts` - A reference to the collection of scripts.
+/// * `script_name` - The name of the script to describe.
+///
+/// # Errors
+///
+/// This function will return an error if the script does not exist.
+pub fn show_script_info(scripts: &Scripts, script_name: &str) -> Result<(), CargoScriptError> {
+    let script = scripts.scripts.get(script_name)
+        .ok_or_else(|| CargoScriptError::ScriptNotFound { script: script_name.to_string() })?;
+    println!("{}: {}", "Script".yellow(), script_name.green());
+    let description = table_description(script);
+    println!("{}: {}", "Description".yellow(), if description.is_empty() { "No description provided" } else { &description });
+    if !script_tags(script).is_empty() {
+        println!("{}: {}", "Tags".yellow(), script_tags(script).join(", "));
+    }
+    if !includes(script).is_empty() {
+        println!("{}: {}", "Includes".yellow(), includes(script).join(", "));
+    }
+    if let Some(long_info) = script_long_info(script) {
+        println!();
+        println!("{}", long_info.trim_end());
     }
+    Ok(())
 }
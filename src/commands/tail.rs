@@ -0,0 +1,71 @@
+//! This module follows a log file while a run is in progress, for `run --tail`.
+
+use std::{fs::File, io::{Read, Seek, SeekFrom}, path::Path, sync::mpsc, thread, time::Duration};
+
+/// How often the followed file is checked for new content.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A background thread printing lines appended to a file.
+///
+/// Content already present when tailing starts is skipped. A file that does not exist yet is
+/// waited for and then followed from its beginning. Dropping the tailer prints what is left
+/// and stops the thread.
+pub struct LogTailer {
+    stop: Option<mpsc::Sender<()>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl LogTailer {
+    /// Start following a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to follow.
+    pub fn start(path: &Path) -> Self {
+        let path = path.to_path_buf();
+        // Measured before the run starts, so nothing written by the script is skipped.
+        let offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || follow(&path, offset, &stopped));
+        LogTailer { stop: Some(stop), handle: Some(handle) }
+    }
+}
+
+impl Drop for LogTailer {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Poll the file from `offset` until stopped, printing complete lines as they are appended.
+fn follow(path: &Path, mut offset: u64, stopped: &mpsc::Receiver<()>) {
+    let mut pending = String::new();
+    loop {
+        let done = !matches!(stopped.recv_timeout(POLL_INTERVAL), Err(mpsc::RecvTimeoutError::Timeout));
+        if let Ok(mut file) = File::open(path) {
+            let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+            if len < offset {
+                // The file was truncated or replaced, so start over.
+                offset = 0;
+            }
+            let mut bytes = Vec::new();
+            if file.seek(SeekFrom::Start(offset)).is_ok() && file.read_to_end(&mut bytes).is_ok() {
+                offset += bytes.len() as u64;
+                pending.push_str(&String::from_utf8_lossy(&bytes));
+            }
+        }
+        while let Some(end) = pending.find('\n') {
+            println!("{}", pending[..end].trim_end_matches('\r'));
+            pending.drain(..=end);
+        }
+        if done {
+            if !pending.is_empty() {
+                println!("{}", pending);
+            }
+            return;
+        }
+    }
+}
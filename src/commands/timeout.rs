@@ -0,0 +1,128 @@
+//! This module enforces per-step timeouts, killing the process group of a step that overruns
+//! (its process tree on Windows).
+
+use std::{io::{self, IsTerminal}, process::{Child, Command, ExitStatus}, thread, time::{Duration, Instant}};
+
+/// How often a running step is checked against its deadline.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Start the command in its own process group so the whole tree can be killed at once.
+///
+/// When cargo-script holds the terminal, the child's group is also made the terminal's
+/// foreground group before the command starts, so it can read input without being stopped by
+/// SIGTTIN. The returned guard hands the terminal back when dropped. This has no effect on
+/// Windows, where the tree is killed through `taskkill /T` instead.
+pub fn use_own_process_group(cmd: &mut Command) -> Option<TerminalForeground> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+        let foreground = TerminalForeground::take()?;
+        // SAFETY: the closure runs between fork and exec and only makes async-signal-safe calls.
+        unsafe {
+            cmd.pre_exec(|| {
+                give_terminal(libc::getpgrp());
+                Ok(())
+            });
+        }
+        Some(foreground)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = cmd;
+        None
+    }
+}
+
+/// Holds the terminal's previous foreground group, and restores it when dropped.
+pub struct TerminalForeground {
+    #[cfg(unix)]
+    previous: libc::pid_t,
+}
+
+#[cfg(unix)]
+impl TerminalForeground {
+    /// Remember the foreground group of the terminal on stdin, if cargo-script's group holds it.
+    fn take() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        // SAFETY: these calls only read process and terminal state.
+        let (previous, own) = unsafe { (libc::tcgetpgrp(libc::STDIN_FILENO), libc::getpgrp()) };
+        (previous == own).then_some(TerminalForeground { previous })
+    }
+}
+
+impl Drop for TerminalForeground {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        give_terminal(self.previous);
+    }
+}
+
+/// Make a process group the foreground group of the terminal on stdin.
+///
+/// SIGTTOU is ignored meanwhile, since a background group taking the terminal is otherwise
+/// stopped by it. Only makes async-signal-safe calls, so it can run between fork and exec.
+#[cfg(unix)]
+fn give_terminal(group: libc::pid_t) {
+    // SAFETY: the previous SIGTTOU disposition is restored right after, and `tcsetpgrp` only
+    // changes terminal state.
+    unsafe {
+        let previous = libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+        libc::tcsetpgrp(libc::STDIN_FILENO, group);
+        libc::signal(libc::SIGTTOU, previous);
+    }
+}
+
+/// Wait for a child to exit, killing it and its process group once the timeout passes.
+///
+/// A child waited on with a timeout must have been started with [`use_own_process_group`].
+///
+/// # Arguments
+///
+/// * `child` - The running child.
+/// * `timeout` - How long the child may run, or `None` to wait indefinitely.
+///
+/// # Returns
+///
+/// The exit status, or `None` when the child was killed because it timed out.
+///
+/// # Errors
+///
+/// This function will return an error if the child's status cannot be read.
+pub fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> io::Result<Option<ExitStatus>> {
+    let Some(timeout) = timeout else {
+        return child.wait().map(Some);
+    };
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            kill_tree(child);
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Kill a child and every process it started, then reap it.
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    {
+        let pid = child.id() as libc::pid_t;
+        // SAFETY: sending a signal touches no memory. The child is not reaped yet, so the group
+        // it leads can't have been reused.
+        unsafe {
+            libc::killpg(pid, libc::SIGKILL);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = Command::new("taskkill").args(["/T", "/F", "/PID", &child.id().to_string()]).output();
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
@@ -0,0 +1,42 @@
+//! This module applies a script's `umask` to the processes it starts.
+
+use std::process::Command;
+
+/// Parse an octal umask such as `022` or `0077`.
+///
+/// # Errors
+///
+/// This function will return an error if the value is not an octal number up to `777`.
+pub fn parse_umask(value: &str) -> Result<u32, String> {
+    let mask = u32::from_str_radix(value, 8)
+        .map_err(|_| format!("invalid umask `{}`, expected an octal value such as `022`", value))?;
+    if mask > 0o777 {
+        return Err(format!("invalid umask `{}`, expected a value up to `777`", value));
+    }
+    Ok(mask)
+}
+
+/// Set the umask of the process a command starts.
+///
+/// The mask is applied in the child right before it executes, so the umask of cargo-script
+/// itself is unchanged. Returns `false` on platforms without umask support, where the mask
+/// is ignored.
+pub fn apply_umask(cmd: &mut Command, mask: u32) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: `umask` is async-signal-safe and touches no memory of the parent process.
+        unsafe {
+            cmd.pre_exec(move || {
+                libc::umask(mask as libc::mode_t);
+                Ok(())
+            });
+        }
+        true
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (cmd, mask);
+        false
+    }
+}
@@ -0,0 +1,46 @@
+//! This module compares installed tool versions against the constraints written in `requires`.
+
+use regex::Regex;
+use semver::{Version, VersionReq};
+
+/// Extract the first version number from a tool's `--version` output.
+///
+/// Versions with two or three numeric parts are recognized, with or without a `v` prefix
+/// (`cargo 1.79.0 (...)`, `v18.17.1`, `Docker version 19.03.12`). Pre-release and build
+/// suffixes are ignored.
+pub fn extract_version(output: &str) -> Option<Version> {
+    let pattern = Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?").expect("Invalid version pattern");
+    let captures = pattern.captures(output)?;
+    let part = |i: usize| captures.get(i).map_or(Some(0), |m| m.as_str().parse().ok());
+    Some(Version::new(part(1)?, part(2)?, part(3)?))
+}
+
+/// Parse a constraint such as `>=1.70`, `^18.0`, `< 1.24.3`, or a bare `1.27`.
+///
+/// A bare version matches every release it is a prefix of, so `18` matches `18.17.1` but not
+/// `1.18.0`. Leading zeros (`19.03`) are dropped before parsing.
+pub fn parse_constraint(constraint: &str) -> Option<VersionReq> {
+    let leading_zeros = Regex::new(r"\b0+(\d)").expect("Invalid version pattern");
+    let constraint = leading_zeros.replace_all(constraint.trim(), "$1");
+    let constraint = if constraint.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("={}", constraint)
+    } else {
+        constraint.into_owned()
+    };
+    VersionReq::parse(&constraint).ok()
+}
+
+/// Check a tool's `--version` output against a constraint.
+///
+/// When either the constraint or the output cannot be read as a version, this falls back to
+/// checking that the output contains the constraint text.
+///
+/// # Returns
+///
+/// Whether the constraint is met, and the version found (or the raw output when none was found).
+pub fn satisfies(constraint: &str, output: &str) -> (bool, String) {
+    match (parse_constraint(constraint), extract_version(output)) {
+        (Some(requirement), Some(version)) => (requirement.matches(&version), version.to_string()),
+        _ => (output.contains(constraint.trim()), output.trim().to_string()),
+    }
+}
@@ -0,0 +1,89 @@
+//! This module reruns a script whenever files in the watched paths change, for `watch`.
+
+use std::{collections::HashMap, fs, io::{self, IsTerminal, Write}, path::{Path, PathBuf}, thread, time::{Duration, SystemTime, UNIX_EPOCH}};
+use colored::*;
+use crate::commands::history::format_timestamp;
+
+/// How often the watched paths are scanned for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Directories that are never watched, since builds write into them.
+const IGNORED_DIRS: [&str; 3] = ["target", ".git", "node_modules"];
+
+/// Escape sequence clearing the screen and moving the cursor to the top-left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Record the modification time of every file under the watched paths.
+fn snapshot(paths: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    fn visit(path: &Path, files: &mut HashMap<PathBuf, SystemTime>) {
+        let Ok(metadata) = fs::metadata(path) else { return };
+        if metadata.is_dir() {
+            let ignored = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| IGNORED_DIRS.contains(&n));
+            if ignored {
+                return;
+            }
+            for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+                visit(&entry.path(), files);
+            }
+        } else if let Ok(modified) = metadata.modified() {
+            files.insert(path.to_path_buf(), modified);
+        }
+    }
+
+    let mut files = HashMap::new();
+    for path in paths {
+        visit(path, &mut files);
+    }
+    files
+}
+
+/// Return a file that was added, modified, or removed between two snapshots.
+fn changed_file(before: &HashMap<PathBuf, SystemTime>, after: &HashMap<PathBuf, SystemTime>) -> Option<PathBuf> {
+    after.iter()
+        .find(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .or_else(|| before.keys().find(|path| !after.contains_key(*path)).cloned())
+}
+
+/// Whether the screen can be cleared: stdout is a terminal and colors are not disabled.
+fn can_clear() -> bool {
+    io::stdout().is_terminal() && colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+/// Run a script, then run it again every time a file under `paths` changes. Never returns.
+///
+/// `target`, `.git`, and `node_modules` directories are not watched.
+///
+/// # Arguments
+///
+/// * `script_name` - The name of the script, shown in the header before each run.
+/// * `paths` - The files and directories to watch.
+/// * `clear` - Whether to clear the screen before each run (skipped when it isn't a terminal).
+/// * `run` - Runs the script once.
+pub fn watch(script_name: &str, paths: &[PathBuf], clear: bool, mut run: impl FnMut()) -> ! {
+    let clear = clear && can_clear();
+    let mut files = snapshot(paths);
+    let mut trigger: Option<PathBuf> = None;
+    loop {
+        if clear {
+            print!("{}", CLEAR_SCREEN);
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        let cause = trigger.as_ref().map_or("initial run".to_string(), |path| path.display().to_string());
+        println!("{} [ {} ] {} ({})", "Watch".bold().yellow(), script_name, format_timestamp(now).dimmed(), cause);
+        let _ = io::stdout().flush();
+        run();
+
+        println!("{}", format!("Waiting for changes in {} ...", paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")).dimmed());
+        trigger = loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = snapshot(paths);
+            if let Some(path) = changed_file(&files, &current) {
+                // Let editors and tools finish writing before rerunning.
+                thread::sleep(POLL_INTERVAL);
+                files = snapshot(paths);
+                break Some(path);
+            }
+        };
+    }
+}
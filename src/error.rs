@@ -0,0 +1,380 @@
+//! This module defines the error type returned by the cargo-script commands.
+
+use std::fmt;
+
+/// Enum representing the errors that can occur while running scripts.
+#[derive(Debug)]
+pub enum CargoScriptError {
+    /// The requested script is not defined in Scripts.toml.
+    ScriptNotFound {
+        script: String,
+    },
+    /// The scripts file does not exist.
+    ScriptFileNotFound {
+        path: String,
+    },
+    /// The scripts file could not be parsed in the format of its extension.
+    InvalidScriptsFile {
+        path: String,
+        format: &'static str,
+        message: String,
+    },
+    /// The profile selected with `--profile` is not defined.
+    ProfileNotFound {
+        profile: String,
+        available: Vec<String>,
+    },
+    /// `bench` was given a script that only includes other scripts.
+    NothingToBenchmark {
+        script: String,
+    },
+    /// `run` was given no script name and cannot prompt for one.
+    ScriptNameRequired,
+    /// The scripts file does not define any scripts.
+    NoScriptsDefined {
+        path: String,
+    },
+    /// `run -` was used but no previous run is recorded for the scripts file.
+    NoPreviousRun {
+        path: String,
+    },
+    /// The user did not confirm running a script with `confirm`.
+    ConfirmationDeclined {
+        script: String,
+    },
+    /// A directory referenced by the run does not exist.
+    DirectoryNotFound {
+        path: String,
+    },
+    /// The benchmark baseline could not be read or written.
+    InvalidBaseline {
+        path: String,
+        message: String,
+    },
+    /// Scripts got slower than the benchmark baseline allows.
+    BenchmarkRegression {
+        scripts: Vec<String>,
+        threshold_percent: f64,
+    },
+    /// Scripts ran longer than the slow threshold with `--fail-slow`.
+    SlowScripts {
+        scripts: Vec<String>,
+        threshold: std::time::Duration,
+    },
+    /// Files produced by a run could not be captured as artifacts.
+    ArtifactCaptureFailed {
+        pattern: String,
+        message: String,
+    },
+    /// A file listed in `import` could not be read or parsed.
+    ImportFailed {
+        path: String,
+        message: String,
+    },
+    /// Two files of a scripts directory define the same script.
+    DuplicateScript {
+        script: String,
+        first: String,
+        second: String,
+    },
+    /// The scripts file does not match its pinned SHA-256 hash.
+    IntegrityMismatch {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    /// A script's `command_file` could not be used.
+    CommandFileFailed {
+        script: String,
+        path: String,
+        message: String,
+    },
+    /// The `inputs` of a cached script could not be hashed.
+    CacheFailed {
+        script: String,
+        message: String,
+    },
+    /// `--changed` was used outside of a git repository.
+    NotAGitRepository {
+        path: String,
+    },
+    /// `git diff` failed for the ref passed to `--changed`.
+    GitFailed {
+        git_ref: String,
+        message: String,
+    },
+    /// A command references a `${VAR}` that is not set.
+    UndefinedVariable {
+        script: String,
+        variable: String,
+    },
+    /// A script's command runs cargo-script on the same script again.
+    SelfInvocation {
+        script: String,
+    },
+    /// A script sets a key that is not one of the script fields.
+    UnknownField {
+        script: String,
+        field: String,
+        suggestion: Option<String>,
+    },
+    /// A script includes itself through its include chain.
+    CircularInclude {
+        chain: Vec<String>,
+    },
+    /// A static check of the scripts failed.
+    ValidationFailed {
+        message: String,
+    },
+    /// The `[settings] name_pattern` is not a valid regular expression.
+    InvalidNamePattern {
+        pattern: String,
+        message: String,
+    },
+    /// A `requires` or `toolchain` check failed for the script.
+    RequirementFailed {
+        script: String,
+        message: String,
+    },
+    /// The performance metrics could not be written to `--metrics-output`.
+    MetricsOutputFailed {
+        path: String,
+        message: String,
+    },
+    /// The `--log-file` could not be created.
+    LogFileFailed {
+        path: String,
+        message: String,
+    },
+    /// An `env_file` could not be read or parsed.
+    EnvFileFailed {
+        path: String,
+        message: String,
+    },
+    /// `--step` selected a position outside of the flattened chain.
+    StepOutOfRange {
+        script: String,
+        step: usize,
+        steps: usize,
+    },
+    /// The script command ran longer than its timeout and was killed.
+    Timeout {
+        script: String,
+        seconds: u64,
+    },
+    /// The script only has platform-specific commands, and none for the current OS.
+    NoCommandForPlatform {
+        script: String,
+        os: String,
+        platforms: Vec<String>,
+    },
+    /// One or more scripts failed during a `--keep-going` run.
+    ScriptsFailed {
+        failures: Vec<CargoScriptError>,
+    },
+    /// The script command exited with a status that is not one of its success codes.
+    ///
+    /// `code` is the exit code of the command, or 128 + the signal number when it was killed.
+    ExecutionFailed {
+        script: String,
+        command: String,
+        code: i32,
+        message: String,
+    },
+    /// The script command could not be started or run.
+    ExecutionError {
+        script: String,
+        command: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for CargoScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CargoScriptError::ScriptNotFound { script } => {
+                write!(f, "Script not found: [ {} ]", script)
+            }
+            CargoScriptError::ScriptFileNotFound { path } => {
+                write!(f, "Scripts file not found: [ {} ]. Run `cargo script init` to create one, or pass --scripts-path.", path)
+            }
+            CargoScriptError::InvalidScriptsFile { path, format, message } => {
+                write!(f, "Failed to parse [ {} ] as {}: {}", path, format, message.trim_end())
+            }
+            CargoScriptError::ProfileNotFound { profile, available } if available.is_empty() => {
+                write!(f, "Profile not found: [ {} ]. No profiles are defined; add a [profiles.{}] table.", profile, profile)
+            }
+            CargoScriptError::ProfileNotFound { profile, available } => {
+                write!(f, "Profile not found: [ {} ]. Available profiles: {}", profile, available.join(", "))
+            }
+            CargoScriptError::NothingToBenchmark { script } => {
+                write!(f, "Script [ {} ] has no command of its own to benchmark; benchmark the scripts it includes instead", script)
+            }
+            CargoScriptError::ScriptNameRequired => {
+                write!(f, "No script name given. Pass one, as in `cargo script run <name>`, or run in a terminal to pick one.")
+            }
+            CargoScriptError::NoScriptsDefined { path } => {
+                write!(f, "No scripts defined in [ {} ]. Run `cargo script init` to create a Scripts.toml.", path)
+            }
+            CargoScriptError::NoPreviousRun { path } => {
+                write!(f, "No previous run recorded for [ {} ]. Run a script with `cargo script run <name>` first.", path)
+            }
+            CargoScriptError::ConfirmationDeclined { script } => {
+                write!(f, "Script [ {} ] was not confirmed and did not run (pass --yes to skip confirmations)", script)
+            }
+            CargoScriptError::DirectoryNotFound { path } => {
+                write!(f, "Directory not found: [ {} ]", path)
+            }
+            CargoScriptError::InvalidBaseline { path, message } => {
+                write!(f, "Invalid benchmark baseline [ {} ]: {}", path, message)
+            }
+            CargoScriptError::BenchmarkRegression { scripts, threshold_percent } => {
+                write!(f, "{} script(s) regressed beyond {}%: {}", scripts.len(), threshold_percent, scripts.join(", "))
+            }
+            CargoScriptError::SlowScripts { scripts, threshold } => {
+                write!(f, "{} script(s) ran longer than the slow threshold of {:.2?}: {}", scripts.len(), threshold, scripts.join(", "))
+            }
+            CargoScriptError::ArtifactCaptureFailed { pattern, message } => {
+                write!(f, "Failed to capture artifacts [ {} ]: {}", pattern, message)
+            }
+            CargoScriptError::ImportFailed { path, message } => {
+                write!(f, "Failed to import [ {} ]: {}", path, message)
+            }
+            CargoScriptError::DuplicateScript { script, first, second } => {
+                write!(f, "Script [ {} ] is defined in both {} and {}", script, first, second)
+            }
+            CargoScriptError::IntegrityMismatch { path, expected, actual } => {
+                write!(f, "Integrity check failed for [ {} ]: expected sha256 {}, found {}. Refusing to run.", path, expected, actual)
+            }
+            CargoScriptError::CommandFileFailed { script, path, message } => {
+                write!(f, "Script [ {} ] command_file [ {} ]: {}", script, path, message)
+            }
+            CargoScriptError::CacheFailed { script, message } => {
+                write!(f, "Script [ {} ] inputs: {}", script, message)
+            }
+            CargoScriptError::NotAGitRepository { path } => {
+                write!(f, "--changed needs a git repository, but [ {} ] is not inside one (no .git directory found)", path)
+            }
+            CargoScriptError::GitFailed { git_ref, message } => {
+                write!(f, "Failed to list the files changed since [ {} ]: {}", git_ref, message)
+            }
+            CargoScriptError::UndefinedVariable { script, variable } => {
+                write!(f, "Script [ {} ] references undefined variable ${{{}}} (write $${{{}}} to leave it to the shell)", script, variable, variable)
+            }
+            CargoScriptError::SelfInvocation { script } => {
+                write!(f, "Script [ {} ] runs `cargo script run {}` again, which would never stop. Use `include` to run other scripts from a script.", script, script)
+            }
+            CargoScriptError::UnknownField { script, field, suggestion: Some(suggestion) } => {
+                write!(f, "Script [ {} ] has an unknown field `{}`. Did you mean `{}`?", script, field, suggestion)
+            }
+            CargoScriptError::UnknownField { script, field, suggestion: None } => {
+                write!(f, "Script [ {} ] has an unknown field `{}`. Run `cargo script schema` to see the accepted fields.", script, field)
+            }
+            CargoScriptError::CircularInclude { chain } => {
+                write!(f, "Circular include detected: {}", chain.join(" -> "))
+            }
+            CargoScriptError::ValidationFailed { message } => {
+                write!(f, "Validation failed: {}", message)
+            }
+            CargoScriptError::InvalidNamePattern { pattern, message } => {
+                write!(f, "Invalid [settings] name_pattern [ {} ]: {}", pattern, message)
+            }
+            CargoScriptError::RequirementFailed { message, .. } => {
+                write!(f, "Requirement check failed: {}", message)
+            }
+            CargoScriptError::MetricsOutputFailed { path, message } => {
+                write!(f, "Failed to write metrics [ {} ]: {}", path, message)
+            }
+            CargoScriptError::LogFileFailed { path, message } => {
+                write!(f, "Failed to create log file [ {} ]: {}", path, message)
+            }
+            CargoScriptError::EnvFileFailed { path, message } => {
+                write!(f, "Failed to load env file [ {} ]: {}", path, message)
+            }
+            CargoScriptError::StepOutOfRange { script, step, steps } => {
+                write!(f, "Step {} is out of range for [ {} ]: valid steps are 1-{}", step, script, steps)
+            }
+            CargoScriptError::Timeout { script, seconds } => {
+                write!(f, "Script [ {} ] timed out after {}s and was killed", script, seconds)
+            }
+            CargoScriptError::NoCommandForPlatform { script, os, platforms } => {
+                write!(f, "Script [ {} ] has no command for {}: it only defines {} and no base `command`", script, os, platforms.join(", "))
+            }
+            CargoScriptError::ScriptsFailed { failures } => {
+                write!(f, "{} script(s) failed", failures.len())
+            }
+            CargoScriptError::ExecutionFailed { script, command, message, .. } => {
+                write!(f, "Script [ {} ] failed: {} (command: {})", script, message, command)
+            }
+            CargoScriptError::ExecutionError { script, command, message } => {
+                write!(f, "Script [ {} ] failed: {} (command: {})", script, message, command)
+            }
+        }
+    }
+}
+
+impl CargoScriptError {
+    /// The status the process exits with when this error ends the run.
+    ///
+    /// A failed command passes on its own exit code, and a `--keep-going` run the code of its
+    /// first failure. Every other error exits with 1.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CargoScriptError::ExecutionFailed { code, .. } if *code != 0 => *code,
+            CargoScriptError::ScriptsFailed { failures } => failures.first().map_or(1, CargoScriptError::exit_code),
+            _ => 1,
+        }
+    }
+
+    /// The scripts whose own step failed, for `run --select-failed`.
+    pub fn failed_scripts(&self) -> Vec<String> {
+        match self {
+            CargoScriptError::ScriptsFailed { failures } => failures.iter().flat_map(CargoScriptError::failed_scripts).collect(),
+            CargoScriptError::ExecutionFailed { script, .. }
+            | CargoScriptError::ExecutionError { script, .. }
+            | CargoScriptError::Timeout { script, .. }
+            | CargoScriptError::RequirementFailed { script, .. }
+            | CargoScriptError::NoCommandForPlatform { script, .. }
+            | CargoScriptError::CommandFileFailed { script, .. }
+            | CargoScriptError::CacheFailed { script, .. }
+            | CargoScriptError::UndefinedVariable { script, .. } => vec![script.clone()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl std::error::Error for CargoScriptError {}
+
+/// Return the closest candidate to a misspelled name, for "did you mean" suggestions.
+///
+/// Candidates more than a third of the name's length away (and at least 2 edits) are not
+/// considered close.
+///
+/// # Arguments
+///
+/// * `name` - The misspelled name.
+/// * `candidates` - The valid names.
+pub fn closest_match<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    candidates.into_iter()
+        .map(|candidate| (levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Return the number of single-character insertions, deletions, and substitutions that turn
+/// one string into another.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
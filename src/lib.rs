@@ -3,4 +3,5 @@
 //! This module defines the main library components, including commands and the start logic.
 
 pub mod commands;
+pub mod error;
 pub mod start;
\ No newline at end of file
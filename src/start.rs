@@ -1,9 +1,11 @@
 //! This module contains the main logic for the cargo-script CLI tool.
 //!
 //! It parses the command-line arguments and executes the appropriate commands.
-use crate::commands::{init::init_script_file, script::run_script, Commands, script::Scripts, show::show_scripts};
-use std::fs;
-use clap::Parser;
+use crate::commands::{OutputFormat, analysis::{check_unknown_fields, name_pattern, shell_escape_check, validate, verify_commands, ValidateOptions}, artifacts::capture_artifacts, bench::print_bench_stats, changed::ChangedFiles, completions::{complete_script_names, generate_completions}, env_schema::{dump_env_schema, report_unused_env}, format::{self, ScriptsFormat}, graph::{print_graph, GraphFormat}, history::{read_history, read_last_run, record_history, show_history, write_last_run, LastRun}, init::init_script_file, integrity::{sha256_hex, verify_scripts_hash}, interpreters::list_interpreters, interrupt, log_file::LogFile, metrics::{compare_with_baseline, BenchmarkCompare}, plan::print_plan, print_env::print_env, requirements::list_requirements, schema::print_schema, select::interactive_select_script, script::{bench_script, dry_run_script, print_includes_order, run_script, scripts_dir, scripts_dir_files, RunOptions}, Commands, script::Scripts, show::{list_scripts, show_script_info, show_scripts, show_scripts_json, show_scripts_tree, ShowFilter}, watch::watch};
+use crate::error::CargoScriptError;
+use emoji::symbols;
+use std::{collections::HashMap, env, ffi::OsString, fs, io, path::{Path, PathBuf}, time::{Instant, SystemTime}};
+use clap::{CommandFactory, Parser};
 use colored::*;
 
 /// Command-line arguments structure for the cargo-script CLI tool.
@@ -12,9 +14,30 @@ use colored::*;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
-    /// Optional path to the Scripts.toml file.
-    #[arg(long, default_value = "Scripts.toml", global = true)]
-    scripts_path: String,
+    /// Optional path to the Scripts.toml file, or to a directory of scripts files to merge (TOML, YAML, or JSON). Always wins over `--scripts-env`.
+    /// Without it, the nearest Scripts.toml in the current directory or one of its parents is used.
+    #[arg(long, global = true)]
+    scripts_path: Option<String>,
+    /// Optional environment name used to prefer `Scripts.<env>.toml` (defaults to `CARGO_SCRIPT_ENV`).
+    #[arg(long, global = true)]
+    scripts_env: Option<String>,
+    /// Exit with an error when the scripts file defines no scripts.
+    #[arg(long, global = true)]
+    fail_if_no_scripts: bool,
+    /// Run scripts marked with `confirm` without asking, for automation.
+    #[arg(short, long, global = true)]
+    yes: bool,
+    /// Do not print the banner.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Also write the output of every command `run` starts to this file, after a header line
+    /// naming its script and start time. The file is truncated once per run.
+    #[arg(long, global = true, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+    /// Print the banner even for runs that are otherwise quiet, such as `--quiet-success`, echo
+    /// the lines of command arrays before they run, and print long descriptions in `show`.
+    #[arg(short, long, global = true)]
+    verbose: bool,
 }
 
 /// Run unction that parses command-line arguments and executes the specified command.
@@ -26,28 +49,545 @@ struct Cli {
 ///
 /// This function will panic if it fails to read or parse the `Scripts.toml` file.
 pub fn run() {
-    let init_msg = format!("A CLI tool to run custom scripts in Rust, defined in [ Scripts.toml ] {}", emoji::objects::computer::FLOPPY_DISK.glyph);
-    print_framed_message(&init_msg);
+    let cli = Cli::parse_from(cli_args());
+    interrupt::install_handler();
 
-    let cli = Cli::parse();
-    
-    let scripts_path = &cli.scripts_path;
+    let scripts_env = cli.scripts_env.clone().or_else(|| env::var("CARGO_SCRIPT_ENV").ok());
+    let scripts_path = &resolve_scripts_path(cli.scripts_path.as_deref(), scripts_env.as_deref(), cli.verbose);
+
+    if should_display_banner(&cli.command, cli.quiet, cli.verbose, Path::new(scripts_path).exists()) {
+        let init_msg = format!("A CLI tool to run custom scripts in Rust, defined in [ Scripts.toml ] {}", emoji::objects::computer::FLOPPY_DISK.glyph);
+        print_framed_message(&init_msg);
+    }
 
     match &cli.command {
-        Commands::Run { script, env } => {
-            let scripts: Scripts = toml::from_str(&fs::read_to_string(scripts_path).expect("Fail to load Scripts.toml"))
-                .expect("Fail to parse Scripts.toml");
-            run_script(&scripts, script, env.clone());
+        Commands::Run {
+            script,
+            select_failed,
+            env,
+            quiet_success,
+            working_set,
+            benchmark_compare,
+            regression_threshold,
+            min_delta_ms,
+            update_baseline,
+            shell_escape_check: escape_check,
+            trace_requirements,
+            sandbox,
+            isolate_cwd,
+            keep_isolated,
+            verify_hash,
+            print_stage_timings,
+            error_context,
+            capture_artifacts: artifact_patterns,
+            artifacts_dir,
+            require_artifacts,
+            metrics_format,
+            metrics_output,
+            no_metrics,
+            no_cache,
+            changed,
+            no_hooks,
+            profile,
+            warn_slow,
+            fail_slow,
+            label,
+            ignore_exit_codes,
+            notify_on_long,
+            tail,
+            step,
+            only,
+            skip,
+            timeout,
+            retries,
+            explain_failure,
+            keep_going,
+            pipefail_report,
+            json_stream,
+            include_output,
+            strict,
+            dry_run,
+            verify_commands: check_commands,
+            env_diff,
+            prefix,
+            use_shell_env,
+            show_secrets,
+            dump_env_schema: dump_schema,
+            report_unused_env: report_unused,
+            print_includes_order: print_order,
+            format,
+            args,
+        } => {
+            let scripts = load_verified_scripts(scripts_path, cli.fail_if_no_scripts, verify_hash.as_deref());
+            let mut dry_run = *dry_run;
+            let (targets, env) = match script.as_deref() {
+                _ if *select_failed => {
+                    let Some(targets) = select_failed_scripts(scripts_path) else { return };
+                    (targets, env.clone())
+                }
+                None => {
+                    let Some(selection) = interactive_select_script(&scripts, scripts_path, cli.quiet).unwrap_or_else(|e| exit_with_error(e)) else {
+                        println!("Operation cancelled.");
+                        return;
+                    };
+                    dry_run |= selection.dry_run;
+                    // Overrides entered at the prompt come last so they win over `--env`.
+                    (vec![selection.script], env.iter().cloned().chain(selection.env).collect())
+                }
+                Some("-") => {
+                    let last_run = read_last_run(scripts_path).unwrap_or_else(|| {
+                        exit_with_error(CargoScriptError::NoPreviousRun { path: scripts_path.to_string() })
+                    });
+                    // New overrides come last so they win over the recorded ones.
+                    let env: Vec<String> = last_run.env.iter().chain(env).cloned().collect();
+                    (vec![last_run.script], env)
+                }
+                Some(script) => (vec![script.to_string()], env.clone()),
+            };
+            let (targets, env) = (&targets, &env);
+            if *dump_schema {
+                dump_env_schema(&scripts, &targets[0], env, *format, *show_secrets).unwrap_or_else(|e| exit_with_error(e));
+                return;
+            }
+            if *report_unused {
+                report_unused_env(&scripts, &targets[0], *format).unwrap_or_else(|e| exit_with_error(e));
+                return;
+            }
+            if *escape_check {
+                for script in targets {
+                    shell_escape_check(&scripts, script, *strict).unwrap_or_else(|e| exit_with_error(e));
+                }
+            }
+            let mut options = RunOptions {
+                env_overrides: env.clone(),
+                quiet_success: *quiet_success,
+                scripts_dir: scripts_dir(scripts_path),
+                working_set: working_set.as_ref().map(|dir| {
+                    fs::canonicalize(dir).unwrap_or_else(|_| exit_with_error(CargoScriptError::DirectoryNotFound { path: dir.clone() }))
+                }),
+                trace_requirements: *trace_requirements,
+                sandbox: *sandbox,
+                isolate_cwd: *isolate_cwd,
+                keep_isolated: *keep_isolated,
+                print_stage_timings: *print_stage_timings,
+                error_context: *error_context,
+                label: label.clone(),
+                ignore_exit_codes: ignore_exit_codes.clone(),
+                notify_on_long: *notify_on_long,
+                explain_failure: *explain_failure,
+                args: args.clone(),
+                timeout: *timeout,
+                retries: *retries,
+                step: *step,
+                only: only.clone(),
+                skip: skip.clone(),
+                tail: tail.as_ref().map(PathBuf::from),
+                metrics_format: *metrics_format,
+                metrics_output: metrics_output.as_ref().map(PathBuf::from),
+                no_metrics: *no_metrics,
+                no_cache: *no_cache,
+                changed: None,
+                verbose: cli.verbose,
+                no_hooks: *no_hooks,
+                profile: profile.clone(),
+                warn_slow: *warn_slow,
+                fail_slow: *fail_slow,
+                show_secrets: *show_secrets,
+                env_diff: *env_diff,
+                use_shell_env: *use_shell_env,
+                prefix_output: *prefix,
+                yes: cli.yes,
+                keep_going: *keep_going,
+                pipefail_report: *pipefail_report,
+                json_stream: *json_stream,
+                include_output: *include_output,
+                log_file: None,
+            };
+            if *print_order {
+                print_includes_order(&scripts, &targets[0], &options).unwrap_or_else(|e| exit_with_error(e));
+                return;
+            }
+            // An unknown profile is reported before anything runs.
+            scripts.profile_env(options.profile.as_deref()).unwrap_or_else(|e| exit_with_error(e));
+            if let Some(git_ref) = changed {
+                options.changed = Some(ChangedFiles::since(options.base_dir(), git_ref).unwrap_or_else(|e| exit_with_error(e)));
+            }
+            if dry_run {
+                for script in targets {
+                    dry_run_script(&scripts, script, &options).unwrap_or_else(|e| exit_with_error(e));
+                    if *check_commands {
+                        verify_commands(&scripts, script, *strict).unwrap_or_else(|e| exit_with_error(e));
+                    }
+                }
+                return;
+            }
+            // Created once, so the output of every target ends up in the same file.
+            options.log_file = cli.log_file.as_deref().map(|path| LogFile::create(path).unwrap_or_else(|e| exit_with_error(e)));
+            let (started, start_time) = (SystemTime::now(), Instant::now());
+            let mut durations = HashMap::new();
+            let mut failures = Vec::new();
+            for script in targets {
+                match run_script(&scripts, script, &options) {
+                    Ok(script_durations) => durations.extend(script_durations),
+                    // With --keep-going, the remaining scripts still run and the failures are summarized at the end.
+                    Err(e) if *keep_going && targets.len() > 1 => {
+                        eprintln!("{} {}", symbols::other_symbol::CROSS_MARK.glyph, e.to_string().red());
+                        match e {
+                            CargoScriptError::ScriptsFailed { failures: inner } => failures.extend(inner),
+                            e => failures.push(e),
+                        }
+                    }
+                    Err(e) => {
+                        failures.push(e);
+                        break;
+                    }
+                }
+            }
+            let failed: Vec<String> = failures.iter().flat_map(CargoScriptError::failed_scripts).collect();
+            record_history(scripts_path, &targets.join(", "), label.as_deref(), started, start_time.elapsed(), failures.is_empty(), &failed);
+            if targets.len() > 1 && *keep_going && !failures.is_empty() {
+                exit_with_error(CargoScriptError::ScriptsFailed { failures });
+            }
+            if let Some(e) = failures.pop() {
+                exit_with_error(e);
+            }
+            if let [script] = targets.as_slice() {
+                write_last_run(scripts_path, &LastRun { script: script.clone(), env: env.clone() });
+            }
+            if !artifact_patterns.is_empty() {
+                capture_artifacts(artifact_patterns, options.base_dir(), Path::new(artifacts_dir), *require_artifacts)
+                    .unwrap_or_else(|e| exit_with_error(e));
+            }
+            if let Some(baseline) = benchmark_compare {
+                let compare = BenchmarkCompare {
+                    baseline: baseline.clone(),
+                    threshold_percent: *regression_threshold,
+                    min_delta_ms: *min_delta_ms,
+                    update_baseline: *update_baseline,
+                };
+                compare_with_baseline(&durations, &compare).unwrap_or_else(|e| exit_with_error(e));
+            }
+        }
+        Commands::Init { from_existing, interactive, force, format } => {
+            init_script_file(*from_existing, *interactive, *force, *format);
+        }
+        Commands::Show { tree, filter, tag, format } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            let filter = ShowFilter { text: filter.as_deref(), tag: tag.as_deref() };
+            if *format == OutputFormat::Json {
+                show_scripts_json(&scripts, &filter);
+            } else if *tree {
+                show_scripts_tree(&scripts, &filter);
+            } else {
+                show_scripts(&scripts, &filter, cli.verbose);
+            }
+        }
+        Commands::Info { script } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            show_script_info(&scripts, script).unwrap_or_else(|e| exit_with_error(e));
+        }
+        Commands::List => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            list_scripts(&scripts);
+        }
+        Commands::Graph { format } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            print_graph(&scripts, *format);
+        }
+        Commands::CompleteScripts => {
+            complete_script_names(scripts_path);
+        }
+        Commands::Completions { shell, out_dir, dry_run } => {
+            generate_completions(*shell, &mut Cli::command(), out_dir.as_deref().map(Path::new), *dry_run)
+                .expect("Failed to write completions");
+        }
+        Commands::Validate { check_shebang, strict, strict_names } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            let options = ValidateOptions { check_shebang: *check_shebang, strict_names: *strict_names, scripts_dir: scripts_dir(scripts_path) };
+            validate(&scripts, &options, *strict).unwrap_or_else(|e| exit_with_error(e));
+        }
+        Commands::Schema => {
+            print_schema();
+        }
+        Commands::Hash => {
+            println!("{}", sha256_hex(read_scripts_content(scripts_path).as_bytes()));
+        }
+        Commands::History { limit, format } => {
+            show_history(scripts_path, *limit, *format);
+        }
+        Commands::Interpreters { format } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            list_interpreters(&scripts, *format);
+        }
+        Commands::Requirements { format } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            list_requirements(&scripts, *format);
+        }
+        Commands::Bench { script, runs, warmup, ignore_failures, env } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            let options = RunOptions { env_overrides: env.clone(), scripts_dir: scripts_dir(scripts_path), yes: cli.yes, ..RunOptions::default() };
+            let (durations, failed) = bench_script(&scripts, script, &options, *runs, *warmup, *ignore_failures).unwrap_or_else(|e| exit_with_error(e));
+            print_bench_stats(script, &durations, *warmup, failed);
+        }
+        Commands::Env { script, env, profile, format, show_secrets } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            let options = RunOptions {
+                env_overrides: env.clone(),
+                scripts_dir: scripts_dir(scripts_path),
+                profile: profile.clone(),
+                show_secrets: *show_secrets,
+                ..RunOptions::default()
+            };
+            print_env(&scripts, script, &options, *format).unwrap_or_else(|e| exit_with_error(e));
+        }
+        Commands::Explain { script, env, profile, no_hooks, use_shell_env, show_secrets } => {
+            let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+            let options = RunOptions {
+                env_overrides: env.clone(),
+                scripts_dir: scripts_dir(scripts_path),
+                profile: profile.clone(),
+                no_hooks: *no_hooks,
+                use_shell_env: *use_shell_env,
+                show_secrets: *show_secrets,
+                ..RunOptions::default()
+            };
+            print_plan(&scripts, script, &options).unwrap_or_else(|e| exit_with_error(e));
+        }
+        Commands::Watch { script, env, path, clear } => {
+            let paths: Vec<PathBuf> = path.iter().map(PathBuf::from).collect();
+            watch(script, &paths, *clear, || {
+                // Reloaded on every run, so edits to the scripts file take effect.
+                let scripts = load_scripts(scripts_path, cli.fail_if_no_scripts);
+                let options = RunOptions { env_overrides: env.clone(), scripts_dir: scripts_dir(scripts_path), yes: cli.yes, ..RunOptions::default() };
+                if let Err(e) = run_script(&scripts, script, &options) {
+                    eprintln!("{} {}", symbols::other_symbol::CROSS_MARK.glyph, e.to_string().red());
+                }
+            });
+        }
+    }
+}
+
+/// Returns the command-line arguments, without the `script` argument cargo passes when the
+/// tool runs as `cargo script ...`.
+fn cli_args() -> Vec<OsString> {
+    let mut args: Vec<OsString> = env::args_os().collect();
+    if args.get(1).is_some_and(|arg| arg == "script") {
+        args.remove(1);
+    }
+    args
+}
+
+/// Loads and parses the scripts file.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+/// * `fail_if_no_scripts` - Whether to exit with an error when no scripts are defined.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read or parse the file.
+fn load_scripts(scripts_path: &str, fail_if_no_scripts: bool) -> Scripts {
+    let content = read_scripts_content(scripts_path);
+    parse_scripts(scripts_path, &content, fail_if_no_scripts)
+}
+
+/// Reads the scripts file, or the scripts files of a scripts directory joined in load order.
+///
+/// A missing scripts file exits the process with an error.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read the file or directory.
+fn read_scripts_content(scripts_path: &str) -> String {
+    if !Path::new(scripts_path).is_dir() {
+        return match fs::read_to_string(scripts_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                exit_with_error(CargoScriptError::ScriptFileNotFound { path: scripts_path.to_string() })
+            }
+            Err(e) => panic!("Fail to load Scripts.toml: {}", e),
+        };
+    }
+    let files = scripts_dir_files(Path::new(scripts_path)).unwrap_or_else(|e| exit_with_error(e));
+    files.iter().map(|path| fs::read_to_string(path).expect("Fail to load scripts file")).collect::<Vec<_>>().join("\n")
+}
+
+/// Loads the scripts file and checks its integrity before parsing it.
+///
+/// The hash is checked against `expected_hash`, or else against the `.cargo-script.lock` next to
+/// the scripts file when there is one. On mismatch, the process exits before anything runs.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+/// * `fail_if_no_scripts` - Whether to exit with an error when no scripts are defined.
+/// * `expected_hash` - The SHA-256 hash passed with `--verify-hash`, if any.
+///
+/// # Panics
+///
+/// This function will panic if it fails to read or parse the file.
+fn load_verified_scripts(scripts_path: &str, fail_if_no_scripts: bool, expected_hash: Option<&str>) -> Scripts {
+    let content = read_scripts_content(scripts_path);
+    verify_scripts_hash(scripts_path, content.as_bytes(), expected_hash).unwrap_or_else(|e| exit_with_error(e));
+    parse_scripts(scripts_path, &content, fail_if_no_scripts)
+}
+
+/// Parses the content of the scripts file and merges the files it imports.
+///
+/// The file is parsed as TOML, YAML, or JSON depending on its extension. A scripts directory is
+/// loaded file by file instead, and the files are merged. Content that cannot be parsed exits
+/// the process with an error.
+fn parse_scripts(scripts_path: &str, content: &str, fail_if_no_scripts: bool) -> Scripts {
+    let (scripts, warnings) = if Path::new(scripts_path).is_dir() {
+        Scripts::load_dir(Path::new(scripts_path)).unwrap_or_else(|e| exit_with_error(e))
+    } else {
+        let format = ScriptsFormat::from_path(Path::new(scripts_path));
+        let mut scripts: Scripts = format.parse(content).unwrap_or_else(|message| {
+            exit_with_error(CargoScriptError::InvalidScriptsFile { path: scripts_path.to_string(), format: format.name(), message })
+        });
+        let warnings = scripts.merge_imports(scripts_path).unwrap_or_else(|e| exit_with_error(e));
+        (scripts, warnings)
+    };
+    for warning in warnings {
+        eprintln!("{}  {}: {}", symbols::warning::WARNING.glyph, "Import".yellow(), warning);
+    }
+    if fail_if_no_scripts && scripts.scripts.is_empty() {
+        exit_with_error(CargoScriptError::NoScriptsDefined { path: scripts_path.to_string() });
+    }
+    name_pattern(&scripts).unwrap_or_else(|e| exit_with_error(e));
+    check_unknown_fields(&scripts).unwrap_or_else(|e| exit_with_error(e));
+    scripts
+}
+
+/// Returns the scripts that failed in the last recorded run of a scripts file, for
+/// `run --select-failed`.
+///
+/// When nothing failed, or no run is recorded, this is reported and `None` is returned.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The path of the scripts file.
+///
+fn select_failed_scripts(scripts_path: &str) -> Option<Vec<String>> {
+    match read_history(scripts_path).pop() {
+        Some(last) if !last.success => {
+            let failed = last.failed_scripts();
+            println!("{}: {}", "Rerunning failed scripts".yellow(), failed.join(", "));
+            Some(failed)
+        }
+        Some(last) => {
+            println!("{}: the last run ([ {} ]) succeeded", "Nothing to rerun".green(), last.script);
+            None
         }
-        Commands::Init => {
-            init_script_file();
+        None => {
+            println!("{}: no runs are recorded for {}", "Nothing to rerun".green(), scripts_path);
+            None
         }
-        Commands::Show => {
-            let scripts: Scripts = toml::from_str(&fs::read_to_string(scripts_path).expect("Fail to load Scripts.toml"))
-                .expect("Fail to parse Scripts.toml");
-            show_scripts(&scripts);
+    }
+}
+
+/// Prints an error and exits the process with a failure status.
+///
+/// When a script command failed, the process exits with the command's own exit code.
+///
+/// # Arguments
+///
+/// * `error` - The error to report.
+///
+fn exit_with_error(error: CargoScriptError) -> ! {
+    eprintln!("{} {}", symbols::other_symbol::CROSS_MARK.glyph, error.to_string().red());
+    std::process::exit(error.exit_code());
+}
+
+/// Resolves the path of the scripts file to load.
+///
+/// An explicit `--scripts-path` always wins. Otherwise the current directory and then each of
+/// its parents is searched, like cargo looks for `Cargo.toml`, and the nearest scripts file is
+/// used. When an environment name is given (via `--scripts-env` or `CARGO_SCRIPT_ENV`),
+/// `Scripts.<env>.toml` is preferred over `Scripts.toml` in the same directory. In each
+/// directory, `.toml` is looked up first, then `.yaml`, `.yml`, and `.json`.
+///
+/// A file found in a parent directory is returned with its full path, so relative paths in it
+/// resolve against its own directory. When no file is found, `Scripts.toml` is returned.
+///
+/// # Arguments
+///
+/// * `scripts_path` - The explicit path passed on the command line, if any.
+/// * `scripts_env` - The selected environment name, if any.
+/// * `verbose` - Whether to report when the environment-specific file is missing.
+///
+fn resolve_scripts_path(scripts_path: Option<&str>, scripts_env: Option<&str>, verbose: bool) -> String {
+    if let Some(path) = scripts_path {
+        return path.to_string();
+    }
+    let mut stems = Vec::new();
+    let env_stem = scripts_env.filter(|e| !e.is_empty()).map(|env_name| format!("Scripts.{}", env_name));
+    stems.extend(env_stem.clone());
+    stems.push("Scripts".to_string());
+    let file_names: Vec<String> = stems.iter()
+        .flat_map(|stem| format::EXTENSIONS.iter().map(move |ext| format!("{}.{}", stem, ext)))
+        .collect();
+
+    let current_dir = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    for (depth, dir) in current_dir.ancestors().enumerate() {
+        for file_name in &file_names {
+            let path = dir.join(file_name);
+            if path.is_file() {
+                // Files in the current directory keep their short name in messages and history.
+                let path = if depth == 0 { file_name.clone() } else { path.display().to_string() };
+                if let Some(env_stem) = env_stem.as_ref().filter(|stem| verbose && !file_name.starts_with(&format!("{}.", stem))) {
+                    eprintln!("{}.toml not found, using {}", env_stem, path);
+                }
+                return path;
+            }
         }
     }
+    "Scripts.toml".to_string()
+}
+
+/// Decides whether the banner is printed before a command runs.
+///
+/// `quiet` always wins. Commands whose output is meant for other programs (completions, JSON,
+/// DOT, dry-run plans, hashes, environments) never get the banner, even with `verbose`. Runs
+/// that asked to be quiet on success get it only with `verbose`, or on a first run, when there
+/// is no scripts file yet. Every other command, such as `show`, `init`, or `validate`, always gets it.
+///
+/// # Arguments
+///
+/// * `command` - The command being run.
+/// * `quiet` - Whether `--quiet` was passed.
+/// * `verbose` - Whether `--verbose` was passed.
+/// * `scripts_exist` - Whether the scripts file exists.
+pub fn should_display_banner(command: &Commands, quiet: bool, verbose: bool, scripts_exist: bool) -> bool {
+    if quiet {
+        return false;
+    }
+    let machine_output = matches!(command,
+        Commands::Completions { .. }
+        | Commands::CompleteScripts
+        | Commands::Graph { format: GraphFormat::Dot }
+        | Commands::Env { .. }
+        | Commands::Explain { .. }
+        | Commands::Hash
+        | Commands::List
+        | Commands::Schema
+        | Commands::Run { json_stream: true, .. }
+        | Commands::Run { dry_run: true, .. }
+        | Commands::Run { dump_env_schema: true, .. }
+        | Commands::Run { report_unused_env: true, format: OutputFormat::Json, .. }
+        | Commands::Run { print_includes_order: true, .. }
+        | Commands::History { format: OutputFormat::Json, .. }
+        | Commands::Interpreters { format: OutputFormat::Json }
+        | Commands::Requirements { format: OutputFormat::Json }
+        | Commands::Show { format: OutputFormat::Json, .. });
+    if machine_output {
+        return false;
+    }
+    if matches!(command, Commands::Run { quiet_success: true, .. }) {
+        return verbose || !scripts_exist;
+    }
+    true
 }
 
 /// Prints a framed message with a dashed line frame.
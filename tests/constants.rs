@@ -1 +1,4 @@
-pub const SCRIPT_TOML: &str = "./tests/Scripts_test.toml";
\ No newline at end of file
+#[allow(dead_code)]
+pub const SCRIPT_TOML: &str = "./tests/Scripts_test.toml";
+#[allow(dead_code)]
+pub const EMPTY_SCRIPT_TOML: &str = "./tests/Scripts_empty.toml";
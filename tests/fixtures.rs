@@ -0,0 +1,35 @@
+//! Temporary projects shared by the integration tests.
+
+use std::{fs, path::PathBuf};
+
+/// Create a fresh temporary project directory whose `Scripts.toml` holds `scripts`, and return
+/// the directory.
+#[allow(dead_code)]
+pub fn project(name: &str, scripts: &str) -> PathBuf {
+    let dir = fresh_dir(name);
+    fs::write(dir.join("Scripts.toml"), scripts).unwrap();
+    dir
+}
+
+/// Create a fresh temporary project like [`project`], and return the path of its scripts file.
+#[allow(dead_code)]
+pub fn scripts_file(name: &str, scripts: &str) -> String {
+    named_scripts_file(name, "Scripts.toml", scripts)
+}
+
+/// Create a fresh temporary directory holding a scripts file named `file_name`, and return the
+/// path of the file.
+#[allow(dead_code)]
+pub fn named_scripts_file(name: &str, file_name: &str, scripts: &str) -> String {
+    let path = fresh_dir(name).join(file_name);
+    fs::write(&path, scripts).unwrap();
+    path.display().to_string()
+}
+
+/// Create an empty temporary directory, removing whatever an earlier run left in it.
+fn fresh_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,40 @@
+use assert_cmd::Command;
+
+mod fixtures;
+
+/// Scripts with one script that generates files in nested directories.
+const SCRIPTS: &str = "[scripts]\ngenerate = \"mkdir -p out/reports && echo a > out/a.txt && echo b > out/reports/b.txt && echo log > out/run.log\"\n";
+
+/// Tests that files generated by the run are captured with their relative paths preserved.
+#[test]
+fn test_capture_artifacts() {
+    let dir = fixtures::project("cargo_script_capture_artifacts", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "generate", "--capture-artifacts", "out/**/*.txt", "--artifacts-dir", "collected"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 file(s) captured"));
+    assert!(dir.join("collected/out/a.txt").exists());
+    assert!(dir.join("collected/out/reports/b.txt").exists());
+    assert!(!dir.join("collected/out/run.log").exists());
+}
+
+/// Tests that a pattern without matches only warns, unless `--require-artifacts` is given.
+#[test]
+fn test_capture_artifacts_missing() {
+    let dir = fixtures::project("cargo_script_capture_artifacts_missing", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "generate", "--capture-artifacts", "*.zip"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("no files matched [ *.zip ]"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "generate", "--capture-artifacts", "*.zip", "--require-artifacts"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to capture artifacts [ *.zip ]: no files matched"));
+}
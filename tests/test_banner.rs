@@ -0,0 +1,87 @@
+use assert_cmd::Command;
+use cargo_run::commands::Commands;
+use cargo_run::start::should_display_banner;
+use clap::Parser;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+const BANNER: &str = "A CLI tool to run custom scripts in Rust";
+
+/// Parses a command line into the command it runs.
+fn command(args: &[&str]) -> Commands {
+    #[derive(Parser)]
+    struct Cli {
+        #[command(subcommand)]
+        command: Commands,
+    }
+    Cli::parse_from(std::iter::once("cargo-script").chain(args.iter().copied())).command
+}
+
+/// Tests that completions never get the banner, even with `--verbose` or on a first run.
+#[test]
+fn test_banner_never_for_completions() {
+    let completions = command(&["completions", "bash"]);
+    assert!(!should_display_banner(&completions, false, false, true));
+    assert!(!should_display_banner(&completions, false, true, true));
+    assert!(!should_display_banner(&completions, false, false, false));
+}
+
+/// Tests that dry-run never gets the banner.
+#[test]
+fn test_banner_never_for_dry_run() {
+    let dry_run = command(&["run", "build", "--dry-run"]);
+    assert!(!should_display_banner(&dry_run, false, false, true));
+    assert!(!should_display_banner(&dry_run, false, true, false));
+}
+
+/// Tests that `--verbose` shows the banner for a run that is quiet on success.
+#[test]
+fn test_banner_verbose_always() {
+    let quiet_run = command(&["run", "build", "--quiet-success"]);
+    assert!(!should_display_banner(&quiet_run, false, false, true));
+    assert!(should_display_banner(&quiet_run, false, true, true));
+}
+
+/// Tests that a first run, without a scripts file, shows the banner.
+#[test]
+fn test_banner_first_run_always() {
+    let quiet_run = command(&["run", "build", "--quiet-success"]);
+    assert!(should_display_banner(&quiet_run, false, false, false));
+}
+
+/// Tests that `show`, `init`, and `validate` always show the banner.
+#[test]
+fn test_banner_always_for_show_init_validate() {
+    for args in [&["show"][..], &["init"], &["validate"]] {
+        let command = command(args);
+        assert!(should_display_banner(&command, false, false, true), "{:?}", args);
+        assert!(should_display_banner(&command, false, false, false), "{:?}", args);
+    }
+}
+
+/// Tests that `--quiet` wins over everything, including a first run and `--verbose`.
+#[test]
+fn test_banner_quiet_wins() {
+    for args in [&["run", "build"][..], &["show"], &["init"], &["run", "build", "--quiet-success"]] {
+        let command = command(args);
+        assert!(!should_display_banner(&command, true, false, false), "{:?}", args);
+        assert!(!should_display_banner(&command, true, true, true), "{:?}", args);
+    }
+}
+
+/// Tests that `--quiet` hides the banner when running from the command line.
+#[test]
+fn test_quiet_flag_hides_banner() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(BANNER));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--quiet", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(BANNER).not());
+}
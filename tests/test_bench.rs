@@ -0,0 +1,72 @@
+use assert_cmd::Command;
+use cargo_run::commands::bench::BenchStats;
+use predicates::prelude::*;
+use std::time::Duration;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests the statistics computed from a set of durations.
+#[test]
+fn test_bench_stats() {
+    let ms = Duration::from_millis;
+    let stats = BenchStats::from_durations(&[ms(40), ms(10), ms(30), ms(20)]).unwrap();
+    assert_eq!(stats.min, ms(10));
+    assert_eq!(stats.max, ms(40));
+    assert_eq!(stats.mean, ms(25));
+    assert_eq!(stats.median, ms(25));
+    assert!((stats.stddev.as_secs_f64() - 0.012_91).abs() < 0.000_01, "{:?}", stats.stddev);
+
+    let single = BenchStats::from_durations(&[ms(7)]).unwrap();
+    assert_eq!((single.median, single.stddev), (ms(7), Duration::ZERO));
+    assert!(BenchStats::from_durations(&[]).is_none());
+}
+
+/// Tests that `bench` runs the command the requested number of times, after the warm-up runs,
+/// and prints the statistics without the command's output.
+#[test]
+fn test_bench_runs_and_warmup() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["bench", "bench_fast", "--runs", "3", "--warmup", "2", "--scripts-path", SCRIPT_TOML])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Warmup 2/2"))
+        .stdout(predicates::str::contains("Run 3/3"))
+        .stdout(predicates::str::contains("Run 4/3").not())
+        .stdout(predicates::str::contains("Benchmark [ bench_fast ] (3 run(s), 2 warmup)"))
+        .stdout(predicates::str::contains("median"))
+        .stdout(predicates::str::contains("stddev"))
+        .stdout(predicates::str::contains("bench output").not());
+}
+
+/// Tests that a failing run stops the benchmark and shows its output, unless `--ignore-failures`.
+#[test]
+fn test_bench_failures() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["bench", "bench_fail", "--runs", "3", "--scripts-path", SCRIPT_TOML])
+        .env("NO_COLOR", "1")
+        .assert()
+        .failure()
+        .code(3)
+        .stdout(predicates::str::contains("bench failure output"))
+        .stdout(predicates::str::contains("Run 2/3").not())
+        .stdout(predicates::str::contains("Benchmark [").not());
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["bench", "bench_fail", "--runs", "3", "--ignore-failures", "--scripts-path", SCRIPT_TOML])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Benchmark [ bench_fail ] (3 run(s), 3 failed)"));
+}
+
+/// Tests that a script that only includes other scripts cannot be benchmarked.
+#[test]
+fn test_bench_include_only_script() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["bench", "release", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("has no command of its own to benchmark"));
+}
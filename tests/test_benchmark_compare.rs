@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+use std::fs;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--update-baseline` writes a baseline that a later run can compare against.
+#[test]
+fn test_benchmark_update_and_compare() {
+    let baseline = std::env::temp_dir().join("cargo_script_baseline_ok.json");
+    let baseline = baseline.to_str().unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "build", "--benchmark-compare", baseline, "--update-baseline", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(baseline).unwrap().contains("\"build\""));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "build", "--benchmark-compare", baseline, "--min-delta-ms", "1000", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Benchmark Comparison"));
+}
+
+/// Tests that a script slower than the baseline allows makes the run fail.
+#[test]
+fn test_benchmark_regression_fails() {
+    let baseline = std::env::temp_dir().join("cargo_script_baseline_regressed.json");
+    fs::write(&baseline, r#"{ "scripts": { "sleepy": 1 }, "total_ms": 1 }"#).unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "sleepy", "--benchmark-compare", baseline.to_str().unwrap(), "--regression-threshold", "20%", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("regressed beyond 20%: sleepy"));
+}
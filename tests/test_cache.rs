@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+mod fixtures;
+
+/// Creates a fresh project whose `gen` script copies `input.txt` to `out.txt`.
+fn setup_project(name: &str) -> PathBuf {
+    let dir = fixtures::project(
+        &format!("cargo_script_cache_{}", name),
+        "[scripts]\ngen = { command = \"cp input.txt out.txt && echo generated\", inputs = [\"input.txt\"], outputs = [\"out.txt\"] }\n",
+    );
+    fs::write(dir.join("input.txt"), "one\n").unwrap();
+    dir
+}
+
+/// Runs `gen` in the project and returns its assertion.
+fn run_gen(dir: &PathBuf, extra: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(dir)
+        .args(["run", "gen"])
+        .args(extra)
+        .assert()
+        .success()
+}
+
+/// Tests that a second run with unchanged inputs is skipped, and a changed input runs again.
+#[test]
+fn test_cache_skips_unchanged_inputs() {
+    let dir = setup_project("unchanged");
+    run_gen(&dir, &[]).stdout(predicate::str::contains("generated"));
+    run_gen(&dir, &[])
+        .stdout(predicate::str::contains("Cached: [ gen ] inputs unchanged, skipping"))
+        .stdout(predicate::str::contains("generated").not());
+
+    fs::write(dir.join("input.txt"), "two\n").unwrap();
+    run_gen(&dir, &[]).stdout(predicate::str::contains("generated"));
+}
+
+/// Tests that a missing output makes the script run even though its inputs are unchanged.
+#[test]
+fn test_cache_reruns_when_output_missing() {
+    let dir = setup_project("missing_output");
+    run_gen(&dir, &[]).stdout(predicate::str::contains("generated"));
+    fs::remove_file(dir.join("out.txt")).unwrap();
+    run_gen(&dir, &[]).stdout(predicate::str::contains("generated"));
+}
+
+/// Tests that `--no-cache` runs the script even when it is cached.
+#[test]
+fn test_no_cache_forces_run() {
+    let dir = setup_project("no_cache");
+    run_gen(&dir, &[]).stdout(predicate::str::contains("generated"));
+    run_gen(&dir, &["--no-cache"]).stdout(predicate::str::contains("generated"));
+}
+
+/// Tests that scripts whose names differ only in punctuation keep separate cache entries.
+#[test]
+fn test_cache_separates_similar_names() {
+    let dir = fixtures::project(
+        "cargo_script_cache_similar_names",
+        "[scripts]\n\"test:unit\" = { command = \"echo colon\", inputs = [\"input.txt\"] }\ntest_unit = { command = \"echo underscore\", inputs = [\"input.txt\"] }\n",
+    );
+    fs::write(dir.join("input.txt"), "one\n").unwrap();
+    for name in ["test:unit", "test_unit"] {
+        Command::cargo_bin("cargo-script").unwrap().current_dir(&dir).args(["run", name]).assert().success();
+    }
+    for name in ["test:unit", "test_unit"] {
+        Command::cargo_bin("cargo-script").unwrap()
+            .current_dir(&dir)
+            .args(["run", name])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(format!("Cached: [ {} ] inputs unchanged, skipping", name)));
+    }
+}
@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CHANGED_SCRIPTS: &str = r#"[scripts]
+test_api = { command = "echo api tested", inputs = ["api/**/*.rs"] }
+test_web = { command = "echo web tested", inputs = ["web/*.js"] }
+lint = "echo linted"
+ci = { include = ["test_api", "test_web", "lint"] }
+"#;
+
+/// Run git in a directory, failing the test if it fails.
+fn git(dir: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Creates a fresh project with a commit, then changes a file under `api/`.
+fn setup_repo(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cargo_script_changed_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("api/src")).unwrap();
+    fs::create_dir_all(dir.join("web")).unwrap();
+    fs::write(dir.join("Scripts.toml"), CHANGED_SCRIPTS).unwrap();
+    fs::write(dir.join("api/src/lib.rs"), "// one\n").unwrap();
+    fs::write(dir.join("web/app.js"), "// one\n").unwrap();
+    git(&dir, &["init", "-q"]);
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    fs::write(dir.join("api/src/lib.rs"), "// two\n").unwrap();
+    dir
+}
+
+/// Tests that `--changed` skips scripts whose inputs match no changed file, and always runs
+/// scripts without inputs.
+#[test]
+fn test_changed_skips_unaffected_scripts() {
+    let dir = setup_repo("skips");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--no-cache", "--changed", "HEAD"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("api tested"))
+        .stdout(predicates::str::contains("[ test_web ] no inputs changed since HEAD, skipping"))
+        .stdout(predicates::str::contains("web tested").not())
+        .stdout(predicates::str::contains("linted"));
+}
+
+/// Tests that `--changed` reports an unknown ref and a directory outside of a git repository.
+#[test]
+fn test_changed_errors() {
+    let dir = setup_repo("errors");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--changed", "no-such-ref"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to list the files changed since [ no-such-ref ]"));
+
+    let plain = std::env::temp_dir().join("cargo_script_changed_no_git");
+    fs::create_dir_all(&plain).unwrap();
+    fs::write(plain.join("Scripts.toml"), CHANGED_SCRIPTS).unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&plain)
+        .args(["run", "ci", "--changed", "HEAD"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--changed needs a git repository"));
+}
@@ -0,0 +1,46 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that running a self-including script fails with the cycle instead of overflowing the stack.
+#[test]
+fn test_run_self_include() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "self_include", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Circular include detected: self_include -> self_include"));
+}
+
+/// Tests that running a two-node cycle fails with the full cycle, even with `--keep-going`.
+#[test]
+fn test_run_two_node_cycle() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "cycle_a", "--keep-going", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Circular include detected: cycle_a -> cycle_b -> cycle_a"));
+}
+
+/// Tests that dry-run reports the cycle before printing the plan.
+#[test]
+fn test_dry_run_cycle() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "cycle_b", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("Dry run").not())
+        .stderr(predicates::str::contains("Circular include detected: cycle_b -> cycle_a -> cycle_b"));
+}
+
+/// Tests that `validate` reports a self-including script without running anything.
+#[test]
+fn test_validate_self_include() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["validate", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ self_include ] Circular include detected: self_include -> self_include"));
+}
@@ -0,0 +1,95 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+/// Create a project whose scripts keep their bodies in `scripts/`, and return its directory.
+fn project(name: &str) -> std::path::PathBuf {
+    let dir = fixtures::project(name, r#"[scripts]
+deploy = { command_file = "scripts/deploy.sh", interpreter = "bash", info = "Deploy" }
+missing = { command_file = "scripts/missing.sh" }
+both = { command = "echo inline", command_file = "scripts/deploy.sh" }
+"#);
+    fs::create_dir_all(dir.join("scripts")).unwrap();
+    let mut body = "name=world\necho \"deploy ${name}\"\n".to_string();
+    for i in 1..=23 {
+        body.push_str(&format!("echo line {}\n", i));
+    }
+    fs::write(dir.join("scripts").join("deploy.sh"), body).unwrap();
+    dir
+}
+
+/// Tests that a `command_file` is run as written, relative to the scripts file.
+#[test]
+fn test_command_file_runs() {
+    let dir = project("cargo_script_command_file_runs");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--scripts-path", &dir.join("Scripts.toml").display().to_string()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("deploy world"))
+        .stdout(predicates::str::contains("line 23"));
+}
+
+/// Tests that dry-run shows the file and a preview of its contents.
+#[test]
+fn test_command_file_dry_run() {
+    let dir = project("cargo_script_command_file_dry_run");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "deploy", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Command file:"))
+        .stdout(predicates::str::contains("deploy.sh"))
+        .stdout(predicates::str::contains("echo line 18"))
+        .stdout(predicates::str::contains("echo line 19").not())
+        .stdout(predicates::str::contains("... (5 more line(s))"));
+}
+
+/// Tests that a `command_file` is resolved against `--working-set`, in runs and in dry-run.
+#[test]
+fn test_command_file_working_set() {
+    let dir = project("cargo_script_command_file_working_set");
+    let scripts_path = dir.join("Scripts.toml").display().to_string();
+    let working_set = std::env::temp_dir().join("cargo_script_command_file_working_set_tree");
+    fs::create_dir_all(working_set.join("scripts")).unwrap();
+    fs::write(working_set.join("scripts").join("deploy.sh"), "echo deploy from working set\n").unwrap();
+    let working_set = fs::canonicalize(working_set).unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--working-set", &working_set.display().to_string(), "--scripts-path", &scripts_path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("deploy from working set"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--dry-run", "--working-set", &working_set.display().to_string(), "--scripts-path", &scripts_path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(working_set.join("scripts").join("deploy.sh").display().to_string()));
+}
+
+/// Tests that a missing file, or a script with both `command` and `command_file`, fails clearly.
+#[test]
+fn test_command_file_errors() {
+    let dir = project("cargo_script_command_file_errors");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("command_file [ scripts/missing.sh ]: could not be read"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "both"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("also has a `command`"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["validate"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ missing ]"))
+        .stdout(predicates::str::contains("[ both ]"));
+}
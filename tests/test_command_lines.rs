@@ -0,0 +1,56 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+const LINES_SCRIPTS: &str = r#"[scripts]
+greet = { command = ["export GREETING=hi", "echo \"$GREETING world\""] }
+stops = { command = ["echo first", "false", "echo never"] }
+"#;
+
+/// Write the command array scripts and return their path.
+fn scripts_file(name: &str) -> String {
+    fixtures::scripts_file(name, LINES_SCRIPTS)
+}
+
+/// Tests that the lines of a command array share one shell session, so variables carry over.
+#[test]
+fn test_command_lines_share_session() {
+    let path = scripts_file("cargo_script_command_lines_session");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "greet", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hi world"));
+}
+
+/// Tests that a command array stops at the first failing line.
+#[test]
+fn test_command_lines_stop_at_failure() {
+    let path = scripts_file("cargo_script_command_lines_failure");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "stops", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("first"))
+        .stdout(predicates::str::contains("never").not());
+}
+
+/// Tests that dry-run lists each line and `--verbose` echoes them before running.
+#[test]
+fn test_command_lines_dry_run_and_verbose() {
+    let path = scripts_file("cargo_script_command_lines_dry_run");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "stops", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Command lines: 3 line(s)"))
+        .stdout(predicates::str::contains("  echo first\n"))
+        .stdout(predicates::str::contains("  false\n"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .env("NO_COLOR", "1")
+        .args(["run", "greet", "--verbose", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("$ export GREETING=hi"));
+}
@@ -0,0 +1,96 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Tests that `completions bash` prints a completion script without the banner.
+#[test]
+fn test_completions_bash() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["completions", "bash"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("complete -F"))
+        .stdout(predicates::str::contains("A CLI tool to run custom scripts").not());
+}
+
+/// Tests that `completions --dry-run --out-dir` previews the target path without writing it.
+#[test]
+fn test_completions_dry_run() {
+    let out_dir = std::env::temp_dir().join("cargo_script_completions_dry_run");
+    let _ = std::fs::remove_dir_all(&out_dir);
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["completions", "zsh", "--dry-run", "--out-dir", out_dir.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("would write"))
+        .stdout(predicates::str::contains("#compdef cargo-script"));
+    assert!(!out_dir.exists());
+}
+
+/// Tests that `completions fig` prints a Fig spec with the `run` subcommand and its script argument.
+/// When `node` is installed, the spec (with its type annotation removed) must also parse as JavaScript.
+#[test]
+fn test_completions_fig() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let output = cmd.args(["completions", "fig"]).output().unwrap();
+    assert!(output.status.success());
+    let spec = String::from_utf8(output.stdout).unwrap();
+    assert!(spec.starts_with("const completion: Fig.Spec = {"));
+    assert!(spec.contains("name: \"run\""));
+    assert!(spec.contains("name: \"script\""));
+
+    if std::process::Command::new("node").arg("--version").output().is_ok() {
+        let js = spec.replacen(": Fig.Spec", "", 1).replace("export default completion;", "");
+        let path = std::env::temp_dir().join("cargo_script_fig_spec.js");
+        std::fs::write(&path, js).unwrap();
+        let status = std::process::Command::new("node").arg("--check").arg(&path).status().unwrap();
+        assert!(status.success(), "Fig spec is not valid JavaScript");
+    }
+}
+
+/// Tests that the hidden `__complete_scripts` command prints the script names, one per line.
+#[test]
+fn test_complete_scripts_names() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["__complete_scripts", "--scripts-path", "./tests/Scripts_test.toml"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("bench_fail\nbench_fast\n"))
+        .stdout(predicates::str::contains("A CLI tool to run custom scripts").not());
+}
+
+/// Tests that `__complete_scripts` prints nothing and succeeds without a scripts file.
+#[test]
+fn test_complete_scripts_without_scripts_file() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(std::env::temp_dir())
+        .args(["__complete_scripts", "--scripts-path", "cargo_script_missing_Scripts.toml"])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+}
+
+/// Tests that bash, zsh, and fish completions call `__complete_scripts` for script names, and
+/// that the hidden command itself is not offered.
+#[test]
+fn test_completions_dynamic_script_names() {
+    for (shell, hook) in [
+        ("bash", "complete -F _cargo__script_scripts"),
+        ("zsh", "':script -- The name of the script to run:_cargo-script_scripts'"),
+        ("fish", "__fish_cargo_script_using_subcommand run; and not __fish_seen_argument -l help\" -f -a \"(cargo-script __complete_scripts"),
+    ] {
+        let output = Command::cargo_bin("cargo-script").unwrap()
+            .args(["completions", shell])
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let script = String::from_utf8(output).unwrap();
+        assert!(script.contains(hook), "{} completions lack the script names hook", shell);
+        if shell != "fish" {
+            assert_eq!(script.matches("__complete_scripts").count(), 1, "{} completions offer the hidden command", shell);
+        }
+    }
+}
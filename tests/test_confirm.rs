@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// Scripts that ask for confirmation.
+const SCRIPTS: &str = r#"[scripts]
+deploy = { command = "echo deploying", confirm = true }
+release = { command = "echo releasing", confirm = "Release to production?" }
+"#;
+
+/// Tests that a script with `confirm` runs once the question is answered with yes.
+#[test]
+fn test_confirm_accepted() {
+    let dir = fixtures::project("cargo_script_confirm_accepted", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "deploy"])
+        .write_stdin("y\n")
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Are you sure you want to run deploy? (y/N)"))
+        .stdout(predicates::str::contains("deploying"));
+}
+
+/// Tests that no answer means no, and that the script does not run.
+#[test]
+fn test_confirm_declined() {
+    let dir = fixtures::project("cargo_script_confirm_declined", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "release"])
+        .write_stdin("\n")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Release to production? (y/N)"))
+        .stderr(predicates::str::contains("Script [ release ] was not confirmed"))
+        .stdout(predicates::str::contains("releasing").not());
+}
+
+/// Tests that `--yes` skips the question.
+#[test]
+fn test_confirm_yes_flag() {
+    let dir = fixtures::project("cargo_script_confirm_yes", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "release", "-y"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Release to production?").not())
+        .stdout(predicates::str::contains("releasing"));
+}
+
+/// Tests that dry-run notes the confirmation without asking.
+#[test]
+fn test_confirm_dry_run() {
+    let dir = fixtures::project("cargo_script_confirm_dry_run", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "release", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Confirmation: required (\"Release to production?\")"))
+        .stderr(predicates::str::contains("(y/N)").not());
+}
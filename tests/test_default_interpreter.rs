@@ -0,0 +1,56 @@
+use assert_cmd::Command;
+
+mod fixtures;
+
+/// Create a project with the given scripts file content and return the path of its scripts file.
+fn scripts_file(name: &str, content: &str) -> String {
+    fixtures::scripts_file(name, content)
+}
+
+const BASH_DEFAULT: &str = r#"default_interpreter = "bash"
+
+[scripts]
+which_shell = "echo \"bash=${BASH_VERSION:+yes}\""
+explicit_sh = { command = "echo \"bash=${BASH_VERSION:+yes}\"", interpreter = "sh" }
+"#;
+
+/// Tests that scripts without an interpreter run with `default_interpreter`.
+#[test]
+fn test_default_interpreter_used() {
+    let path = scripts_file("cargo_script_default_interpreter_used", BASH_DEFAULT);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "which_shell", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("bash=yes"));
+}
+
+/// Tests that a script's own `interpreter` wins over `default_interpreter`.
+#[test]
+fn test_script_interpreter_wins() {
+    let path = scripts_file("cargo_script_default_interpreter_override", BASH_DEFAULT);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "explicit_sh", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Interpreter: sh"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "which_shell", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Interpreter: bash"));
+}
+
+/// Tests that `validate` reports a `default_interpreter` that cannot be found.
+#[test]
+fn test_validate_default_interpreter() {
+    let path = scripts_file(
+        "cargo_script_default_interpreter_validate",
+        "default_interpreter = \"no-such-shell\"\n\n[scripts]\nhello = \"echo hello\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ default_interpreter ] interpreter `no-such-shell` was not found on PATH"));
+}
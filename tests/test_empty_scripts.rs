@@ -0,0 +1,35 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::EMPTY_SCRIPT_TOML;
+
+/// Tests that `show` on an empty scripts file still succeeds without `--fail-if-no-scripts`.
+#[test]
+fn test_show_empty_scripts() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["show", "--scripts-path", EMPTY_SCRIPT_TOML])
+        .assert()
+        .success();
+}
+
+/// Tests that `--fail-if-no-scripts` makes `show` fail on an empty scripts file.
+#[test]
+fn test_fail_if_no_scripts() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["show", "--fail-if-no-scripts", "--scripts-path", EMPTY_SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("No scripts defined"));
+}
+
+/// Tests that `run` without a script name points to `init` when no scripts are defined.
+#[test]
+fn test_run_without_script_on_empty_scripts() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "--scripts-path", EMPTY_SCRIPT_TOML])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("No scripts defined"))
+        .stderr(predicates::str::contains("cargo script init"));
+}
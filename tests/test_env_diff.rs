@@ -0,0 +1,39 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--env-diff` shows added and changed variables, masking secrets, before running.
+#[test]
+fn test_env_diff_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("RUST_LOG", "error")
+        .env_remove("EXAMPLE_VAR")
+        .args(["run", "test03_env", "--env-diff", "--env", "API_TOKEN=abc123", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Env diff [ test03_env ]"))
+        .stdout(predicates::str::contains("~ RUST_LOG: error -> info"))
+        .stdout(predicates::str::contains("+ EXAMPLE_VAR = change_value_again"))
+        .stdout(predicates::str::contains("+ API_TOKEN = ***"))
+        .stdout(predicates::str::contains("abc123").not());
+}
+
+/// Tests that variables dropped by `env_passthrough` show as removed in a dry run.
+#[test]
+fn test_env_diff_dry_run_removed() {
+    let dir = std::env::temp_dir().join("cargo_script_env_diff_removed");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, "env_passthrough = [\"PATH\"]\n\n[scripts]\nshow = { command = \"echo hi\", env = { ADDED = \"1\" } }\n").unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .env("CS_DIFF_DROPPED", "1")
+        .args(["run", "show", "--dry-run", "--env-diff", "--scripts-path", &path.display().to_string()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("- CS_DIFF_DROPPED"))
+        .stdout(predicates::str::contains("+ ADDED = 1"))
+        .stdout(predicates::str::contains("- PATH").not());
+}
@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use std::fs;
+
+mod fixtures;
+
+const ENV_FILE_SCRIPTS: &str = r#"env_file = ".env"
+
+[global_env]
+SHARED = "from_global_env"
+
+[scripts]
+show = { command = "printf '%s|' \"$PLAIN\" \"$QUOTED\" \"$SINGLE\" \"$SHARED\" \"$LOCAL\"" }
+local = { command = "printf '%s|' \"$LOCAL\" \"$PLAIN\"", env_file = "local.env", env = { PLAIN = "from_env" } }
+missing = { command = "echo never", env_file = "missing.env" }
+"#;
+
+const ENV_FILE: &str = r#"# Shared settings
+PLAIN=plain value # trailing comment
+export QUOTED="two\nlines \"quoted\""
+SINGLE='literal \n $HOME'
+
+SHARED=from_env_file
+"#;
+
+/// Create a project with env files and return the path of its scripts file.
+fn project(name: &str) -> String {
+    let dir = fixtures::project(name, ENV_FILE_SCRIPTS);
+    fs::write(dir.join(".env"), ENV_FILE).unwrap();
+    fs::write(dir.join("local.env"), "LOCAL=from_local_file\nPLAIN=from_local_file\n").unwrap();
+    dir.join("Scripts.toml").display().to_string()
+}
+
+/// Tests that the top-level `env_file` is parsed and ranks below `global_env`.
+#[test]
+fn test_env_file_loaded() {
+    let path = project("cargo_script_env_file_loaded");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "show", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("plain value|two\nlines \"quoted\"|literal \\n $HOME|from_global_env||"));
+}
+
+/// Tests that a script's `env_file` ranks below its `env` and `--env` wins over both.
+#[test]
+fn test_env_file_precedence() {
+    let path = project("cargo_script_env_file_precedence");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "local", "--env", "LOCAL=from_cli", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("from_cli|from_env|"));
+}
+
+/// Tests that a missing env file is reported with its path.
+#[test]
+fn test_env_file_missing() {
+    let path = project("cargo_script_env_file_missing");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "missing", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to load env file"))
+        .stderr(predicates::str::contains("missing.env"));
+}
@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+
+mod fixtures;
+
+/// Write a scripts file into a fresh directory and return the directory.
+fn project(name: &str, content: &str) -> std::path::PathBuf {
+    fixtures::project(name, content)
+}
+
+const SHOW_ENV: &str = "show = \"echo ${CS_KEEP_ONE:-unset} ${CS_DROP:-unset} ${CONFIGURED:-unset}\"\n";
+
+/// Tests that only allowed ambient variables plus configured ones reach the command.
+#[test]
+fn test_env_passthrough_allowlist() {
+    let dir = project(
+        "cargo_script_env_passthrough",
+        &format!("env_passthrough = [\"PATH\", \"CS_KEEP_*\"]\n\n[global_env]\nCONFIGURED = \"yes\"\n\n[scripts]\n{}", SHOW_ENV),
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("CS_KEEP_ONE", "1")
+        .env("CS_DROP", "2")
+        .args(["run", "show"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("1 unset yes"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("CS_KEEP_ONE", "1")
+        .args(["run", "show", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Env passthrough: PATH, CS_KEEP_* -> CS_KEEP_ONE, PATH"));
+}
+
+/// Tests that without `env_passthrough` the whole environment is inherited.
+#[test]
+fn test_env_passthrough_default_inherits() {
+    let dir = project("cargo_script_env_passthrough_default", &format!("[scripts]\n{}", SHOW_ENV));
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("CS_KEEP_ONE", "1")
+        .env("CS_DROP", "2")
+        .args(["run", "show"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("1 2 unset"));
+}
@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests `--dump-env-schema` on the `test02_env` script defined in `Scripts.toml`.
+/// `RUST_LOG` has a default, while `COMMON_VAR` is only referenced by the command.
+#[test]
+fn test_dump_env_schema_json() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let output = cmd.args(["run", "test02_env", "--dump-env-schema", "--format", "json", "--scripts-path", SCRIPT_TOML])
+        .env_remove("COMMON_VAR")
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let entry = |name: &str| schema.as_array().unwrap().iter().find(|e| e["name"] == name).cloned().unwrap();
+
+    assert_eq!(entry("RUST_LOG")["kind"], "optional");
+    assert_eq!(entry("RUST_LOG")["default"], "warn");
+    assert_eq!(entry("COMMON_VAR")["kind"], "referenced");
+    assert_eq!(entry("COMMON_VAR")["satisfied"], false);
+    assert_eq!(entry("EXAMPLE_VAR")["sources"][0], "global_env");
+}
+
+/// Tests `--report-unused-env` on `test02_env`: the `global_env` variables are never referenced,
+/// while `RUST_LOG` is.
+#[test]
+fn test_report_unused_env() {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "test02_env", "--report-unused-env", "--format", "json", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let unused: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let names: Vec<&str> = unused.as_array().unwrap().iter().map(|e| e["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["EXAMPLE_VAR", "RUST_BACKTRACE"]);
+    assert_eq!(unused[0]["sources"][0], "global_env");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "test02_env", "--report-unused-env", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ test02_env ] RUST_BACKTRACE is possibly unused (declared in global_env)"))
+        .stdout(predicates::str::contains("RUST_LOG is possibly unused").not())
+        .stdout(predicates::str::contains("warn unset").not());
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use assert_cmd::Command;
 use std::fs;
 
@@ -44,3 +46,16 @@ fn test03_env() {
         .stdout(predicates::str::contains("change_value_again"))
         .stdout(predicates::str::contains("info"));
 }
+
+/// Tests that dry-run shows the value a `--env` override replaces, and prints other variables as is.
+#[test]
+fn test_dry_run_shows_overridden_value() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "test03_env", "--dry-run", "--env", "RUST_LOG=trace", "--env", "NEW_VAR=fresh", "--scripts-path", SCRIPT_TOML])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("RUST_LOG = trace (was: info)"))
+        .stdout(predicates::str::contains("EXAMPLE_VAR = change_value_again\n"))
+        .stdout(predicates::str::contains("NEW_VAR = fresh\n"));
+}
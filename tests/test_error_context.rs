@@ -0,0 +1,41 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--error-context N` replays only the last N lines plus error lines on failure.
+#[test]
+fn test_error_context_selects_lines() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "noisy_fail", "--quiet-success", "--error-context", "2", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("error: broken thing"))
+        .stdout(predicates::str::contains("noise-10"))
+        .stdout(predicates::str::contains("noise-11"))
+        .stdout(predicates::str::contains("noise-3\n").not())
+        .stdout(predicates::str::contains("noise-9\n").not())
+        .stderr(predicates::str::contains("line(s) omitted"));
+}
+
+/// Tests that without `--error-context` the whole captured output is replayed.
+#[test]
+fn test_error_context_default_full_output() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "noisy_fail", "--quiet-success", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("noise-3\n"))
+        .stderr(predicates::str::contains("omitted").not());
+}
+
+/// Tests that `--error-context` requires the capture mode.
+#[test]
+fn test_error_context_requires_quiet_success() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "noisy_fail", "--error-context", "2", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--quiet-success"));
+}
@@ -0,0 +1,61 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that a mapped exit code adds its meaning to the error.
+#[test]
+fn test_mapped_exit_code() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "mapped_exit", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("exit status: 3: connection failed"));
+}
+
+/// Tests that an unmapped exit code shows the generic message.
+#[test]
+fn test_unmapped_exit_code() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "unmapped_exit", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("exit status: 4 (command"))
+        .stderr(predicates::str::contains("config invalid").not());
+}
+
+/// Tests that dry-run lists the known exit code meanings.
+#[test]
+fn test_exit_codes_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "mapped_exit", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("2 = config invalid"))
+        .stdout(predicates::str::contains("3 = connection failed"));
+}
+
+/// Tests that the run exits with the exit code of the failed command.
+#[test]
+fn test_exit_code_is_propagated() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "unmapped_exit", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .code(4);
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "keep_going_chain", "--keep-going", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .code(3);
+}
+
+/// Tests that a command killed by a signal exits with 128 + the signal number.
+#[cfg(unix)]
+#[test]
+fn test_exit_code_for_signal() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "killed_by_signal", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .code(137);
+}
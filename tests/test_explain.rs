@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+mod fixtures;
+use constants::SCRIPT_TOML;
+
+/// Writes a scripts file with hooks, includes, and a command array, and returns its path.
+fn scripts_file(name: &str) -> String {
+    fixtures::scripts_file(name, r#"[scripts]
+prebuild = "cargo fmt --check"
+build = { command = "cargo build", env = { EXPLAIN_FLAGS = "-D warnings" }, toolchain = "nightly" }
+lint = { command = ["cargo clippy", "cargo doc"], interpreter = "bash" }
+ci = { include = ["build", "lint"], command = "echo done" }
+"#)
+}
+
+/// Tests that `explain` prints hooks, includes, and commands in run order as a tree.
+#[test]
+fn test_explain_tree() {
+    let path = scripts_file("cargo_script_explain_tree");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("NO_COLOR", "1")
+        .args(["explain", "ci", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("ci\n├─ build\n│  ├─ prebuild (pre hook)\n│  │  └─ $ cargo fmt --check\n"))
+        .stdout(predicates::str::contains("│  └─ $ cargo build\n│       Interpreter: sh\n│       Toolchain: nightly (script)\n"))
+        .stdout(predicates::str::contains("│       + EXPLAIN_FLAGS = -D warnings\n"))
+        .stdout(predicates::str::contains("├─ lint\n│  └─ $ cargo clippy (+1 more line(s))\n│       Interpreter: bash\n"))
+        .stdout(predicates::str::contains("└─ $ echo done\n"));
+}
+
+/// Tests that `explain --no-hooks` leaves the hooks out of the plan.
+#[test]
+fn test_explain_no_hooks() {
+    let path = scripts_file("cargo_script_explain_no_hooks");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["explain", "build", "--no-hooks", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("prebuild").not());
+}
+
+/// Tests that `explain` reports a cycle instead of recursing forever.
+#[test]
+fn test_explain_cycle() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["explain", "cycle_a", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Circular include detected: cycle_a -> cycle_b -> cycle_a"));
+}
@@ -0,0 +1,34 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--explain-failure` prints the command, exit code, output, env, and tool versions.
+#[test]
+fn test_explain_failure_report() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "explain_fail", "--quiet-success", "--explain-failure", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failure report"))
+        .stderr(predicates::str::contains("exit 3"))
+        .stderr(predicates::str::contains(": 3\n"))
+        .stderr(predicates::str::contains("  about to fail"))
+        .stderr(predicates::str::contains("STAGE = ci"))
+        .stderr(predicates::str::contains("cargo: cargo 1."));
+}
+
+/// Tests that without `--quiet-success` the report is still printed, leaving out the output
+/// that was already shown.
+#[test]
+fn test_explain_failure_without_quiet_success() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "explain_fail", "--explain-failure", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("about to fail"))
+        .stderr(predicates::str::contains("Failure report"))
+        .stderr(predicates::str::contains("STAGE = ci"))
+        .stderr(predicates::str::contains("Output").not());
+}
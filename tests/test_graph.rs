@@ -0,0 +1,35 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that the tree view nests includes under their scripts and marks cycles.
+#[test]
+fn test_graph_tree() {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["graph", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("release\n├─ i_am_shell  $ ./.scripts/test_script.sh\n"));
+    assert!(stdout.contains("└─ build  $ echo 'build'\n"));
+    assert!(stdout.contains("(cycle: cycle_a -> cycle_b -> cycle_a)"));
+}
+
+/// Tests that `--format dot` prints a digraph with one edge per include and red cycle edges.
+#[test]
+fn test_graph_dot() {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["graph", "--format", "dot", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("digraph scripts {\n"));
+    assert!(stdout.trim_end().ends_with('}'));
+    assert!(stdout.contains("    \"build\" [shape=box];\n"));
+    assert!(stdout.contains("    \"release\" [shape=ellipse];\n"));
+    assert!(stdout.contains("    \"release\" -> \"build\";\n"));
+    assert!(stdout.contains("    \"cycle_a\" -> \"cycle_b\" [color=red, label=\"cycle\"];\n"));
+}
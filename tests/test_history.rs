@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+/// Scripts with a passing and a failing script.
+const SCRIPTS: &str = "[scripts]\nok = \"echo ok\"\nbad = \"exit 1\"\n";
+
+/// Tests that runs are recorded with their status and shown newest first.
+#[test]
+fn test_history_records_runs() {
+    let dir = fixtures::project("cargo_script_history_records", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap().current_dir(&dir).args(["run", "ok"]).assert().success();
+    Command::cargo_bin("cargo-script").unwrap().current_dir(&dir).args(["run", "bad"]).assert().failure();
+
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["history", "--format", "json"])
+        .output()
+        .unwrap();
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["script"], "bad");
+    assert_eq!(entries[0]["success"], false);
+    assert_eq!(entries[1]["script"], "ok");
+    assert_eq!(entries[1]["success"], true);
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["history", "--limit", "1"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("failed"))
+        .stdout(predicates::str::contains(" ok ").not());
+}
+
+/// Tests that the log is pruned to `CARGO_SCRIPT_HISTORY_CAP` entries and corrupt lines are skipped.
+#[test]
+fn test_history_cap_and_corrupt_lines() {
+    let dir = fixtures::project("cargo_script_history_cap", SCRIPTS);
+    fs::create_dir_all(dir.join(".cargo-script")).unwrap();
+    fs::write(dir.join(".cargo-script/history.jsonl"), "not json\n").unwrap();
+    for _ in 0..3 {
+        Command::cargo_bin("cargo-script").unwrap()
+            .current_dir(&dir)
+            .env("CARGO_SCRIPT_HISTORY_CAP", "2")
+            .args(["run", "ok"])
+            .assert()
+            .success();
+    }
+
+    let log = fs::read_to_string(dir.join(".cargo-script/history.jsonl")).unwrap();
+    assert_eq!(log.lines().count(), 2);
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["history", "--format", "json"])
+        .output()
+        .unwrap();
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 2);
+}
+
+/// Tests that a missing log is reported as no runs.
+#[test]
+fn test_history_empty() {
+    let dir = fixtures::project("cargo_script_history_empty", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["history"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("No runs recorded"));
+}
@@ -0,0 +1,100 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+mod fixtures;
+use constants::SCRIPT_TOML;
+
+/// Returns the stdout of a successful run.
+fn run_stdout(args: &[&str]) -> String {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(args)
+        .args(["--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+/// Tests that the `pre` and `post` hooks run before and after the script, in order.
+#[test]
+fn test_pre_and_post_hooks_run() {
+    let stdout = run_stdout(&["run", "hooked"]);
+    let pre = stdout.find("prehooked ran").expect("pre hook did not run");
+    let main = stdout.find("\nhooked ran").expect("script did not run");
+    let post = stdout.find("posthooked ran").expect("post hook did not run");
+    assert!(pre < main && main < post, "hooks ran out of order:\n{}", stdout);
+}
+
+/// Tests that a script without hooks runs on its own.
+#[test]
+fn test_script_without_hooks() {
+    let stdout = run_stdout(&["run", "unhooked"]);
+    assert!(stdout.contains("unhooked ran"));
+    assert!(!stdout.contains("Running script: [ pre"));
+    assert!(!stdout.contains("Running script: [ post"));
+}
+
+/// Tests that `--no-hooks` runs only the script itself.
+#[test]
+fn test_no_hooks() {
+    let stdout = run_stdout(&["run", "hooked", "--no-hooks"]);
+    assert!(stdout.contains("hooked ran"));
+    assert!(!stdout.contains("prehooked ran"));
+    assert!(!stdout.contains("posthooked ran"));
+}
+
+/// Tests that a hook run directly does not look for hooks of its own.
+#[test]
+fn test_hook_run_directly() {
+    let stdout = run_stdout(&["run", "prehooked"]);
+    assert!(stdout.contains("prehooked ran"));
+    assert!(!stdout.contains("\nhooked ran"));
+}
+
+/// Tests that the post hook does not run when the script fails.
+#[test]
+fn test_post_hook_skipped_on_failure() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "hook_failing", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("hook_failing ran"))
+        .stdout(predicates::str::contains("posthook_failing ran").not());
+}
+
+/// Tests that dry-run lists the hooks of a script.
+#[test]
+fn test_dry_run_lists_hooks() {
+    let stdout = run_stdout(&["run", "hooked", "--dry-run"]);
+    assert!(stdout.contains("Hooks: prehooked, posthooked"), "{}", stdout);
+}
+
+/// Tests that `--print-includes-order` lists hooks where they run, also around included scripts.
+#[test]
+fn test_print_includes_order_lists_hooks() {
+    assert_eq!(run_stdout(&["run", "hooked", "--print-includes-order"]), "prehooked\nhooked\nposthooked\n");
+    assert_eq!(run_stdout(&["run", "hooked", "--print-includes-order", "--no-hooks"]), "hooked\n");
+
+    let dir = fixtures::project(
+        "cargo_script_hooks_order",
+        "[scripts]\nprebuild = \"echo prebuild ran\"\nbuild = \"echo build ran\"\npostbuild = \"echo postbuild ran\"\nlint = \"echo lint ran\"\nci = { include = [\"lint\", \"build\"] }\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--print-includes-order"])
+        .assert()
+        .success()
+        .stdout("lint\nprebuild\nbuild\npostbuild\nci\n");
+
+    // Step positions follow the same list, so a hook can be run as a step.
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--step", "4"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("postbuild ran"))
+        .stdout(predicates::str::contains("\nbuild ran").not());
+}
@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+/// Create a project whose main file imports two partial files.
+fn project(name: &str) -> std::path::PathBuf {
+    let dir = fixtures::project(name, "import = [\"scripts/ci.toml\", \"scripts/dev.toml\"]\n\n[scripts]\nbuild = \"echo main-build\"\nall = { include = [\"lint\", \"serve\"] }\n");
+    fs::create_dir_all(dir.join("scripts")).unwrap();
+    fs::write(dir.join("scripts/ci.toml"), "[scripts]\nlint = \"echo ci-lint\"\nbuild = \"echo ci-build\"\n").unwrap();
+    fs::write(dir.join("scripts/dev.toml"), "[scripts]\nserve = { command = \"echo dev-serve\", info = \"Serve locally\" }\n").unwrap();
+    dir
+}
+
+/// Tests that imported scripts can be run and included, and that the main file wins on collisions.
+#[test]
+fn test_import_merges_scripts() {
+    let dir = project("cargo_script_import_merge");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "all"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("ci-lint"))
+        .stdout(predicates::str::contains("dev-serve"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "build"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("main-build"))
+        .stdout(predicates::str::contains("ci-build").not())
+        .stderr(predicates::str::contains("[ build ] from scripts/ci.toml is already defined"));
+}
+
+/// Tests that `show` lists the merged scripts.
+#[test]
+fn test_import_show() {
+    let dir = project("cargo_script_import_show");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .arg("show")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Serve locally"));
+}
+
+/// Tests that a missing imported file is reported as an error.
+#[test]
+fn test_import_missing_file() {
+    let dir = project("cargo_script_import_missing");
+    fs::remove_file(dir.join("scripts/dev.toml")).unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .arg("show")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to import"));
+}
+
+/// Tests that imports are followed relative to the importing file and that `global_env` is
+/// merged, with local values winning.
+#[test]
+fn test_import_nested_and_global_env() {
+    let dir = project("cargo_script_import_nested");
+    fs::write(
+        dir.join("Scripts.toml"),
+        "import = [\"scripts/ci.toml\"]\n\n[global_env]\nTARGET = \"main\"\n\n[scripts]\nshow_env = \"echo $TARGET-$REGION\"\n",
+    ).unwrap();
+    fs::write(dir.join("scripts/ci.toml"), "import = [\"shared/common.toml\"]\n\n[global_env]\nTARGET = \"ci\"\n\n[scripts]\nlint = \"echo ci-lint\"\n").unwrap();
+    fs::create_dir_all(dir.join("scripts/shared")).unwrap();
+    fs::write(dir.join("scripts/shared/common.toml"), "[global_env]\nREGION = \"eu\"\n\n[scripts]\nfmt = \"echo common-fmt\"\n").unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "fmt"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("common-fmt"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "show_env"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("main-eu"))
+        .stderr(predicates::str::contains("global_env.TARGET from scripts/ci.toml is already set in Scripts.toml"));
+}
+
+/// Tests that files importing each other are reported as a cycle.
+#[test]
+fn test_import_cycle() {
+    let dir = project("cargo_script_import_cycle");
+    fs::write(dir.join("scripts/ci.toml"), "import = [\"dev.toml\"]\n\n[scripts]\nlint = \"echo ci-lint\"\n").unwrap();
+    fs::write(dir.join("scripts/dev.toml"), "import = [\"ci.toml\"]\n\n[scripts]\nserve = \"echo dev-serve\"\n").unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .arg("show")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("import cycle: scripts/ci.toml -> scripts/dev.toml -> scripts/ci.toml"));
+}
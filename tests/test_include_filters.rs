@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// Scripts with a `ci` aggregate script.
+const SCRIPTS: &str = r#"[scripts]
+fmt = "echo running-fmt"
+clippy = "echo running-clippy"
+unit = "echo running-unit"
+integration = "echo running-integration"
+checks = { include = ["fmt", "clippy"], command = "echo running-checks" }
+ci = { include = ["checks", "unit", "integration"], command = "echo running-ci" }
+"#;
+
+/// Tests that `--skip` bypasses an include with a notice, and still runs the parent's command.
+#[test]
+fn test_skip_include() {
+    let dir = fixtures::project("cargo_script_skip_include", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--skip", "integration", "--skip", "fmt"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Skipping include script: [ integration ] (--skip)"))
+        .stdout(predicates::str::contains("running-integration").not())
+        .stdout(predicates::str::contains("running-fmt").not())
+        .stdout(predicates::str::contains("running-clippy"))
+        .stdout(predicates::str::contains("running-ci"));
+}
+
+/// Tests that `--only` runs the selected includes, reaching nested ones through their parents
+/// without running the parents' own commands.
+#[test]
+fn test_only_include() {
+    let dir = fixtures::project("cargo_script_only_include", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--only", "clippy", "--only", "unit"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("running-clippy"))
+        .stdout(predicates::str::contains("running-unit"))
+        .stdout(predicates::str::contains("running-fmt").not())
+        .stdout(predicates::str::contains("running-checks").not())
+        .stdout(predicates::str::contains("running-integration").not())
+        .stdout(predicates::str::contains("running-ci"));
+}
+
+/// Tests that names outside the chain are warned about.
+#[test]
+fn test_unknown_filter_warns() {
+    let dir = fixtures::project("cargo_script_unknown_filter", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "checks", "--skip", "integration"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("--skip: [ integration ] is not included by [ checks ]"));
+}
+
+/// Tests that `--print-includes-order` lists only what `--only` and `--skip` leave to run.
+#[test]
+fn test_print_includes_order_with_filters() {
+    let dir = fixtures::project("cargo_script_filtered_order", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--only", "checks", "--skip", "clippy", "--print-includes-order"])
+        .assert()
+        .success()
+        .stdout("fmt\nchecks\nci\n");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "ci", "--only", "clippy", "--only", "unit", "--skip", "fmt", "--print-includes-order"])
+        .assert()
+        .success()
+        .stdout("clippy\nunit\nci\n");
+}
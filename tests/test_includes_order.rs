@@ -0,0 +1,25 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--print-includes-order` prints the chain in execution order without running it.
+#[test]
+fn test_print_includes_order() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "release", "--print-includes-order", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout("i_am_shell\ni_am_shell_obj\nbuild\nrelease\n");
+}
+
+/// Tests that a cycle in the chain is reported as an error instead of being printed.
+#[test]
+fn test_print_includes_order_cycle() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "cycle_a", "--print-includes-order", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::is_empty())
+        .stderr(predicates::str::contains("Circular include detected: cycle_a -> cycle_b -> cycle_a"));
+}
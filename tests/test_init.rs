@@ -0,0 +1,66 @@
+use assert_cmd::Command;
+use std::fs;
+
+/// Tests that `init --from-existing` scaffolds scripts from `Cargo.toml`, `package.json`,
+/// and `docker-compose.yml` found in the current directory.
+#[test]
+fn test_init_from_existing() {
+    let dir = std::env::temp_dir().join("cargo_script_init_from_existing");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[workspace]\nmembers = []\n").unwrap();
+    fs::write(dir.join("package.json"), r#"{ "scripts": { "lint:js": "eslint ." } }"#).unwrap();
+    fs::write(dir.join("docker-compose.yml"), "services: {}\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .args(["init", "--from-existing", "--force"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Detected Cargo workspace"));
+
+    let content = fs::read_to_string(dir.join("Scripts.toml")).unwrap();
+    assert!(content.contains(r#"build = { command = "cargo build --workspace""#));
+    assert!(content.contains(r#"npm_lint_js = { command = "npm run lint:js""#));
+    assert!(content.contains("docker-up"));
+    assert!(content.parse::<toml::Table>().is_ok());
+}
+
+/// Tests that npm scripts whose names sanitize to the same key get a numeric suffix.
+#[test]
+fn test_init_from_existing_npm_name_collision() {
+    let dir = std::env::temp_dir().join("cargo_script_init_npm_collision");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("package.json"), r#"{ "scripts": { "build:dev": "vite build", "build/dev": "webpack" } }"#).unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .args(["init", "--from-existing", "--force"])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(dir.join("Scripts.toml")).unwrap();
+    let table = content.parse::<toml::Table>().unwrap();
+    let scripts = table["scripts"].as_table().unwrap();
+    assert!(scripts.contains_key("npm_build_dev"));
+    assert!(scripts.contains_key("npm_build_dev_2"));
+}
+
+/// Tests that `init --interactive` falls back to the default template without a terminal.
+#[test]
+fn test_init_interactive_without_terminal() {
+    let dir = std::env::temp_dir().join("cargo_script_init_interactive");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .args(["init", "--interactive"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Not running in a terminal, using the default template"));
+
+    let content = fs::read_to_string(dir.join("Scripts.toml")).unwrap();
+    assert!(content.contains(r#"dev = "cargo run""#));
+}
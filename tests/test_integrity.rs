@@ -0,0 +1,61 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+/// Scripts with a single script.
+const SCRIPTS: &str = "[scripts]\nhello = \"echo hello-integrity\"\n";
+
+/// Return the hash printed by `cargo script hash`.
+fn hash(dir: &std::path::Path) -> String {
+    let output = Command::cargo_bin("cargo-script").unwrap().current_dir(dir).arg("hash").output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+/// Tests that `hash` prints only the SHA-256 hash and that `--verify-hash` accepts it.
+#[test]
+fn test_verify_hash_matches() {
+    let dir = fixtures::project("cargo_script_verify_hash_ok", SCRIPTS);
+    let hash = hash(&dir);
+    assert_eq!(hash.len(), 64);
+    assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "hello", "--verify-hash", &hash])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello-integrity"));
+}
+
+/// Tests that a mismatching `--verify-hash` refuses to run anything.
+#[test]
+fn test_verify_hash_mismatch() {
+    let dir = fixtures::project("cargo_script_verify_hash_mismatch", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "hello", "--verify-hash", &"0".repeat(64)])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Integrity check failed"))
+        .stdout(predicates::str::contains("hello-integrity").not());
+}
+
+/// Tests that a committed `.cargo-script.lock` pins the scripts file.
+#[test]
+fn test_lock_file_detects_tampering() {
+    let dir = fixtures::project("cargo_script_lock_file", SCRIPTS);
+    fs::write(dir.join(".cargo-script.lock"), format!("{}\n", hash(&dir))).unwrap();
+    Command::cargo_bin("cargo-script").unwrap().current_dir(&dir).args(["run", "hello"]).assert().success();
+
+    fs::write(dir.join("Scripts.toml"), "[scripts]\nhello = \"echo tampered\"\n").unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "hello"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Integrity check failed"))
+        .stdout(predicates::str::contains("tampered").not());
+}
@@ -0,0 +1,78 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `${VAR}` and `$(script)` are expanded before the command runs.
+#[test]
+fn test_interpolation() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_vars", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello world"))
+        .stdout(predicates::str::contains("fragment ran"));
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_vars", "--env", "GREETING=hi", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hi world"));
+}
+
+/// Tests that an undefined variable fails the script instead of expanding to nothing.
+#[test]
+fn test_interpolation_undefined_variable() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_undefined", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("undefined variable ${CS_SURELY_UNDEFINED_VAR}"))
+        .stderr(predicates::str::contains("write $${CS_SURELY_UNDEFINED_VAR}"));
+}
+
+/// Tests that an escaped shell loop variable is left to the shell.
+#[test]
+fn test_interpolation_shell_loop_variable() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_shell_loop", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("item-a"))
+        .stdout(predicates::str::contains("item-b"));
+}
+
+/// Tests that commands for a language interpreter are not expanded, keeping template literals.
+#[test]
+fn test_interpolation_skipped_for_node() {
+    if std::process::Command::new("node").arg("--version").output().is_err() {
+        return;
+    }
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_node", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("from template"));
+}
+
+/// Tests that escaped references and shell command substitutions are left to the shell.
+#[test]
+fn test_interpolation_left_to_shell() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_escaped", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("from shell subst"));
+}
+
+/// Tests that dry-run shows the expanded command.
+#[test]
+fn test_interpolation_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "interp_vars", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("echo hello world && echo fragment ran"))
+        .stdout(predicates::str::contains("${GREETING}").not());
+}
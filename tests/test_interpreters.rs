@@ -0,0 +1,63 @@
+use assert_cmd::Command;
+
+mod constants;
+mod fixtures;
+use constants::SCRIPT_TOML;
+
+/// Tests that `interpreters --format json` lists the default shell and the `bash` interpreter
+/// used by `i_am_shell_obj` in `Scripts.toml`.
+#[test]
+fn test_interpreters_json() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let output = cmd.args(["interpreters", "--format", "json", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let interpreters: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let interpreters = interpreters.as_array().unwrap();
+
+    let sh = interpreters.iter().find(|i| i["name"] == "sh").unwrap();
+    assert_eq!(sh["default"], true);
+    let bash = interpreters.iter().find(|i| i["name"] == "bash").unwrap();
+    assert!(bash["used_by"].as_array().unwrap().iter().any(|s| s == "i_am_shell_obj"));
+}
+
+const INLINE_SCRIPTS: &str = r#"[scripts]
+node_inline = { interpreter = "node", command = "console.log('node says ' + (1 + 1))" }
+python_inline = { interpreter = "python3", command = "print('python says', 1 + 1)" }
+node_template = { interpreter = "node", shell_args = ["--eval", "{command}"], command = "console.log('templated')" }
+"#;
+
+/// Write the inline interpreter scripts and return their path.
+fn inline_scripts_file(name: &str) -> String {
+    fixtures::scripts_file(name, INLINE_SCRIPTS)
+}
+
+/// Tests that inline commands are passed with each interpreter's own flag: `-e` for node and
+/// `-c` for python.
+#[test]
+fn test_inline_interpreter_flags() {
+    let path = inline_scripts_file("cargo_script_inline_interpreter_flags");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "node_inline", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("node says 2"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "python_inline", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("python says 2"));
+}
+
+/// Tests that `shell_args` containing `{command}` replace the command flag entirely.
+#[test]
+fn test_shell_args_command_template() {
+    let path = inline_scripts_file("cargo_script_shell_args_template");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "node_template", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("templated"));
+}
@@ -0,0 +1,45 @@
+#![cfg(unix)]
+
+use std::{fs, process::{Command, Stdio}, thread, time::{Duration, Instant}};
+
+/// Tests that SIGTERM stops the running command and what it started, and exits with 130 without metrics.
+#[test]
+fn test_sigterm_stops_command_tree() {
+    let dir = std::env::temp_dir().join("cargo_script_interrupt");
+    fs::create_dir_all(&dir).unwrap();
+    let pid_file = dir.join("sleep.pid");
+    let _ = fs::remove_file(&pid_file);
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, format!("[scripts]\nhangs = \"sleep 30 & echo $! > {}; wait\"\n", pid_file.display())).unwrap();
+
+    let start = Instant::now();
+    let child = Command::new(assert_cmd::cargo::cargo_bin("cargo-script"))
+        .args(["run", "hangs", "--scripts-path", path.to_str().unwrap()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    while !pid_file.exists() && start.elapsed() < Duration::from_secs(10) {
+        thread::sleep(Duration::from_millis(20));
+    }
+    thread::sleep(Duration::from_millis(100));
+    Command::new("kill").args(["-TERM", &child.id().to_string()]).status().unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(130));
+    assert!(start.elapsed() < Duration::from_secs(15));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("Scripts Performance"));
+
+    // The killed process may take a moment to go away, or stay a zombie until it is reaped.
+    let sleep_pid = fs::read_to_string(&pid_file).unwrap();
+    let running = || {
+        let ps = Command::new("ps").args(["-o", "stat=", "-p", sleep_pid.trim()]).output().unwrap();
+        let stat = String::from_utf8_lossy(&ps.stdout).trim().to_string();
+        !stat.is_empty() && !stat.starts_with('Z')
+    };
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while running() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(20));
+    }
+    assert!(!running(), "the command's background process was left running");
+}
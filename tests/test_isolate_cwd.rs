@@ -0,0 +1,58 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+const ISOLATED_SCRIPTS: &str = r#"[scripts]
+pollute = { command = "cat marker.txt && echo dirty > polluted.txt && pwd" }
+"#;
+
+/// Tests that `--isolate-cwd` runs a step in a copy of the project and leaves the tree untouched.
+#[test]
+fn test_isolate_cwd_keeps_tree_clean() {
+    let dir = fixtures::project("cargo_script_isolate_cwd", ISOLATED_SCRIPTS);
+    fs::write(dir.join("marker.txt"), "copied-marker").unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .args(["run", "pollute", "--isolate-cwd"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("copied-marker"))
+        .stdout(predicates::str::contains("cargo-script-isolated-"));
+    assert!(!dir.join("polluted.txt").exists());
+}
+
+/// Tests that `--keep-isolated` reports the isolated directory and leaves it in place.
+#[test]
+fn test_keep_isolated() {
+    let dir = fixtures::project("cargo_script_keep_isolated", ISOLATED_SCRIPTS);
+    fs::write(dir.join("marker.txt"), "copied-marker").unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let output = cmd.current_dir(&dir)
+        .args(["run", "pollute", "--isolate-cwd", "--keep-isolated"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let kept = stderr.lines()
+        .find_map(|line| line.split("] ").nth(1).filter(|_| line.contains("Isolated directory kept")))
+        .expect("kept directory is reported");
+    let kept = std::path::Path::new(kept.trim());
+    assert!(kept.join("polluted.txt").exists());
+    assert!(kept.join("marker.txt").exists());
+    assert!(!dir.join("polluted.txt").exists());
+    fs::remove_dir_all(kept).unwrap();
+}
+
+/// Tests that `--keep-isolated` requires `--isolate-cwd`.
+#[test]
+fn test_keep_isolated_requires_isolate_cwd() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--keep-isolated"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--isolate-cwd").and(predicates::str::contains("required")));
+}
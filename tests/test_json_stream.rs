@@ -0,0 +1,51 @@
+use assert_cmd::Command;
+use serde_json::Value;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Parse every stdout line of a run as a JSON event.
+fn events(output: &[u8]) -> Vec<Value> {
+    String::from_utf8_lossy(output)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap_or_else(|_| panic!("not a JSON event: {}", line)))
+        .collect()
+}
+
+/// Tests that stdout only carries events, in order, and command output goes to stderr.
+#[test]
+fn test_json_stream_events() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let assert = cmd.args(["run", "keep_going_chain", "--keep-going", "--json-stream", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("kg_after ran"));
+    let events = events(&assert.get_output().stdout);
+    let names: Vec<&str> = events.iter().map(|e| e["event"].as_str().unwrap()).collect();
+    assert_eq!(names.first(), Some(&"script_started"));
+    assert_eq!(names.last(), Some(&"run_finished"));
+    let failed = events.iter().find(|e| e["event"] == "script_finished" && e["script"] == "kg_fail").unwrap();
+    assert_eq!(failed["success"], false);
+    let after = events.iter().find(|e| e["event"] == "script_finished" && e["script"] == "kg_after").unwrap();
+    assert_eq!(after["success"], true);
+    let chain = events.iter().find(|e| e["event"] == "script_finished" && e["script"] == "keep_going_chain").unwrap();
+    assert_eq!(chain["success"], false);
+    assert!(after["duration_ms"].is_u64());
+    let finished = events.last().unwrap();
+    assert_eq!(finished["success"], false);
+    assert!(finished["scripts"]["kg_after"].is_u64());
+}
+
+/// Tests that `--include-output` turns command output into `command_output` events.
+#[test]
+fn test_json_stream_include_output() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let assert = cmd.args(["run", "kg_after", "--json-stream", "--include-output", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success();
+    let events = events(&assert.get_output().stdout);
+    let output = events.iter().find(|e| e["event"] == "command_output").unwrap();
+    assert_eq!(output["script"], "kg_after");
+    assert_eq!(output["stream"], "stdout");
+    assert_eq!(output["line"], "kg_after ran");
+}
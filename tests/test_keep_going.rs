@@ -0,0 +1,40 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that a failing include stops the chain by default.
+#[test]
+fn test_chain_stops_on_failure() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "keep_going_chain", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("kg_after ran").not());
+}
+
+/// Tests that `--keep-going` runs the remaining scripts, reports timings, and fails with a summary.
+#[test]
+fn test_keep_going_runs_remaining_scripts() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "keep_going_chain", "--keep-going", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("kg_after ran"))
+        .stdout(predicates::str::contains("parent ran"))
+        .stdout(predicates::str::contains("Scripts Performance"))
+        .stderr(predicates::str::contains("Failed scripts"))
+        .stderr(predicates::str::contains("Script [ kg_fail ] failed"))
+        .stderr(predicates::str::contains("1 script(s) failed"));
+}
+
+/// Tests that `--keep-going` succeeds when nothing fails.
+#[test]
+fn test_keep_going_success() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "kg_after", "-k", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Failed scripts").not());
+}
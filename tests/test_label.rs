@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+
+mod fixtures;
+
+/// Tests that `--label` appears in the performance summary and is recorded in the history.
+#[test]
+fn test_label_in_summary_and_history() {
+    let dir = fixtures::project("cargo_script_label", "[scripts]\nbuild = \"echo build\"\n");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "build", "--label", "nightly-ci"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Scripts Performance [nightly-ci]"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "build"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["history", "--format", "json"])
+        .output()
+        .unwrap();
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(entries[0].get("label").is_none());
+    assert_eq!(entries[1]["label"], "nightly-ci");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .arg("history")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("build [nightly-ci]"));
+}
@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+mod fixtures;
+
+/// Creates a fresh temporary project directory with a `Scripts.toml`.
+fn setup_project(name: &str) -> PathBuf {
+    fixtures::project(&format!("cargo_script_last_run_{}", name), "[scripts]\ngreet = \"echo hello-$NAME\"\n")
+}
+
+/// Tests that `run -` reruns the last script with its recorded env overrides,
+/// and that new overrides win over recorded ones.
+#[test]
+fn test_rerun_last_script() {
+    let dir = setup_project("rerun");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "greet", "--env", "NAME=first"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "-"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello-first"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "-", "--env", "NAME=second"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello-second"));
+}
+
+/// Tests that `run -` fails helpfully when nothing has been run yet.
+#[test]
+fn test_rerun_without_previous_run() {
+    let dir = setup_project("empty");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "-"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("No previous run recorded"));
+}
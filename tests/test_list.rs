@@ -0,0 +1,31 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::{EMPTY_SCRIPT_TOML, SCRIPT_TOML};
+
+/// Tests that `list` prints only the sorted script names, one per line.
+#[test]
+fn test_list_names() {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["list", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let names: Vec<&str> = stdout.lines().collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+    assert!(names.contains(&"fail_script"));
+    assert!(names.iter().all(|name| !name.is_empty() && !name.contains(' ') && !name.contains('\x1b')));
+}
+
+/// Tests that `list` prints nothing and succeeds when there are no scripts.
+#[test]
+fn test_list_empty() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["list", "--scripts-path", EMPTY_SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout("");
+}
@@ -0,0 +1,48 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+/// Tests that `--log-file` copies the output of every script of a chain into one file, under a
+/// header per script, while the output still shows on the terminal.
+#[test]
+fn test_log_file_tees_output() {
+    let dir = std::env::temp_dir().join("cargo_script_log_file");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, "[scripts]\nfirst = \"echo out-first; echo err-first >&2\"\nsecond = \"echo out-second\"\nall = { include = [\"first\", \"second\"] }\n").unwrap();
+    let log = dir.join("run.log");
+    fs::write(&log, "left from an earlier run\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "all", "--log-file", log.to_str().unwrap(), "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("out-first"))
+        .stderr(predicates::str::contains("err-first"));
+
+    let content = fs::read_to_string(&log).unwrap();
+    assert!(!content.contains("left from an earlier run"));
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 5, "{}", content);
+    assert!(lines[0].starts_with("==> [ first ] started at ") && lines[0].ends_with(" UTC"));
+    assert_eq!(lines[1..3].iter().filter(|line| ["out-first", "err-first"].contains(line)).count(), 2);
+    assert!(lines[3].starts_with("==> [ second ] started at "));
+    assert_eq!(lines[4], "out-second");
+}
+
+/// Tests that the log file gets the output of a `--quiet-success` run that hides it.
+#[test]
+fn test_log_file_with_quiet_success() {
+    let dir = std::env::temp_dir().join("cargo_script_log_file_quiet");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, "[scripts]\nbuild = \"echo built\"\n").unwrap();
+    let log = dir.join("run.log");
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--quiet-success", "--log-file", log.to_str().unwrap(), "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("built").not());
+    assert!(fs::read_to_string(&log).unwrap().ends_with("UTC\nbuilt\n"));
+}
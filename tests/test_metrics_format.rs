@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--metrics-format json` replaces the performance table with JSON.
+#[test]
+fn test_metrics_format_json() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--metrics-format", "json", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("\"total_ms\""))
+        .stdout(predicates::str::contains("\"build\""))
+        .stdout(predicates::str::contains("Scripts Performance").not());
+}
+
+/// Tests that `--metrics-output` writes the metrics to a file.
+#[test]
+fn test_metrics_output_file() {
+    let path = std::env::temp_dir().join("cargo_script_metrics_output.json");
+    let _ = fs::remove_file(&path);
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--metrics-format", "json", "--metrics-output", path.to_str().unwrap(), "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("total_ms").not());
+
+    let metrics: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(metrics["scripts"]["build"].is_u64());
+    assert!(metrics["total_ms"].is_u64());
+}
+
+/// Tests that `--no-metrics` disables the performance summary.
+#[test]
+fn test_no_metrics() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--no-metrics", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Scripts Performance").not());
+}
+
+/// Tests that the performance table pads names with wide characters by display width.
+#[test]
+fn test_metrics_aligns_wide_names() {
+    let dir = std::env::temp_dir().join("cargo_script_metrics_wide_names");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, "[scripts]\n\"構築\" = \"echo built\"\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("NO_COLOR", "1")
+        .args(["run", "構築", "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!("Script: 構築{}  🕒", " ".repeat(21))));
+}
@@ -0,0 +1,39 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests the `multiline_bash` script defined in `Scripts.toml`.
+/// The function definition only works if newlines are preserved.
+#[test]
+fn test_multiline_bash() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "multiline_bash", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello from multiline"));
+}
+
+/// Tests the `multiline_python` script defined in `Scripts.toml`.
+/// The indented loop body only works if the script is passed verbatim.
+#[test]
+fn test_multiline_python() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "multiline_python", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("python line 1"));
+}
+
+/// Tests that dry-run shows the full multi-line body without executing it.
+#[test]
+fn test_multiline_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "multiline_bash", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("greet() {"))
+        .stdout(predicates::str::contains("    echo \"hello from $1\""))
+        .stdout(predicates::str::contains("hello from multiline").not());
+}
@@ -0,0 +1,41 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that a step running past the threshold is reported while it is still running.
+#[test]
+fn test_notify_on_long_fires() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "sleepy", "--notify-on-long", "50ms", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("[ sleepy ] is still running after 50ms"));
+}
+
+/// Tests that steps finishing before the threshold do not notify.
+#[test]
+fn test_notify_on_long_quiet_when_fast() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--notify-on-long", "10s", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("still running").not());
+}
+
+/// Tests that dry-run notes the threshold and that invalid durations are rejected.
+#[test]
+fn test_notify_on_long_dry_run_and_parse() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--dry-run", "--notify-on-long", "2m", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Notify on long: steps running longer than 120s"));
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--notify-on-long", "soon", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid duration"));
+}
@@ -0,0 +1,47 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that a failed pipeline doesn't name the failed stage without `--pipefail-report`.
+#[test]
+fn test_pipeline_without_report() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "broken_pipeline", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("pipeline stage").not());
+}
+
+/// Tests that `--pipefail-report` names the first failed stage of a failed pipeline.
+#[test]
+fn test_pipefail_report_names_stage() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "broken_pipeline", "--pipefail-report", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("pipeline stage 2 (grep) failed with status 1 (statuses: 0 1 0 1)"));
+}
+
+/// Tests that a passing pipeline still passes with `--pipefail-report`, even though a stage
+/// exits non-zero (`yes` is killed by SIGPIPE once `head` exits).
+#[test]
+fn test_pipefail_report_keeps_passing_pipeline() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "passing_pipeline", "--pipefail-report", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("y"));
+}
+
+/// Tests that shells other than bash and zsh get a warning instead of a report.
+#[test]
+fn test_pipefail_report_unsupported_shell() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "sh_pipeline", "--pipefail-report", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("pipeline statuses are only reported for bash and zsh"))
+        .stderr(predicates::str::contains("pipeline stage").not());
+}
@@ -0,0 +1,73 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// An OS other than the one the tests run on.
+fn other_os() -> &'static str {
+    if std::env::consts::OS == "windows" { "linux" } else { "windows" }
+}
+
+/// Create a project with platform-specific scripts and return the path of its scripts file.
+fn project(name: &str) -> String {
+    let scripts = format!(
+        r#"[scripts]
+per_os = {{ command = "echo base", linux = "echo on linux", macos = "echo on macos", windows = "echo on windows", info = "Per OS" }}
+fallback = {{ command = "echo base command", {other} = "echo elsewhere" }}
+other_only = {{ {other} = "echo elsewhere" }}
+"#,
+        other = other_os()
+    );
+    fixtures::scripts_file(name, &scripts)
+}
+
+/// Tests that the variant for the current OS replaces `command`.
+#[test]
+fn test_platform_variant_selected() {
+    let path = project("cargo_script_platform_selected");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "per_os", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!("on {}", std::env::consts::OS)))
+        .stdout(predicates::str::contains("base").not());
+}
+
+/// Tests that the base `command` runs when no variant matches the current OS.
+#[test]
+fn test_platform_fallback_to_command() {
+    let path = project("cargo_script_platform_fallback");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "fallback", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("base command"));
+}
+
+/// Tests that a script with only other platforms' variants fails to run.
+#[test]
+fn test_platform_missing_variant() {
+    let path = project("cargo_script_platform_missing");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "other_only", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains(format!("Script [ other_only ] has no command for {}", std::env::consts::OS)));
+}
+
+/// Tests that `show` and `validate` point out platform-specific variants.
+#[test]
+fn test_platform_show_and_validate() {
+    let path = project("cargo_script_platform_show");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Per OS [windows, macos, linux]"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ per_os ] platform-specific commands: windows, macos, linux"))
+        .stdout(predicates::str::contains("[ other_only ] has no base `command` and no variant for"));
+}
@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// Create a project whose `all` script includes two scripts writing to both streams, and
+/// return its directory.
+fn project(name: &str, settings: &str) -> std::path::PathBuf {
+    let scripts = format!(
+        r#"{}[scripts]
+frontend = "echo built frontend; echo frontend warning >&2"
+backend = "echo built backend; echo second line"
+all = {{ include = ["frontend", "backend"] }}
+"#,
+        settings
+    );
+    fixtures::project(name, &scripts)
+}
+
+/// Tests that `--prefix` marks each line with its script, on the stream it was written to.
+#[test]
+fn test_prefix_flag() {
+    let dir = project("cargo_script_prefix_flag", "");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "all", "--prefix"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[frontend] built frontend"))
+        .stdout(predicates::str::contains("[backend] built backend\n[backend] second line"))
+        .stderr(predicates::str::contains("[frontend] frontend warning"));
+}
+
+/// Tests that `[settings] prefix_output` enables prefixes, and that output is unprefixed by default.
+#[test]
+fn test_prefix_setting() {
+    let dir = project("cargo_script_prefix_setting", "[settings]\nprefix_output = true\n\n");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "backend"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[backend] built backend"));
+
+    let dir = project("cargo_script_prefix_default", "");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "backend"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("built backend"))
+        .stdout(predicates::str::contains("[backend]").not());
+}
@@ -0,0 +1,54 @@
+use assert_cmd::Command;
+
+mod fixtures;
+
+const ENV_SCRIPTS: &str = r#"[global_env]
+LEVEL = "global"
+SHARED = "global"
+
+[profiles.prod.env]
+LEVEL = "prod"
+
+[scripts]
+deploy = { command = "echo deploy", env = { SHARED = "script", API_TOKEN = "abc123", GREETING = "it's me" } }
+"#;
+
+/// Write a scripts file for `env` tests and return its path.
+fn scripts_file(name: &str) -> String {
+    fixtures::scripts_file(name, ENV_SCRIPTS)
+}
+
+/// Tests that `env` prints the layered variables sorted by name, masking secrets.
+#[test]
+fn test_env_layering() {
+    let path = scripts_file("cargo_script_print_env_layering");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["env", "deploy", "--profile", "prod", "--env", "EXTRA=1", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout("API_TOKEN=***\nEXTRA=1\nGREETING=it's me\nLEVEL=prod\nSHARED=script\n");
+}
+
+/// Tests that `--format export` prints quoted `export` lines and `--show-secrets` reveals values.
+#[test]
+fn test_env_export() {
+    let path = scripts_file("cargo_script_print_env_export");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["env", "deploy", "--format", "export", "--show-secrets", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("export API_TOKEN=abc123\n"))
+        .stdout(predicates::str::contains("export GREETING='it'\\''s me'\n"))
+        .stdout(predicates::str::contains("export LEVEL=global\n"));
+}
+
+/// Tests that `env` fails for an unknown script.
+#[test]
+fn test_env_unknown_script() {
+    let path = scripts_file("cargo_script_print_env_unknown");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["env", "missing", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("missing"));
+}
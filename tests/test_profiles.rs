@@ -0,0 +1,96 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes a scripts file with `dev` and `prod` profiles and returns its path.
+fn setup_scripts(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cargo_script_profiles_{}", name));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(
+        &path,
+        r#"[global_env]
+TARGET = "local"
+LEVEL = "global"
+
+[profiles.dev.env]
+TARGET = "dev-server"
+
+[profiles.prod.env]
+TARGET = "prod-server"
+LEVEL = "profile"
+
+[scripts]
+deploy = "echo target=$TARGET level=$LEVEL"
+pinned = { command = "echo level=$LEVEL", env = { LEVEL = "script" } }
+"#,
+    )
+    .unwrap();
+    path
+}
+
+/// Tests that the selected profile's variables apply on top of `global_env`.
+#[test]
+fn test_profile_overrides_global_env() {
+    let path = setup_scripts("global");
+    let path = path.display().to_string();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("target=local level=global"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--profile", "prod", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("target=prod-server level=profile"));
+}
+
+/// Tests that a script's `env` and `--env` overrides win over the profile.
+#[test]
+fn test_profile_below_script_and_cli_env() {
+    let path = setup_scripts("precedence");
+    let path = path.display().to_string();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "pinned", "--profile", "prod", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("level=script"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--profile", "dev", "--env", "TARGET=cli", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("target=cli"));
+}
+
+/// Tests that an unknown profile fails before running, listing the defined profiles.
+#[test]
+fn test_unknown_profile() {
+    let path = setup_scripts("unknown");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--profile", "staging", "--scripts-path", &path.display().to_string()])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("target=").not())
+        .stderr(predicates::str::contains("Profile not found: [ staging ]. Available profiles: dev, prod"));
+}
+
+/// Tests that dry-run shows the active profile and its variables.
+#[test]
+fn test_dry_run_shows_profile() {
+    let path = setup_scripts("dry_run");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--dry-run", "--profile", "dev", "--scripts-path", &path.display().to_string()])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Profile: dev"))
+        .stdout(predicates::str::contains("TARGET = dev-server"));
+}
@@ -0,0 +1,30 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--quiet-success` hides messages and output of a successful script.
+/// Only the performance summary should be printed.
+#[test]
+fn test_quiet_success_hides_output() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--quiet-success", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Running script").not())
+        .stdout(predicates::str::contains("Scripts Performance"));
+}
+
+/// Tests that `--quiet-success` prints the failing script's messages and output.
+/// Output of the successful `build` step stays hidden.
+#[test]
+fn test_quiet_success_shows_failure() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "chain_with_failure", "--quiet-success", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("about to fail"))
+        .stdout(predicates::str::contains("Running script: [ build ]").not())
+        .stderr(predicates::str::contains("fail_script"));
+}
@@ -0,0 +1,42 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `requirements` aggregates tools, toolchains, and groups as JSON without probing.
+#[test]
+fn test_requirements_json() {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["requirements", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let requirements: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let requirements = requirements.as_array().unwrap();
+
+    let docker = requirements.iter().find(|r| r["tool"]["name"] == "docker").unwrap();
+    assert_eq!(docker["tool"]["version"], ">=19.03");
+    assert_eq!(docker["required_by"], serde_json::json!(["deploy"]));
+
+    let stable = requirements.iter().find(|r| r["toolchain"] == "stable").unwrap();
+    assert_eq!(stable["required_by"], serde_json::json!(["deploy", "inline_script", "test_toolchain"]));
+
+    let group = requirements.iter().find(|r| r["any_of"].is_array()).unwrap();
+    assert_eq!(group["any_of"][0]["name"], "cargo-script-missing-a");
+
+    // `all_of` groups are split into their tools, and tools are deduplicated.
+    let shells: Vec<_> = requirements.iter().filter(|r| r["tool"]["name"] == "sh").collect();
+    assert_eq!(shells.len(), 1);
+    assert_eq!(shells[0]["required_by"], serde_json::json!(["requires_all_fail", "traced_requires"]));
+}
+
+/// Tests the table output of `requirements`.
+#[test]
+fn test_requirements_table() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["requirements", "--format", "table", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("toolchain stable"))
+        .stdout(predicates::str::contains("any of (cargo-script-missing-a | cargo-script-missing-b)"));
+}
@@ -0,0 +1,48 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that an `any_of` group passes when one alternative is available.
+#[test]
+fn test_requires_any_of_passes() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "requires_any", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("any passed"));
+}
+
+/// Tests that a failing `any_of` group lists every alternative that was tried.
+#[test]
+fn test_requires_any_of_fails() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "requires_any_fail", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("None of the alternatives any of (cargo-script-missing-a | cargo-script-missing-b) is available"))
+        .stderr(predicates::str::contains("Failed to execute cargo-script-missing-a"))
+        .stderr(predicates::str::contains("Failed to execute cargo-script-missing-b"));
+}
+
+/// Tests that an `all_of` group fails when any listed tool is missing.
+#[test]
+fn test_requires_all_of_fails() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "requires_all_fail", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to execute cargo-script-missing-c"))
+        .stdout(predicates::str::contains("never").not());
+}
+
+/// Tests that dry-run renders requirement groups.
+#[test]
+fn test_requires_groups_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "requires_any", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Requires: any of (cargo-script-missing-tool | sh)"));
+}
@@ -0,0 +1,71 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// Scripts whose `flaky` script fails until its third attempt.
+const SCRIPTS: &str = r#"[scripts]
+flaky = { command = "n=$(cat count 2>/dev/null || echo 0); n=$((n+1)); echo $n > count; [ $n -ge 3 ]", retries = 2, retry_delay = 0 }
+"#;
+
+/// Tests that a failing command is retried until it succeeds, without reporting the attempts.
+#[test]
+fn test_retries_until_success() {
+    let dir = fixtures::project("cargo_script_retries_success", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "flaky"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Retry").not());
+}
+
+/// Tests that `--verbose` logs each failed attempt.
+#[test]
+fn test_retries_verbose() {
+    let dir = fixtures::project("cargo_script_retries_verbose", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "flaky", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("attempt 1 of 3 failed"))
+        .stdout(predicates::str::contains("attempt 2 of 3 failed"))
+        .stdout(predicates::str::contains("attempt 3 of 3").not());
+}
+
+/// Tests that `--retries` overrides the script's setting.
+#[test]
+fn test_retries_flag_overrides_script() {
+    let dir = fixtures::project("cargo_script_retries_override", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "flaky", "--retries", "0"])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("Retry").not());
+}
+
+/// Tests that the error of exhausted retries reports the number of attempts.
+#[test]
+fn test_retries_exhausted() {
+    let dir = fixtures::project("cargo_script_retries_exhausted", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "flaky", "--retries", "1"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("after 2 attempts"));
+}
+
+/// Tests that dry-run shows the retries that apply.
+#[test]
+fn test_retries_dry_run() {
+    let dir = fixtures::project("cargo_script_retries_dry_run", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "flaky", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Retries: 2 (0s apart)"));
+}
@@ -0,0 +1,20 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that dry-run describes the sandbox settings of a script and whether they are active.
+#[test]
+fn test_sandbox_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "sandboxed", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Sandbox: network off, read-only / (inactive without --sandbox)"));
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "sandboxed", "--dry-run", "--sandbox", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Sandbox: network off, read-only /\n"));
+}
@@ -0,0 +1,21 @@
+use assert_cmd::Command;
+
+/// Tests that `schema` prints a JSON Schema covering the scripts file structure.
+#[test]
+fn test_schema() {
+    let output = Command::cargo_bin("cargo-script").unwrap().arg("schema").output().unwrap();
+    assert!(output.status.success());
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).expect("schema is valid JSON");
+
+    assert_eq!(schema["title"], "Scripts");
+    assert!(schema["properties"]["scripts"].is_object());
+    assert!(schema["properties"]["global_env"].is_object());
+
+    let variants = schema["definitions"]["Script"]["anyOf"].as_array().unwrap();
+    assert_eq!(variants.len(), 3);
+    assert_eq!(variants[0]["type"], "string");
+    for field in ["command", "info", "env", "include", "interpreter", "requires", "toolchain", "sandbox"] {
+        assert!(variants[2]["properties"].get(field).is_some(), "inline scripts accept `{}`", field);
+    }
+    assert!(variants[1]["required"].as_array().unwrap().contains(&"script".into()));
+}
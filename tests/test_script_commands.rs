@@ -1,3 +1,5 @@
+#![allow(clippy::needless_borrows_for_generic_args)]
+
 use assert_cmd::Command;
 use std::fs;
 use std::process::Command as ProcessCommand;
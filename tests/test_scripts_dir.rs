@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use std::fs;
+
+/// Create a scripts directory holding the given files and return its path.
+fn scripts_dir(name: &str, files: &[(&str, &str)]) -> String {
+    let dir = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (file, content) in files {
+        fs::write(dir.join(file), content).unwrap();
+    }
+    dir.display().to_string()
+}
+
+const BACKEND: &str = r#"[global_env]
+STAGE = "backend"
+
+[scripts]
+api = { command = "echo \"api $STAGE\"", info = "Backend API" }
+"#;
+
+const FRONTEND: &str = r#"[global_env]
+STAGE = "frontend"
+ONLY_FRONTEND = "yes"
+
+[scripts]
+web = { command = "echo \"web $ONLY_FRONTEND\"", info = "Frontend build" }
+all = { include = ["api", "web"] }
+"#;
+
+/// Tests that every `*.toml` file of a directory is merged, with the first file winning for `global_env`.
+#[test]
+fn test_scripts_dir_merged() {
+    let dir = scripts_dir("cargo_script_dir_merged", &[("10-backend.toml", BACKEND), ("20-frontend.toml", FRONTEND), ("notes.txt", "ignored")]);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "all", "--scripts-path", &dir])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("api backend"))
+        .stdout(predicates::str::contains("web yes"))
+        .stderr(predicates::str::contains("global_env.STAGE from 20-frontend.toml is already set by an earlier file"));
+}
+
+/// Tests that `show` lists the scripts of every file.
+#[test]
+fn test_scripts_dir_show() {
+    let dir = scripts_dir("cargo_script_dir_show", &[("10-backend.toml", BACKEND), ("20-frontend.toml", FRONTEND)]);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--scripts-path", &dir])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Backend API"))
+        .stdout(predicates::str::contains("Frontend build"));
+}
+
+/// Tests that a script defined in two files is an error naming both files.
+#[test]
+fn test_scripts_dir_collision() {
+    let dir = scripts_dir("cargo_script_dir_collision", &[("a.toml", BACKEND), ("b.toml", "[scripts]\napi = \"echo other\"\n")]);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--scripts-path", &dir])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Script [ api ] is defined in both a.toml and b.toml"));
+}
@@ -0,0 +1,61 @@
+use assert_cmd::Command;
+use std::fs;
+use std::path::PathBuf;
+
+/// Creates a temporary project with `Scripts.toml` and a `.env` file at its root, and returns
+/// the root and a nested subdirectory.
+fn setup_project(name: &str) -> (PathBuf, PathBuf) {
+    let root = std::env::temp_dir().join(format!("cargo_script_discovery_{}", name));
+    let nested = root.join("crates").join("core");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(root.join(".env"), "FROM_ROOT_ENV=root-env-file\n").unwrap();
+    fs::write(
+        root.join("Scripts.toml"),
+        "[scripts]\nwhere = { command = \"echo $FROM_ROOT_ENV\", env_file = \".env\" }\n",
+    )
+    .unwrap();
+    (root, nested)
+}
+
+/// Tests that `run` finds Scripts.toml in a parent directory and resolves its relative paths
+/// against that directory.
+#[test]
+fn test_discovers_parent_scripts_file() {
+    let (_, nested) = setup_project("parent");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&nested)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "where"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("root-env-file"));
+}
+
+/// Tests that a Scripts.toml in the current directory wins over one in a parent directory.
+#[test]
+fn test_nearest_scripts_file_wins() {
+    let (_, nested) = setup_project("nearest");
+    fs::write(nested.join("Scripts.toml"), "[scripts]\nwhere = \"echo nested-file\"\n").unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&nested)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "where"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("nested-file"));
+}
+
+/// Tests that an explicit `--scripts-path` is not searched for in parent directories.
+#[test]
+fn test_explicit_scripts_path_not_searched() {
+    let (_, nested) = setup_project("explicit");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&nested)
+        .args(["run", "where", "--scripts-path", "Scripts.toml"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Scripts file not found: [ Scripts.toml ]"));
+}
@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+mod fixtures;
+
+/// Creates a temporary project directory containing `Scripts.toml` and `Scripts.ci.toml`.
+fn setup_project(name: &str) -> PathBuf {
+    let dir = fixtures::project(&format!("cargo_script_{}", name), "[scripts]\nwhich = \"echo base-file\"\n");
+    fs::write(dir.join("Scripts.ci.toml"), "[scripts]\nwhich = \"echo ci-file\"\n").unwrap();
+    dir
+}
+
+/// Tests that `CARGO_SCRIPT_ENV=ci` prefers `Scripts.ci.toml` over `Scripts.toml`.
+#[test]
+fn test_scripts_env_from_env_var() {
+    let dir = setup_project("env_var");
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .env("CARGO_SCRIPT_ENV", "ci")
+        .args(["run", "which"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("ci-file"));
+}
+
+/// Tests that a missing environment-specific file falls back to `Scripts.toml`.
+#[test]
+fn test_scripts_env_fallback() {
+    let dir = setup_project("fallback");
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "which", "--scripts-env", "staging"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("base-file"));
+}
+
+/// Tests that an explicit `--scripts-path` wins over the selected environment.
+#[test]
+fn test_scripts_path_wins_over_env() {
+    let dir = setup_project("explicit");
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .args(["run", "which", "--scripts-env", "ci", "--scripts-path", "Scripts.toml"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("base-file"));
+}
+
+/// Tests that `--verbose` reports the fallback when the environment-specific file is missing.
+#[test]
+fn test_scripts_env_fallback_verbose() {
+    let dir = setup_project("fallback_verbose");
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "which", "--scripts-env", "staging", "--verbose"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Scripts.staging.toml not found, using Scripts.toml"));
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.current_dir(&dir)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "which", "--scripts-env", "ci", "--verbose"])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("not found").not());
+}
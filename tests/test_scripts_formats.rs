@@ -0,0 +1,110 @@
+use assert_cmd::Command;
+use std::fs;
+use std::path::PathBuf;
+
+/// Creates an empty temporary directory for a test.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cargo_script_formats_{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Tests that scripts defined in a YAML file run, including includes and integer exit code keys.
+#[test]
+fn test_run_yaml_scripts() {
+    let dir = temp_dir("yaml_run");
+    fs::write(
+        dir.join("Scripts.yaml"),
+        r#"global_env:
+  GREETING: hello-from-yaml
+scripts:
+  greet: echo $GREETING
+  build:
+    command: echo building
+    info: Build it
+    exit_codes:
+      2: config invalid
+  all:
+    include: [greet, build]
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "all"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello-from-yaml"))
+        .stdout(predicates::str::contains("building"));
+}
+
+/// Tests that scripts defined in a JSON file can be shown and run with `--scripts-path`.
+#[test]
+fn test_run_json_scripts() {
+    let dir = temp_dir("json_run");
+    fs::write(
+        dir.join("scripts.json"),
+        r#"{
+  "scripts": {
+    "hello": { "command": "echo hello-from-json", "info": "Say hello" }
+  }
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["show", "--scripts-path", "scripts.json"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Say hello"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "hello", "--scripts-path", "scripts.json"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello-from-json"));
+}
+
+/// Tests that a file that does not parse reports its path and format instead of panicking.
+#[test]
+fn test_invalid_yaml_reports_format() {
+    let dir = temp_dir("yaml_invalid");
+    fs::write(dir.join("Scripts.yml"), "scripts:\n  greet: [unclosed\n").unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["run", "greet"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to parse [ Scripts.yml ] as YAML"));
+}
+
+/// Tests that `init --format yaml` writes a Scripts.yaml that the other commands can load.
+#[test]
+fn test_init_yaml() {
+    let dir = temp_dir("init_yaml");
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["init", "--format", "yaml"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Scripts.yaml"));
+    assert!(dir.join("Scripts.yaml").is_file());
+    assert!(!dir.join("Scripts.toml").exists());
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env_remove("CARGO_SCRIPT_ENV")
+        .args(["show"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("release"));
+}
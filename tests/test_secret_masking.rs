@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+const SECRET_SCRIPTS: &str = r#"[settings]
+secret_patterns = ["DB_*"]
+
+[global_env]
+GITHUB_TOKEN = "ghp_supersecret"
+PLAIN_VALUE = "visible"
+
+[scripts]
+deploy = { command = "echo deploy", env = { DB_URL = "postgres://user:pass@db" } }
+"#;
+
+/// Create a project with secret-looking variables and return the path of its scripts file.
+fn project(name: &str) -> String {
+    fixtures::scripts_file(name, SECRET_SCRIPTS)
+}
+
+/// Tests that a `GITHUB_TOKEN` value is masked in dry-run output while other values are shown.
+#[test]
+fn test_dry_run_masks_token() {
+    let path = project("cargo_script_secret_masking_token");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("GITHUB_TOKEN = ***"))
+        .stdout(predicates::str::contains("PLAIN_VALUE = visible"))
+        .stdout(predicates::str::contains("ghp_supersecret").not());
+}
+
+/// Tests that `[settings] secret_patterns` adds patterns to the defaults.
+#[test]
+fn test_dry_run_masks_custom_pattern() {
+    let path = project("cargo_script_secret_masking_custom");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("DB_URL = ***"));
+}
+
+/// Tests that `--show-secrets` prints the real values.
+#[test]
+fn test_show_secrets() {
+    let path = project("cargo_script_secret_masking_reveal");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "deploy", "--dry-run", "--show-secrets", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("GITHUB_TOKEN = ghp_supersecret"))
+        .stdout(predicates::str::contains("DB_URL = postgres://user:pass@db"));
+}
+
+/// Tests that a secret value expanded into a command by `${VAR}` is masked in the dry-run command.
+#[test]
+fn test_dry_run_masks_interpolated_secret() {
+    let dir = std::env::temp_dir().join("cargo_script_secret_masking_command");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, "[global_env]\nAPI_TOKEN = \"tok_123456\"\n\n[scripts]\npush = \"curl -H 'Authorization: ${API_TOKEN}' example.com\"\n").unwrap();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "push", "--dry-run", "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("curl -H 'Authorization: ***' example.com"))
+        .stdout(predicates::str::contains("tok_123456").not());
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "push", "--dry-run", "--show-secrets", "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Authorization: tok_123456"));
+}
@@ -0,0 +1,26 @@
+use assert_cmd::Command;
+use cargo_run::commands::select::parse_env_overrides;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `run` without a script name fails with a hint when it cannot prompt.
+#[test]
+fn test_run_without_script_outside_terminal() {
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "--scripts-path", SCRIPT_TOML])
+        .write_stdin("")
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("No script name given"));
+}
+
+/// Tests the parsing of the overrides entered after picking a script.
+#[test]
+fn test_parse_env_overrides() {
+    assert_eq!(parse_env_overrides("").unwrap(), Vec::<String>::new());
+    assert_eq!(parse_env_overrides(" A=1  B=x=y ").unwrap(), vec!["A=1", "B=x=y"]);
+    assert_eq!(parse_env_overrides("EMPTY=").unwrap(), vec!["EMPTY="]);
+    assert!(parse_env_overrides("A=1 oops").unwrap_err().contains("`oops`"));
+    assert!(parse_env_overrides("=value").is_err());
+}
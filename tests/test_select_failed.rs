@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+/// Scripts with a chain whose second step fails until `fixed` exists.
+const SCRIPTS: &str = "[scripts]\nok = \"echo ok ran\"\nflaky = \"test -f fixed || exit 2; echo flaky ran\"\nboth = { include = [\"ok\", \"flaky\"] }\n";
+
+/// Tests that `--select-failed` reruns only the failed scripts until they pass.
+#[test]
+fn test_select_failed_reruns_failures() {
+    let dir = fixtures::project("cargo_script_select_failed_reruns", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap().current_dir(&dir).args(["run", "both", "--keep-going"]).assert().failure();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "--select-failed"])
+        .assert()
+        .code(2)
+        .stdout(predicates::str::contains("Rerunning failed scripts: flaky"))
+        .stdout(predicates::str::contains("ok ran").not());
+
+    fs::write(dir.join("fixed"), "").unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "--select-failed"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("flaky ran"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "--select-failed"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Nothing to rerun: the last run ([ flaky ]) succeeded"));
+}
+
+/// Tests that `--select-failed` reports when no run is recorded.
+#[test]
+fn test_select_failed_without_history() {
+    let dir = fixtures::project("cargo_script_select_failed_empty", SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "--select-failed"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Nothing to rerun: no runs are recorded"));
+}
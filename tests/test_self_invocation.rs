@@ -0,0 +1,36 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// Writes a scripts file with a script that runs itself through cargo-script and returns its path.
+fn scripts_file(name: &str) -> String {
+    fixtures::scripts_file(name, r#"[scripts]
+deploy = "echo deploying && cargo script run --quiet deploy"
+release = "cargo script run deploy-all"
+"deploy-all" = { include = ["deploy"] }
+"#)
+}
+
+/// Tests that running a script whose command runs the same script fails before starting it.
+#[test]
+fn test_run_self_invocation() {
+    let path = scripts_file("cargo_script_self_invocation_run");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "deploy", "--keep-going", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("deploying").not())
+        .stderr(predicates::str::contains("Script [ deploy ] runs `cargo script run deploy` again"));
+}
+
+/// Tests that `validate` warns about a self-invocation but not about running a different script.
+#[test]
+fn test_validate_self_invocation() {
+    let path = scripts_file("cargo_script_self_invocation_validate");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["validate", "--scripts-path", &path])
+        .assert()
+        .stdout(predicates::str::contains("[ deploy ] command runs `cargo script run deploy` again"))
+        .stdout(predicates::str::contains("[ release ] command runs").not());
+}
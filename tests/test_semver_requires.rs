@@ -0,0 +1,34 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that version constraints are evaluated as semantic versions.
+#[test]
+fn test_semver_constraints_met() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "semver_ok", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("semver ok"));
+}
+
+/// Tests that an unmet constraint reports both the required and the found version.
+#[test]
+fn test_semver_constraint_failed() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "semver_fail", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::is_match(r"Required version for cargo is \^0\.5, but found 1\.\d+\.\d+").unwrap());
+}
+
+/// Tests that a bare version is a prefix match, so `11` does not match `1.x`.
+#[test]
+fn test_semver_bare_version_prefix() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "semver_prefix_fail", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Required version for cargo is 11, but found 1."));
+}
@@ -0,0 +1,69 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+/// Create a project with the given scripts file content and return the path of its scripts file.
+fn scripts_file(name: &str, content: &str) -> String {
+    fixtures::scripts_file(name, content)
+}
+
+const DEFAULTS: &str = r#"[settings]
+default_toolchain = "cs-missing-toolchain"
+default_shell_args = ["-e"]
+
+[scripts]
+build = "build --release"
+pinned = { command = "build", toolchain = "nightly" }
+strict = { command = "false; echo after", toolchain = false }
+lenient = { command = "false; echo after", toolchain = false, shell_args = [] }
+"#;
+
+/// Tests that dry-run shows the default toolchain and shell arguments with their source.
+#[test]
+fn test_settings_defaults_dry_run() {
+    let path = scripts_file("cargo_script_settings_defaults_dry_run", DEFAULTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "build", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Toolchain: cs-missing-toolchain (settings.default_toolchain)"))
+        .stdout(predicates::str::contains("Shell args: -e (settings.default_shell_args)"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "pinned", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Toolchain: nightly (script)"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "strict", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Toolchain").not());
+}
+
+/// Tests that `default_shell_args` are passed to the shell unless a script sets its own.
+#[test]
+fn test_default_shell_args() {
+    let path = scripts_file("cargo_script_settings_defaults_shell_args", DEFAULTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "strict", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("after").not());
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "lenient", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("after"));
+}
+
+/// Tests that `validate` checks the default toolchain is installed.
+#[test]
+fn test_validate_default_toolchain() {
+    let path = scripts_file("cargo_script_settings_defaults_validate", DEFAULTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ default_toolchain ]"));
+}
@@ -0,0 +1,29 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--shell-escape-check` warns about the unquoted `$EXAMPLE_VAR` only.
+/// Quoted, single-quoted, and assignment expansions must not be reported.
+#[test]
+fn test_shell_escape_check_warns() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "unquoted_var", "--shell-escape-check", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("expands $EXAMPLE_VAR without quotes"))
+        .stderr(predicates::str::contains("$RUST_BACKTRACE").not())
+        .stderr(predicates::str::contains("$NOT_EXPANDED").not());
+}
+
+/// Tests that `--strict` turns shell escape warnings into a failure before running.
+#[test]
+fn test_shell_escape_check_strict() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "unquoted_var", "--shell-escape-check", "--strict", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Validation failed"))
+        .stdout(predicates::str::contains("Running script").not());
+}
@@ -0,0 +1,139 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+mod fixtures;
+use constants::SCRIPT_TOML;
+
+/// Tests that `show --tree` renders included scripts beneath their parent.
+#[test]
+fn test_show_tree() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["show", "--tree", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("chain_with_failure"))
+        .stdout(predicates::str::contains("└─ fail_script"));
+}
+
+/// Tests that `show --tree` marks include cycles instead of expanding them forever.
+#[test]
+fn test_show_tree_marks_cycles() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["show", "--tree", "--filter", "cycle_a", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("(cycle)"))
+        .stdout(predicates::str::contains("release").not());
+}
+
+/// Tests that `show --filter` only lists matching scripts.
+#[test]
+fn test_show_filter() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["show", "--filter", "multiline", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("multiline_bash"))
+        .stdout(predicates::str::contains("test01_env").not());
+}
+
+/// Tests that `show --format json` lists scripts with their details and without the banner.
+#[test]
+fn test_show_json() {
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--format", "json", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let scripts: serde_json::Value = serde_json::from_slice(&output.stdout).expect("Output is not valid JSON");
+    let scripts = scripts.as_array().unwrap();
+
+    let deploy = scripts.iter().find(|s| s["name"] == "deploy").unwrap();
+    assert_eq!(deploy["toolchain"], "stable");
+    assert_eq!(deploy["requires"], serde_json::json!(["docker>=19.03", "kubectl>=1.18"]));
+    assert_eq!(deploy["interpreter"], "sh");
+
+    let release = scripts.iter().find(|s| s["name"] == "release").unwrap();
+    assert_eq!(release["includes"], serde_json::json!(["i_am_shell", "i_am_shell_obj", "build"]));
+
+    let group = scripts.iter().find(|s| s["name"] == "requires_any").unwrap();
+    assert!(group["requires"][0]["any_of"].is_array());
+}
+
+/// Tests that the `script` argument cargo passes for `cargo script ...` is accepted.
+#[test]
+fn test_show_json_as_cargo_subcommand() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["script", "show", "--format", "json", "--filter", "deploy", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("["))
+        .stdout(predicates::str::contains("\"name\": \"deploy\""));
+}
+
+/// Tests that names with wide characters are padded by display width, so descriptions line up.
+#[test]
+fn test_show_aligns_wide_names() {
+    let path = fixtures::scripts_file("cargo_script_show_wide_names", "[scripts]\nbuild = { command = \"echo build\", info = \"one\" }\n\"構築\" = { command = \"echo build\", info = \"two\" }\n");
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("NO_COLOR", "1")
+        .args(["show", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("build   one"))
+        .stdout(predicates::str::contains("構築    two"));
+}
+
+/// Writes a scripts file using `description`, `desc`, and `long_info`, and returns its path.
+fn described_scripts_file(name: &str) -> String {
+    fixtures::scripts_file(
+        name,
+        "[scripts]\nbuild = { command = \"echo build\", description = \"Build the project\", long_info = \"\"\"\nCompiles every crate.\n\nRun it before lint.\n\"\"\" }\nlint = { command = \"echo lint\", desc = \"Lint the project\" }\n",
+    )
+}
+
+/// Tests that `description` and `desc` are read as `info`, and `long_info` stays out of the table.
+#[test]
+fn test_show_description_aliases() {
+    let path = described_scripts_file("cargo_script_show_description_aliases");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("NO_COLOR", "1")
+        .args(["show", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("build   Build the project"))
+        .stdout(predicates::str::contains("lint    Lint the project"))
+        .stdout(predicates::str::contains("Compiles every crate").not());
+}
+
+/// Tests that `show --verbose` prints the `long_info` beneath the script's row.
+#[test]
+fn test_show_verbose_long_info() {
+    let path = described_scripts_file("cargo_script_show_verbose_long_info");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("NO_COLOR", "1")
+        .args(["show", "--verbose", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("build   Build the project  \n        Compiles every crate.\n\n        Run it before lint.\n"));
+}
+
+/// Tests that `info` prints a script's description and long description.
+#[test]
+fn test_info_long_info() {
+    let path = described_scripts_file("cargo_script_info_long_info");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.env("NO_COLOR", "1")
+        .args(["info", "build", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Description: Build the project\n\nCompiles every crate.\n\nRun it before lint.\n"));
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["info", "missing", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Script not found: [ missing ]"));
+}
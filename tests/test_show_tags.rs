@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+const TAGGED: &str = r#"[scripts]
+build = { command = "cargo build", info = "Build the project", tags = ["build"] }
+lint = { command = "cargo clippy", info = "Lint the code", tags = ["check", "ci"] }
+test = { command = "cargo test", info = "Run the tests", tags = ["ci"] }
+hello = "echo hello"
+"#;
+
+/// Create a project with tagged scripts and return the path of its scripts file.
+fn scripts_file(name: &str) -> String {
+    fixtures::scripts_file(name, TAGGED)
+}
+
+/// Tests that `show` groups scripts by tag, with untagged scripts last.
+#[test]
+fn test_show_groups_by_tag() {
+    let path = scripts_file("cargo_script_show_groups");
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--scripts-path", &path])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let position = |text: &str| stdout.find(text).unwrap_or_else(|| panic!("missing {}", text));
+    assert!(position("[build]") < position("[check]"));
+    assert!(position("[check]") < position("[ci]"));
+    assert!(position("[ci]") < position("[untagged]"));
+    assert!(position("[untagged]") < position("hello"));
+    assert_eq!(stdout.matches("lint").count(), 2);
+}
+
+/// Tests that `--tag` lists only the scripts carrying the tag, and composes with `--filter`.
+#[test]
+fn test_show_tag_filter() {
+    let path = scripts_file("cargo_script_show_tag_filter");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--tag", "ci", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("lint"))
+        .stdout(predicates::str::contains("test"))
+        .stdout(predicates::str::contains("build").not())
+        .stdout(predicates::str::contains("[ci]").not());
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--tag", "ci", "--filter", "tests", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Run the tests"))
+        .stdout(predicates::str::contains("lint").not());
+}
+
+/// Tests that the JSON output includes the tags.
+#[test]
+fn test_show_json_tags() {
+    let path = scripts_file("cargo_script_show_json_tags");
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["show", "--format", "json", "--tag", "check", "--scripts-path", &path])
+        .output()
+        .unwrap();
+    let scripts: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(scripts.as_array().unwrap().len(), 1);
+    assert_eq!(scripts[0]["name"], "lint");
+    assert_eq!(scripts[0]["tags"], serde_json::json!(["check", "ci"]));
+}
@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes a scripts file with a script that sleeps and one that returns at once.
+fn setup_scripts(name: &str, settings: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cargo_script_slow_{}.toml", name));
+    let content = format!("{}\n[scripts]\nnap = \"sleep 0.3\"\nquick = \"true\"\nboth = {{ include = [\"nap\", \"quick\"] }}\n", settings);
+    fs::write(&path, content).unwrap();
+    path
+}
+
+/// Tests that `--warn-slow` marks only the scripts over the threshold in the performance summary.
+#[test]
+fn test_warn_slow_marks_slow_scripts() {
+    let path = setup_scripts("warn", "");
+    let output = Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "both", "--warn-slow", "0.1", "--scripts-path", path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let nap = stdout.lines().find(|line| line.contains("Script: nap")).unwrap();
+    let quick = stdout.lines().find(|line| line.contains("Script: quick")).unwrap();
+    assert!(nap.contains("⚠ slow"));
+    assert!(!quick.contains("⚠ slow"));
+}
+
+/// Tests that `--fail-slow` fails the run when `[settings] slow_threshold` is exceeded.
+#[test]
+fn test_fail_slow_with_settings_threshold() {
+    let path = setup_scripts("fail", "[settings]\nslow_threshold = 0.1\n");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "both", "--fail-slow", "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 script(s) ran longer than the slow threshold of 100.00ms: nap"));
+}
+
+/// Tests that `--fail-slow` passes when no script is over the threshold.
+#[test]
+fn test_fail_slow_passes_fast_scripts() {
+    let path = setup_scripts("pass", "");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "quick", "--warn-slow", "10", "--fail-slow", "--scripts-path", path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("⚠ slow").not());
+}
@@ -0,0 +1,30 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--print-stage-timings` prints each step's time right after it finishes,
+/// before the final performance table.
+#[test]
+fn test_print_stage_timings() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    let output = cmd.args(["run", "release_info", "--print-stage-timings", "--scripts-path", SCRIPT_TOML])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let build_timing = stdout.find("build (").expect("timing line for build");
+    let summary = stdout.find("Scripts Performance").expect("summary table");
+    assert!(build_timing < summary);
+}
+
+/// Tests that stage timings are suppressed together with the rest of the output by `--quiet-success`.
+#[test]
+fn test_print_stage_timings_quiet_success() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--print-stage-timings", "--quiet-success", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("build (").not());
+}
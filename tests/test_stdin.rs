@@ -0,0 +1,90 @@
+use assert_cmd::Command;
+use std::fs;
+
+mod fixtures;
+
+/// Create a project whose scripts read their input, and return its directory.
+fn project(name: &str) -> std::path::PathBuf {
+    let dir = fixtures::project(name, r#"[scripts]
+upper = "tr a-z A-Z"
+greeting = { command = "tr a-z A-Z", stdin = "hello from the scripts file" }
+fixture = { command = "wc -l | tr -d ' '", stdin = "@fixtures/input.txt" }
+missing = { command = "cat", stdin = "@fixtures/missing.txt" }
+"#);
+    fs::create_dir_all(dir.join("fixtures")).unwrap();
+    fs::write(dir.join("fixtures").join("input.txt"), "one\ntwo\nthree\n").unwrap();
+    dir
+}
+
+/// Tests that input piped into cargo-script reaches the command.
+#[test]
+fn test_stdin_inherited() {
+    let dir = project("cargo_script_stdin_inherited");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "upper"])
+        .write_stdin("piped text")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("PIPED TEXT"));
+}
+
+/// Tests that `stdin` feeds text, or a file with `@`, to the command.
+#[test]
+fn test_stdin_setting() {
+    let dir = project("cargo_script_stdin_setting");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "greeting"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("HELLO FROM THE SCRIPTS FILE"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "fixture"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("3"));
+}
+
+/// Tests that a missing input file fails the run, and that dry-run shows the input.
+#[test]
+fn test_stdin_file_errors_and_dry_run() {
+    let dir = project("cargo_script_stdin_missing");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Failed to read stdin file fixtures/missing.txt"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .args(["run", "fixture", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Stdin:"))
+        .stdout(predicates::str::contains("input.txt"));
+}
+
+/// Tests that a `stdin` file is resolved against `--working-set`, in runs and in dry-run.
+#[test]
+fn test_stdin_file_working_set() {
+    let dir = project("cargo_script_stdin_working_set");
+    let scripts_path = dir.join("Scripts.toml").display().to_string();
+    let working_set = std::env::temp_dir().join("cargo_script_stdin_working_set_tree");
+    fs::create_dir_all(working_set.join("fixtures")).unwrap();
+    fs::write(working_set.join("fixtures").join("input.txt"), "one\ntwo\nthree\nfour\nfive\n").unwrap();
+    let working_set = fs::canonicalize(working_set).unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "fixture", "--working-set", &working_set.display().to_string(), "--scripts-path", &scripts_path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("5"));
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "fixture", "--dry-run", "--working-set", &working_set.display().to_string(), "--scripts-path", &scripts_path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(working_set.join("fixtures").join("input.txt").display().to_string()));
+}
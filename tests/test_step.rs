@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--step` runs only the selected step of the chain.
+#[test]
+fn test_step_runs_single_step() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "release", "--step", "3", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Running script: [ build ]"))
+        .stdout(predicates::str::contains("i_am_shell").not());
+}
+
+/// Tests that an out-of-range step reports the valid range.
+#[test]
+fn test_step_out_of_range() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "release", "--step", "9", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Step 9 is out of range for [ release ]: valid steps are 1-4"));
+}
+
+/// Tests that dry-run with `--step` previews only the selected step.
+#[test]
+fn test_step_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "release", "--step", "1", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Step: 1 [ i_am_shell ]"))
+        .stdout(predicates::str::contains("[ build ]").not());
+}
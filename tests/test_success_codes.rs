@@ -0,0 +1,38 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that a per-script `success_codes` list accepts non-zero exit codes.
+#[test]
+fn test_success_codes_per_script() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "grep_no_match", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success();
+}
+
+/// Tests that `--ignore-exit-codes` accepts the listed codes and nothing else.
+#[test]
+fn test_ignore_exit_codes() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "fail_script", "--ignore-exit-codes", "1,3", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "fail_script", "--ignore-exit-codes", "1", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .code(3);
+}
+
+/// Tests that dry-run shows the accepted exit codes.
+#[test]
+fn test_success_codes_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "grep_no_match", "--dry-run", "--ignore-exit-codes", "2", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Success codes: 0, 1, 2"));
+}
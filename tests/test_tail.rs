@@ -0,0 +1,40 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+const TAIL_SCRIPTS: &str = r#"[scripts]
+server = "sleep 0.3; echo first line >> server.log; sleep 0.3; printf 'second line\nno newline' >> server.log; sleep 0.3"
+"#;
+
+/// Tests that `--tail` prints lines appended to the file, waiting for it to be created.
+#[test]
+fn test_tail_follows_new_file() {
+    let dir = std::env::temp_dir().join("cargo_script_tail_new");
+    fs::create_dir_all(&dir).unwrap();
+    let _ = fs::remove_file(dir.join("server.log"));
+    fs::write(dir.join("Scripts.toml"), TAIL_SCRIPTS).unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "server", "--tail", "server.log"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("first line\n"))
+        .stdout(predicates::str::contains("second line\n"))
+        .stdout(predicates::str::contains("no newline\n"));
+}
+
+/// Tests that content present before the run is not replayed.
+#[test]
+fn test_tail_skips_existing_content() {
+    let dir = std::env::temp_dir().join("cargo_script_tail_existing");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("server.log"), "old line\n").unwrap();
+    fs::write(dir.join("Scripts.toml"), TAIL_SCRIPTS).unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "server", "--tail", "server.log"])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("first line\n"))
+        .stdout(predicates::str::contains("old line").not());
+}
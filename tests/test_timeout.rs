@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use std::time::{Duration, Instant};
+
+mod constants;
+mod fixtures;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--timeout` kills a hanging step and its children instead of waiting for them.
+#[test]
+fn test_timeout_kills_process_group() {
+    let start = Instant::now();
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "hangs", "--timeout", "1", "--quiet-success", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Script [ hangs ] timed out after 1s"));
+    assert!(start.elapsed() < Duration::from_secs(15));
+}
+
+/// Tests that a script's own `timeout` field applies without the flag.
+#[test]
+fn test_timeout_script_field() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "slow_with_timeout", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("timed out after 1s"));
+}
+
+/// Tests that `--timeout` overrides the script's own `timeout` field.
+#[test]
+fn test_timeout_flag_overrides_field() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "slow_with_timeout", "--timeout", "20", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("finished"));
+}
+
+/// Run cargo-script with `args` inside a pseudo-terminal through `script`, feeding it `input`.
+#[cfg(target_os = "linux")]
+fn run_in_pty(dir: &std::path::Path, args: &str, input: &str) -> std::process::Output {
+    use std::{io::Write, process::Stdio};
+    let bin = assert_cmd::cargo::cargo_bin("cargo-script");
+    let mut child = std::process::Command::new("script")
+        .current_dir(dir)
+        .args(["-qec", &format!("'{}' {}", bin.display(), args), "/dev/null"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Tests that in a terminal, a timed out step's whole process group is killed, so the run
+/// doesn't wait for the step's children, while steps with a timeout can still read input.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_timeout_in_terminal() {
+    if std::process::Command::new("script").arg("--version").output().is_err() {
+        return;
+    }
+    let dir = fixtures::project("cargo_script_timeout_terminal", r#"[scripts]
+hang = { command = "sleep 8; echo done", timeout = 1 }
+ask = { command = "read answer; echo got $answer", timeout = 5 }
+"#);
+
+    let start = Instant::now();
+    let output = run_in_pty(&dir, "run hang --quiet-success", "");
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("timed out after 1s"));
+
+    let output = run_in_pty(&dir, "run ask", "hello\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("got hello"));
+}
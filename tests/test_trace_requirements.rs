@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--trace-requirements` prints each probe and its result to stderr.
+#[test]
+fn test_trace_requirements_pass() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "traced_requires", "--trace-requirements", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("[ traced_requires ] sh -> `sh` -> pass"))
+        .stdout(predicates::str::contains("traced"));
+}
+
+/// Tests that a failing version probe is traced with its output before the run fails.
+#[test]
+fn test_trace_requirements_fail() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "traced_requires_fail", "--trace-requirements", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("cargo 0.0.0-missing -> `cargo --version` -> fail"))
+        .stderr(predicates::str::contains("    cargo "));
+}
+
+/// Tests that requirement probes are not traced without the flag.
+#[test]
+fn test_trace_requirements_off() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "traced_requires", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("Requirement:").not());
+}
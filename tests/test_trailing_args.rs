@@ -0,0 +1,47 @@
+use assert_cmd::Command;
+
+mod constants;
+mod fixtures;
+use constants::SCRIPT_TOML;
+
+/// Tests that arguments after `--` are appended to the command, keeping spaces and quotes intact.
+#[test]
+fn test_trailing_args_forwarded() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "print_args", "--scripts-path", SCRIPT_TOML, "--", "--nocapture", "two words", "it's"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[--nocapture]\n[two words]\n[it's]\n"));
+}
+
+/// Tests that language interpreters receive the arguments as their own argv.
+#[test]
+fn test_trailing_args_language_interpreter() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "print_args_python", "--scripts-path", SCRIPT_TOML, "--", "a b", "c"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("['a b', 'c']"));
+}
+
+/// Tests that dry-run shows the command with the arguments appended.
+#[test]
+fn test_trailing_args_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "print_args", "--dry-run", "--scripts-path", SCRIPT_TOML, "--", "two words"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("printf '[%s]\\n' 'two words'"));
+}
+
+/// Tests that a command runs exactly as written when no arguments are forwarded, keeping the
+/// trailing whitespace and blank lines of a heredoc that ends the command.
+#[test]
+fn test_no_trailing_args_keeps_command() {
+    let path = fixtures::scripts_file("cargo_script_no_trailing_args", "[scripts]\nnotes = \"cat <<EOF\\nlast line  \\n\\n\"\n");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "notes", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("last line  \n\n"));
+}
@@ -0,0 +1,28 @@
+use assert_cmd::Command;
+use std::fs;
+
+/// Tests that integer, boolean, and float env values are coerced to strings,
+/// in both `global_env` and script-specific `env` tables.
+#[test]
+fn test_typed_env_values() {
+    let dir = std::env::temp_dir().join("cargo_script_typed_env");
+    fs::create_dir_all(&dir).unwrap();
+    let scripts_path = dir.join("Scripts.toml");
+    fs::write(
+        &scripts_path,
+        r#"[global_env]
+WORKERS = 4
+NAME = "plain string"
+
+[scripts]
+typed = { command = "echo \"$WORKERS|$NAME|$PORT|$DEBUG|$RATIO|$SCALE\"", env = { PORT = 8080, DEBUG = true, RATIO = 0.25, SCALE = 2.0 } }
+"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "typed", "--scripts-path", scripts_path.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("4|plain string|8080|true|0.25|2.0"));
+}
@@ -0,0 +1,45 @@
+use assert_cmd::Command;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that a script's `umask` is set for the command it runs.
+#[cfg(unix)]
+#[test]
+fn test_umask_applied() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "with_umask", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("0077"));
+}
+
+/// Tests that a umask that is not octal fails the run.
+#[test]
+fn test_umask_invalid() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "bad_umask", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("invalid umask `089`"));
+}
+
+/// Tests that dry-run shows the requested umask.
+#[test]
+fn test_umask_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "with_umask", "--dry-run", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(": 077"));
+}
+
+/// Tests that `validate` reports a umask that is not octal.
+#[test]
+fn test_umask_validate() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["validate", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ bad_umask ] invalid umask `089`"));
+}
@@ -0,0 +1,25 @@
+use assert_cmd::Command;
+
+mod fixtures;
+
+/// Tests that a misspelled field fails with the closest accepted field as a suggestion.
+#[test]
+fn test_unknown_field_suggestion() {
+    let path = fixtures::named_scripts_file("cargo_script_unknown_field", "Scripts.toml", "[scripts]\nbuild = { comand = \"cargo build\" }\n");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Script [ build ] has an unknown field `comand`. Did you mean `command`?"));
+}
+
+/// Tests that a field unlike any accepted one points to the schema instead of guessing.
+#[test]
+fn test_unknown_field_without_suggestion() {
+    let path = fixtures::named_scripts_file("cargo_script_unknown_field_far", "Scripts.yaml", "scripts:\n  build:\n    command: cargo build\n    frobnicate: true\n");
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["show", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Script [ build ] has an unknown field `frobnicate`. Run `cargo script schema` to see the accepted fields."));
+}
@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+
+mod fixtures;
+
+/// Create a project with a fake shell that reports the command it was given, and return its
+/// directory and the shell's path.
+fn project(name: &str, settings: &str) -> (std::path::PathBuf, String) {
+    let dir = fixtures::project(name, &format!("{}[scripts]\ngreet = \"echo hello\"\n", settings));
+    let shell = dir.join("fake-shell");
+    fs::write(&shell, "#!/bin/sh\necho \"fake shell ran: $2\"\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&shell, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    (dir, shell.display().to_string())
+}
+
+/// Tests that `--use-shell-env` runs commands with `$SHELL`, and that it is opt-in.
+#[cfg(unix)]
+#[test]
+fn test_use_shell_env_flag() {
+    let (dir, shell) = project("cargo_script_use_shell_env_flag", "");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("SHELL", &shell)
+        .args(["run", "greet", "--use-shell-env"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("fake shell ran: echo hello"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("SHELL", &shell)
+        .args(["run", "greet"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello"))
+        .stdout(predicates::str::contains("fake shell").not());
+}
+
+/// Tests that `[settings] use_shell_env` enables the shell, and that dry-run says why.
+#[cfg(unix)]
+#[test]
+fn test_use_shell_env_setting() {
+    let (dir, shell) = project("cargo_script_use_shell_env_setting", "[settings]\nuse_shell_env = true\n\n");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("SHELL", &shell)
+        .args(["run", "greet"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("fake shell ran: echo hello"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("SHELL", &shell)
+        .args(["run", "greet", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(format!("Interpreter: {} (from $SHELL, settings.use_shell_env)", shell)));
+}
+
+/// Tests that a missing `$SHELL` is warned about and the default shell is used instead.
+#[cfg(unix)]
+#[test]
+fn test_use_shell_env_missing_shell() {
+    let (dir, _) = project("cargo_script_use_shell_env_missing", "");
+    Command::cargo_bin("cargo-script").unwrap()
+        .current_dir(&dir)
+        .env("SHELL", "/nonexistent/shell")
+        .args(["run", "greet", "--use-shell-env"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("hello"))
+        .stderr(predicates::str::contains("$SHELL [ /nonexistent/shell ] was not found, falling back to sh"));
+}
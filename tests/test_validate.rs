@@ -0,0 +1,162 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod fixtures;
+
+const VALIDATE_SCRIPTS: &str = r##"[scripts]
+clean = "echo clean"
+conflict = { interpreter = "python3", command = "#!/bin/bash\necho hi" }
+agrees = { interpreter = "python3", command = "#!/usr/bin/env python3\nprint('hi')" }
+looks_python = { command = "import sys\nprint(sys.argv)" }
+looks_node = { command = "console.log('hi')" }
+"##;
+
+/// Write a scripts file for validation tests and return its path.
+fn scripts_file(name: &str, content: &str) -> String {
+    fixtures::scripts_file(name, content)
+}
+
+/// Tests that `--check-shebang` flags conflicting shebangs and code sent to the default shell.
+#[test]
+fn test_validate_check_shebang() {
+    let path = scripts_file("cargo_script_validate_shebang", VALIDATE_SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--check-shebang", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ conflict ] command starts with `#!/bin/bash` but interpreter is `python3`"))
+        .stdout(predicates::str::contains("[ looks_python ] command looks like Python code"))
+        .stdout(predicates::str::contains("[ looks_node ] command looks like Node code"))
+        .stdout(predicates::str::contains("[ agrees ]").not())
+        .stdout(predicates::str::contains("[ clean ]").not());
+}
+
+/// Tests that shebang checks only run with `--check-shebang`, and that `--strict` fails on problems.
+#[test]
+fn test_validate_strict_and_defaults() {
+    let path = scripts_file("cargo_script_validate_strict", VALIDATE_SCRIPTS);
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("No problems found"));
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--check-shebang", "--strict", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Validation failed: 3 problem(s) found"));
+}
+
+/// Tests that include chains are checked for missing scripts and cycles.
+#[test]
+fn test_validate_includes() {
+    let path = scripts_file(
+        "cargo_script_validate_includes",
+        "[scripts]\nbroken = { include = [\"missing\"] }\nloop_a = { include = [\"loop_b\"] }\nloop_b = { include = [\"loop_a\"] }\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ broken ] Script not found: [ missing ]"))
+        .stdout(predicates::str::contains("[ loop_a ] Circular include detected"));
+}
+
+/// Tests that `--strict-names` reports names breaking the default convention as errors.
+#[test]
+fn test_validate_strict_names() {
+    let path = scripts_file(
+        "cargo_script_validate_strict_names",
+        "[scripts]\nbuild = \"echo build\"\n\"Build Docs\" = \"echo docs\"\nrelease_notes = \"echo notes\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success();
+
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--strict-names", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("[ Build Docs ] name does not match the pattern `^[a-z][a-z0-9:_-]*$`"))
+        .stdout(predicates::str::contains("[ release_notes ]").not())
+        .stderr(predicates::str::contains("1 script name(s) break the naming convention"));
+}
+
+/// Tests that `[settings] name_pattern` sets the convention, and that an invalid pattern is
+/// rejected at load.
+#[test]
+fn test_validate_name_pattern_setting() {
+    let path = scripts_file(
+        "cargo_script_validate_name_pattern",
+        "[settings]\nname_pattern = \"^[a-z-]+$\"\n\n[scripts]\nbuild = \"echo build\"\nrelease_notes = \"echo notes\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("[ release_notes ] name does not match the pattern `^[a-z-]+$`"));
+
+    let path = scripts_file(
+        "cargo_script_validate_bad_name_pattern",
+        "[settings]\nname_pattern = \"^[a-z\"\n\n[scripts]\nbuild = \"echo build\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "build", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Invalid [settings] name_pattern [ ^[a-z ]"));
+}
+
+/// Tests that script names reusing a command name, such as `run` or `show`, are warned about.
+#[test]
+fn test_validate_reserved_names() {
+    let path = scripts_file(
+        "cargo_script_validate_reserved",
+        "[scripts]\nrun = \"echo run\"\nshow = \"echo show\"\nbuild = \"echo build\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ run ] name collides with the `run` command"))
+        .stdout(predicates::str::contains("use `cargo script run show`"))
+        .stdout(predicates::str::contains("[ build ]").not());
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--strict", "--scripts-path", &path])
+        .assert()
+        .failure();
+}
+
+/// Tests that an empty command fails validation and a script with nothing to run is warned about.
+#[test]
+fn test_validate_empty_command() {
+    let path = scripts_file(
+        "cargo_script_validate_empty_command",
+        "[scripts]\nblank = { command = \"   \" }\nnothing = { info = \"does nothing\" }\nbuild = \"echo build\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stdout(predicates::str::contains("[ blank ] command is empty"))
+        .stdout(predicates::str::contains("[ nothing ] has neither a `command` nor an `include`"))
+        .stdout(predicates::str::contains("[ build ]").not())
+        .stderr(predicates::str::contains("1 script(s) have an empty command"));
+}
+
+/// Tests that an interpreter missing from PATH is warned about.
+#[test]
+fn test_validate_missing_interpreter() {
+    let path = scripts_file(
+        "cargo_script_validate_missing_interpreter",
+        "[scripts]\nbogus = { interpreter = \"cargo-script-no-such-shell\", command = \"echo hi\" }\nbuild = \"echo build\"\n",
+    );
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["validate", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("[ bogus ] interpreter `cargo-script-no-such-shell` was not found on PATH"))
+        .stdout(predicates::str::contains("[ build ]").not());
+}
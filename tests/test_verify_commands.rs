@@ -0,0 +1,37 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--verify-commands` warns about a missing program but not about builtins.
+#[test]
+fn test_verify_commands_warns() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "typo_command", "--dry-run", "--verify-commands", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stderr(predicates::str::contains("[ typo_command ] runs `definitely-not-a-real-program`, which was not found on PATH."))
+        .stderr(predicates::str::contains("`cd`").not())
+        .stderr(predicates::str::contains("`echo`").not());
+}
+
+/// Tests that `--strict` turns missing programs into a failure.
+#[test]
+fn test_verify_commands_strict() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "typo_command", "--dry-run", "--verify-commands", "--strict", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("1 program(s) not found by --verify-commands"));
+}
+
+/// Tests that `--verify-commands` needs `--dry-run`.
+#[test]
+fn test_verify_commands_requires_dry_run() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "typo_command", "--verify-commands", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("--dry-run"));
+}
@@ -0,0 +1,22 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::{fs, time::Duration};
+
+/// Tests that `watch` runs the script right away with a header, and does not clear the screen
+/// when output is not a terminal.
+#[test]
+fn test_watch_initial_run() {
+    let dir = std::env::temp_dir().join("cargo_script_watch_initial");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Scripts.toml");
+    fs::write(&path, "[scripts]\nhello = \"echo hello from watch\"\n").unwrap();
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["watch", "hello", "--clear", "--path", &dir.display().to_string(), "--scripts-path", &path.display().to_string()])
+        .timeout(Duration::from_secs(2))
+        .assert()
+        .stdout(predicates::str::contains("Watch [ hello ]"))
+        .stdout(predicates::str::contains("(initial run)"))
+        .stdout(predicates::str::contains("hello from watch"))
+        .stdout(predicates::str::contains("Waiting for changes in"))
+        .stdout(predicates::str::contains("\x1b[2J").not());
+}
@@ -0,0 +1,52 @@
+use assert_cmd::Command;
+use std::fs;
+
+mod fixtures;
+
+const WORKDIR_SCRIPTS: &str = r#"[scripts]
+where = { command = "pwd", workdir = "frontend" }
+missing = { command = "pwd", workdir = "nowhere" }
+"#;
+
+/// Create a project with a `frontend` directory and return the path of its scripts file.
+fn project(name: &str) -> String {
+    let dir = fixtures::project(name, WORKDIR_SCRIPTS);
+    fs::create_dir_all(dir.join("frontend")).unwrap();
+    dir.join("Scripts.toml").display().to_string()
+}
+
+/// Tests that `workdir` runs the command in a directory relative to the scripts file.
+#[test]
+fn test_workdir_relative_to_scripts_file() {
+    let path = project("cargo_script_workdir_run");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "where", "--scripts-path", &path])
+        .current_dir(std::env::temp_dir())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("cargo_script_workdir_run/frontend\n"));
+}
+
+/// Tests that a missing `workdir` is reported before anything runs.
+#[test]
+fn test_workdir_missing() {
+    let path = project("cargo_script_workdir_missing");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "missing", "--scripts-path", &path])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Directory not found"))
+        .stderr(predicates::str::contains("cargo_script_workdir_missing/nowhere"));
+}
+
+/// Tests that dry-run prints the resolved working directory.
+#[test]
+fn test_workdir_dry_run() {
+    let path = project("cargo_script_workdir_dry_run");
+    Command::cargo_bin("cargo-script").unwrap()
+        .args(["run", "where", "--dry-run", "--scripts-path", &path])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Workdir: "))
+        .stdout(predicates::str::contains("cargo_script_workdir_dry_run/frontend\n"));
+}
@@ -0,0 +1,43 @@
+use assert_cmd::Command;
+use std::fs;
+
+mod constants;
+use constants::SCRIPT_TOML;
+
+/// Tests that `--working-set` makes commands run from the given directory.
+#[test]
+fn test_working_set_runs_commands_in_dir() {
+    let dir = std::env::temp_dir().join("cargo_script_working_set");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("marker.txt"), "from-working-set").unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "cat_marker", "--working-set", dir.to_str().unwrap(), "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("from-working-set"));
+}
+
+/// Tests that dry-run prints the resolved absolute working set.
+#[test]
+fn test_working_set_dry_run() {
+    let dir = std::env::temp_dir().join("cargo_script_working_set_dry");
+    fs::create_dir_all(&dir).unwrap();
+    let resolved = fs::canonicalize(&dir).unwrap();
+
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--dry-run", "--working-set", dir.to_str().unwrap(), "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(resolved.display().to_string()));
+}
+
+/// Tests that a missing working set is reported as an error.
+#[test]
+fn test_working_set_missing() {
+    let mut cmd = Command::cargo_bin("cargo-script").unwrap();
+    cmd.args(["run", "build", "--working-set", "./does-not-exist", "--scripts-path", SCRIPT_TOML])
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Directory not found"));
+}